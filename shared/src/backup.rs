@@ -0,0 +1,472 @@
+//! 备份、还原、自动备份和磁盘空间相关的类型
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use time::OffsetDateTime;
+
+/// 数据库中的一条备份记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Backup {
+    pub id: i32,
+    pub name: Option<String>,
+    pub digest: String,
+    pub size: i64,
+    /// 相对于备份根目录的路径；旧版本记录存的是当时备份根目录的绝对路径
+    pub path: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub save_time: OffsetDateTime,
+    /// 备份时从存档解析出的元信息，数据库里以 JSON 文本保存
+    #[serde(default, deserialize_with = "meta_or_json")]
+    pub more_info: Option<MetaData>,
+    pub notes: Option<String>,
+    /// 置顶的备份排在最前面，删除时需要确认，也不会被自动清理
+    pub pinned: bool,
+    /// 这个备份是从哪个备份继续玩出来的
+    pub parent_id: Option<i32>,
+    /// 所属的游戏局，同一局游戏的备份相同；识别不出时为 None
+    pub run_id: Option<String>,
+    /// 存在 backup_tags 表中，查询后单独填充
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl Backup {
+    /// 备份文件夹名 (命名方式: backup_{digest前12位})
+    pub fn dir_name(&self) -> String {
+        format!("backup_{}", &self.digest[..12])
+    }
+
+    /// 备份文件夹在磁盘上的实际位置
+    pub fn resolve_dir(&self, data_root: &Path) -> PathBuf {
+        let path = Path::new(&self.path);
+        if path.is_absolute() {
+            // 旧记录：path 是备份根目录
+            path.join(self.dir_name())
+        } else {
+            data_root.join(path)
+        }
+    }
+}
+
+/// 旧版本导出的备份包里 more_info 是 JSON 文本，两种格式都接受
+fn meta_or_json<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<MetaData>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Json(String),
+        Meta(MetaData),
+    }
+    Ok(match Option::<Stored>::deserialize(deserializer)? {
+        Some(Stored::Json(json)) => MetaData::from_json(&json),
+        Some(Stored::Meta(meta)) => Some(meta),
+        None => None,
+    })
+}
+
+/// 从存档里解析出的元信息
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MetaData {
+    pub hp: Option<f64>,
+    pub max_hp: Option<f64>,
+    pub gold: Option<i64>,
+    pub location: Option<(f64, f64)>,
+    pub orbs: Option<u32>,
+    /// 本局游戏的会话标识，同一局的多个备份相同
+    pub session: Option<String>,
+    /// 启用的 mod，按加载顺序
+    pub mods: Option<Vec<String>>,
+    /// 世界种子，来自加密的 .salakieli 文件
+    pub seed: Option<u64>,
+    /// 新游戏+ 的层数，0 表示普通模式
+    pub ng_plus: Option<u32>,
+    /// 角色所在的生物群系，由位置推断
+    #[serde(default)]
+    pub biome: Option<String>,
+}
+
+/// 主路线上各生物群系开始的深度（position.y），每段的圣山算在上面的群系里。
+/// 存档里没有记录当前群系，只能按普通模式的地图布局由位置推断
+const MAIN_PATH: [(f64, &str); 9] = [
+    (f64::MIN, "Forest"),
+    (0.0, "Mines"),
+    (1536.0, "Coal Pits"),
+    (3072.0, "Snowy Depths"),
+    (5632.0, "Hiisi Base"),
+    (7168.0, "Underground Jungle"),
+    (9216.0, "The Vault"),
+    (10752.0, "Temple of the Art"),
+    (12800.0, "The Work"),
+];
+
+/// 主路线的大致宽度，超出后两侧是其他群系，无法只靠深度判断
+const MAIN_PATH_HALF_WIDTH: f64 = 2560.0;
+
+/// 由位置推断所在的生物群系，不在主路线上时返回 None
+pub fn biome_at((x, y): (f64, f64)) -> Option<String> {
+    if x.abs() > MAIN_PATH_HALF_WIDTH {
+        return None;
+    }
+    MAIN_PATH
+        .iter()
+        .rev()
+        .find(|(top, _)| y >= *top)
+        .map(|(_, name)| name.to_string())
+}
+
+impl MetaData {
+    pub fn from_json(json: &str) -> Option<MetaData> {
+        serde_json::from_str(json).ok()
+    }
+
+    /// 按字段名取值，供搜索过滤使用
+    pub fn field(&self, name: &str) -> Option<Value> {
+        // 旧备份没有记录群系，按保存的位置推断
+        if name == "biome" && self.biome.is_none() {
+            return self.location.and_then(biome_at).map(Value::from);
+        }
+        let value = serde_json::to_value(self).ok()?;
+        match value.get(name)? {
+            Value::Null => None,
+            v => Some(v.clone()),
+        }
+    }
+
+    /// 是否满足元信息筛选条件
+    pub fn matches(&self, filter: &MetaFilter) -> bool {
+        let Some(actual) = self.field(&filter.field) else {
+            return false;
+        };
+
+        // 两边都是数字时按数值比较，否则按不区分大小写的字符串比较
+        if let (Some(a), Ok(b)) = (actual.as_f64(), filter.value.trim().parse::<f64>()) {
+            return match filter.op.as_str() {
+                ">=" => a >= b,
+                "<=" => a <= b,
+                ">" => a > b,
+                "<" => a < b,
+                "=" | "==" => a == b,
+                "!=" => a != b,
+                _ => false,
+            };
+        }
+
+        let a = match &actual {
+            Value::String(s) => s.to_lowercase(),
+            v => v.to_string().to_lowercase(),
+        };
+        let b = filter.value.trim().to_lowercase();
+        match filter.op.as_str() {
+            "=" | "==" => a == b,
+            "!=" => a != b,
+            "~" => a.contains(&b),
+            _ => false,
+        }
+    }
+}
+
+/// 同一局游戏的所有备份
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupRun {
+    pub run_id: Option<String>,
+    /// 这一局最近一次备份的时间
+    #[serde(with = "time::serde::rfc3339")]
+    pub last_save_time: OffsetDateTime,
+    /// 置顶的在前，其余按时间倒序
+    pub backups: Vec<Backup>,
+}
+
+/// 备份树上的一个节点
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupNode {
+    pub id: i32,
+    pub name: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub save_time: OffsetDateTime,
+    pub parent_id: Option<i32>,
+    pub pinned: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackupGraph {
+    pub nodes: Vec<BackupNode>,
+    /// 当前存档所在的节点
+    pub head: Option<i32>,
+}
+
+/// 还原某个子路径会对当前存档造成的改动
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScopeConflict {
+    pub subtree: String,
+    /// 两边都有但大小不同、会被覆盖的文件
+    pub overwritten: usize,
+    /// 当前存档独有、会被删除的文件
+    pub deleted: usize,
+    /// 备份独有、会被加入的文件
+    pub added: usize,
+}
+
+/// 当前 mod 列表相对备份时的变化
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModDiff {
+    /// 当前启用、备份时没有的 mod
+    pub added: Vec<String>,
+    /// 备份时启用、当前没有的 mod
+    pub removed: Vec<String>,
+    /// 两边都启用但加载顺序变了的 mod
+    pub reordered: Vec<String>,
+}
+
+impl ModDiff {
+    pub fn compare(backup: &[String], current: &[String]) -> ModDiff {
+        let added = current.iter().filter(|m| !backup.contains(m)).cloned().collect();
+        let removed = backup.iter().filter(|m| !current.contains(m)).cloned().collect();
+
+        // 只比较共同的 mod 之间的相对顺序
+        let backup_common: Vec<&String> = backup.iter().filter(|m| current.contains(m)).collect();
+        let current_common: Vec<&String> = current.iter().filter(|m| backup.contains(m)).collect();
+        let reordered = backup_common
+            .iter()
+            .zip(current_common.iter())
+            .filter(|(a, b)| a != b)
+            .map(|(a, _)| a.to_string())
+            .collect();
+
+        ModDiff { added, removed, reordered }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.reordered.is_empty()
+    }
+}
+
+/// 备份的结果，存档和已有的备份完全相同时不会新建，也不算失败
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum SaveOutcome {
+    Saved { message: String },
+    /// id 为相同的那个备份
+    Duplicate { message: String, id: i32 },
+}
+
+impl SaveOutcome {
+    pub fn message(&self) -> &str {
+        match self {
+            SaveOutcome::Saved { message } | SaveOutcome::Duplicate { message, .. } => message,
+        }
+    }
+}
+
+/// 加载备份的结果，mod 不一致时不会覆盖存档，需要带 force = true 再调用一次
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum LoadOutcome {
+    /// summary 为还原前后有变化的部分
+    Loaded { message: String, summary: Vec<ScopeConflict> },
+    ModMismatch { diff: ModDiff },
+    /// 备份已加密且没有解锁，需要带上 passphrase 再调用一次
+    PassphraseRequired,
+}
+
+/// 导入的结果，加密的文件没有口令时需要带上 path 和 passphrase 再调用一次
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ImportOutcome {
+    Imported { message: String },
+    PassphraseRequired { path: String },
+    Cancelled,
+}
+
+/// 元信息筛选条件，例如 { field: "orbs", op: ">=", value: "5" }
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetaFilter {
+    pub field: String,
+    pub op: String,
+    pub value: String,
+}
+
+/// 搜索条件，全部为空时返回所有备份
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupQuery {
+    /// 名称或备注中包含的文字
+    pub text: Option<String>,
+    pub tag: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub from: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub to: Option<OffsetDateTime>,
+    pub min_size: Option<i64>,
+    pub max_size: Option<i64>,
+    #[serde(default)]
+    pub meta: Vec<MetaFilter>,
+}
+
+impl BackupQuery {
+    pub fn is_empty(&self) -> bool {
+        *self == BackupQuery::default()
+    }
+}
+
+/// 备份列表的排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupSort {
+    #[default]
+    Time,
+    Size,
+    Name,
+    /// 置顶的在一起，其次按时间
+    Pinned,
+}
+
+/// 批量操作中失败的一项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchFailure {
+    pub id: i32,
+    pub error: String,
+}
+
+/// 批量操作的汇总结果，某一项失败不影响其他项
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub succeeded: Vec<i32>,
+    pub failed: Vec<BatchFailure>,
+}
+
+/// list_backups 返回的一页备份
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackupPage {
+    pub items: Vec<Backup>,
+    /// 传给下一次 list_backups 取下一页，None 表示已经没有更多
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub has_passphrase: bool,
+    pub unlocked: bool,
+}
+
+/// 设置中的 auto_backup 配置段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoBackupConfig {
+    pub enabled: bool,
+    pub interval_minutes: u32,
+    /// 只在 Noita 运行时备份，游戏没开时存档不会变化
+    #[serde(default = "default_true")]
+    pub only_while_running: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for AutoBackupConfig {
+    fn default() -> Self {
+        AutoBackupConfig {
+            enabled: false,
+            interval_minutes: 10,
+            only_while_running: true,
+        }
+    }
+}
+
+/// 自动备份的设置和运行状态
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoBackupStatus {
+    pub config: AutoBackupConfig,
+    /// 托盘里的暂停开关，只在本次运行中有效
+    pub paused: bool,
+    pub last_run: Option<String>,
+    /// 上次备份的结果，消息格式
+    pub last_result: Option<String>,
+}
+
+/// 设置中的 disk_space 配置段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskSpaceConfig {
+    /// 备份目录所在磁盘的剩余空间低于这个值时在首页提醒，单位 MB，0 表示不提醒
+    pub warn_below_mb: u64,
+}
+
+impl Default for DiskSpaceConfig {
+    fn default() -> Self {
+        DiskSpaceConfig { warn_below_mb: 1024 }
+    }
+}
+
+/// 备份目录所在磁盘的空间情况
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskSpace {
+    /// 剩余字节数，获取不到时为 None
+    pub available: Option<u64>,
+    pub low: bool,
+    pub config: DiskSpaceConfig,
+}
+
+/// 首页的统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DashboardStats {
+    pub backup_count: usize,
+    /// 单位字节
+    pub total_size: u64,
+    /// 后端健康
+    pub is_ready: bool,
+    /// 备份目录所在磁盘的剩余空间，单位字节
+    pub free_space: Option<u64>,
+    /// 剩余空间低于设置的提醒值
+    pub low_space: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meta_filter() {
+        let meta = MetaData { orbs: Some(5), gold: Some(120), ..Default::default() };
+        let filter = |field: &str, op: &str, value: &str| MetaFilter {
+            field: field.to_string(),
+            op: op.to_string(),
+            value: value.to_string(),
+        };
+        assert!(meta.matches(&filter("orbs", ">=", "5")));
+        assert!(!meta.matches(&filter("orbs", ">", "5")));
+        assert!(meta.matches(&filter("gold", "!=", "0")));
+        assert!(!meta.matches(&filter("hp", ">=", "0")));
+
+        let deep = MetaData { location: Some((120.0, 13200.0)), ..Default::default() };
+        assert!(deep.matches(&filter("biome", "=", "The Work")));
+        assert!(deep.matches(&filter("biome", "=", "the work")));
+        assert!(!deep.matches(&filter("biome", "=", "Mines")));
+        let far_east = MetaData { location: Some((9000.0, 13200.0)), ..Default::default() };
+        assert!(!far_east.matches(&filter("biome", "=", "The Work")));
+    }
+
+    #[test]
+    fn test_biome() {
+        assert_eq!(biome_at((227.5, -84.0)).as_deref(), Some("Forest"));
+        assert_eq!(biome_at((0.0, 800.0)).as_deref(), Some("Mines"));
+        assert_eq!(biome_at((-300.0, 4000.0)).as_deref(), Some("Snowy Depths"));
+        assert_eq!(biome_at((-3000.0, 4000.0)), None);
+    }
+
+    #[test]
+    fn test_more_info_formats() {
+        let record = |more_info: Value| {
+            serde_json::json!({
+                "id": 1, "name": null, "digest": "0".repeat(64), "size": 0, "path": "backup_000000000000",
+                "save_time": "2024-01-02T03:04:05Z", "more_info": more_info, "notes": null,
+                "pinned": false, "parent_id": null, "run_id": null,
+            })
+        };
+        let parse = |value| serde_json::from_value::<Backup>(value).unwrap().more_info;
+        let meta = MetaData { gold: Some(7), ..Default::default() };
+        assert_eq!(parse(record(serde_json::to_value(&meta).unwrap())), Some(meta.clone()));
+        assert_eq!(parse(record(Value::from(r#"{"gold":7}"#))), Some(meta));
+        assert_eq!(parse(record(Value::Null)), None);
+    }
+}
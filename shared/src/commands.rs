@@ -0,0 +1,429 @@
+//! 所有后端命令。结构体的字段就是命令的参数，为空的结构体表示没有参数
+use serde::{Deserialize, Serialize};
+use crate::backup::{
+    AutoBackupConfig, AutoBackupStatus, Backup, BackupGraph, BackupPage, BackupQuery, BackupRun, BackupSort, BatchResult,
+    DashboardStats, DiskSpace, DiskSpaceConfig, EncryptionStatus, ImportOutcome, LoadOutcome, SaveOutcome, ScopeConflict,
+};
+use crate::logs::{LogFilter, LogPage};
+use crate::settings::{HotkeyConfig, ImportPreview, NotificationConfig, SettingsInfo, UpdateConfig, UpdateInfo};
+use crate::storage::{RemoteConfig, SyncReport};
+use crate::Command;
+
+macro_rules! command {
+    ($name:ident => $command:literal, $output:ty) => {
+        impl Command for $name {
+            const NAME: &'static str = $command;
+            type Output = $output;
+        }
+    };
+}
+
+// 路径
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetSavePath;
+command!(GetSavePath => "get_save_path", String);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetDataPath;
+command!(GetDataPath => "get_data_path", String);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavePathToEnv {
+    pub path: String,
+}
+command!(SavePathToEnv => "save_path_to_env", ());
+
+/// 打开选择文件夹的对话框，取消时返回 None
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SelectSavePath;
+command!(SelectSavePath => "select_save_path", Option<String>);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SelectDataPath;
+command!(SelectDataPath => "select_data_path", Option<String>);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerifyValidation;
+command!(VerifyValidation => "verify_validation", ());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerifyDataValidation;
+command!(VerifyDataValidation => "verify_data_validation", ());
+
+/// 把备份目录移动到 new_path
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MoveDataPath {
+    pub new_path: String,
+}
+command!(MoveDataPath => "move_data_path", ());
+
+// 备份和还原
+
+/// 备份当前存档，返回给用户看的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveBackup {
+    pub name: Option<String>,
+}
+command!(SaveBackup => "save_backup", SaveOutcome);
+
+/// 按局分组的全部备份
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetAllBackups;
+command!(GetAllBackups => "get_all_backups", Vec<BackupRun>);
+
+/// scope 为还原范围：full、world+player、persistent、stats 或 save00 下的相对路径，为空时还原整个存档
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoadBackup {
+    pub backup_id: i32,
+    pub force: Option<bool>,
+    pub scope: Option<Vec<String>>,
+    pub passphrase: Option<String>,
+}
+command!(LoadBackup => "load_backup", LoadOutcome);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PreviewRestore {
+    pub backup_id: i32,
+    pub scope: Option<Vec<String>>,
+}
+command!(PreviewRestore => "preview_restore", Vec<ScopeConflict>);
+
+/// 置顶的备份需要 force 才能删除
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeleteBackup {
+    pub id: i32,
+    pub force: Option<bool>,
+}
+command!(DeleteBackup => "delete_backup", ());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SetBackupNotes {
+    pub id: i32,
+    pub notes: Option<String>,
+}
+command!(SetBackupNotes => "set_backup_notes", ());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SetBackupTags {
+    pub id: i32,
+    pub tags: Vec<String>,
+}
+command!(SetBackupTags => "set_backup_tags", ());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetAllTags;
+command!(GetAllTags => "get_all_tags", Vec<String>);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PinBackup {
+    pub id: i32,
+}
+command!(PinBackup => "pin_backup", ());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UnpinBackup {
+    pub id: i32,
+}
+command!(UnpinBackup => "unpin_backup", ());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchBackups {
+    pub query: BackupQuery,
+}
+command!(SearchBackups => "search_backups", Vec<Backup>);
+
+/// 分页列出符合条件的备份，cursor 为上一页返回的 next_cursor，第一页为 None
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListBackups {
+    pub query: BackupQuery,
+    pub sort: BackupSort,
+    pub descending: bool,
+    pub cursor: Option<String>,
+    pub limit: u32,
+}
+command!(ListBackups => "list_backups", BackupPage);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetBackupGraph;
+command!(GetBackupGraph => "get_backup_graph", BackupGraph);
+
+/// 地图缩略图，PNG 的 base64，没有时返回 None
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetBackupThumbnail {
+    pub id: i32,
+}
+command!(GetBackupThumbnail => "get_backup_thumbnail", Option<String>);
+
+/// 解密查看 .salakieli 文件，backup_id 为空时读取当前存档
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReadSalakieli {
+    pub backup_id: Option<i32>,
+    pub file_name: String,
+}
+command!(ReadSalakieli => "read_salakieli", String);
+
+/// 在资源管理器中打开备份目录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpenBackup {
+    pub id: i32,
+}
+command!(OpenBackup => "open_backup", ());
+
+/// 导出为单个文件，返回导出的路径，取消选择时返回 None
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportBackup {
+    pub backup_id: i32,
+    pub passphrase: Option<String>,
+}
+command!(ExportBackup => "export_backup", Option<String>);
+
+// 批量操作，ids 中每一项单独成功或失败
+
+/// 置顶的备份需要 force 才能删除
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchDeleteBackups {
+    pub ids: Vec<i32>,
+    pub force: bool,
+}
+command!(BatchDeleteBackups => "batch_delete_backups", BatchResult);
+
+/// 导出到选择的文件夹中，每个备份一个文件，取消选择时返回 None
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchExportBackups {
+    pub ids: Vec<i32>,
+    pub passphrase: Option<String>,
+}
+command!(BatchExportBackups => "batch_export_backups", Option<BatchResult>);
+
+/// 给每个备份加上 add 中的标签，去掉 remove 中的标签
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchTagBackups {
+    pub ids: Vec<i32>,
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+}
+command!(BatchTagBackups => "batch_tag_backups", BatchResult);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchPinBackups {
+    pub ids: Vec<i32>,
+    pub pinned: bool,
+}
+command!(BatchPinBackups => "batch_pin_backups", BatchResult);
+
+/// path 为空时弹出选择文件的对话框
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportBackup {
+    pub path: Option<String>,
+    pub passphrase: Option<String>,
+}
+command!(ImportBackup => "import_backup", ImportOutcome);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetDashboardStats;
+command!(GetDashboardStats => "get_dashboard_stats", DashboardStats);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IsGameRunning;
+command!(IsGameRunning => "is_game_running", bool);
+
+// 加密
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetEncryptionStatus;
+command!(GetEncryptionStatus => "get_encryption_status", EncryptionStatus);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UnlockEncryption {
+    pub passphrase: String,
+}
+command!(UnlockEncryption => "unlock_encryption", ());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LockEncryption;
+command!(LockEncryption => "lock_encryption", ());
+
+/// 返回加密了的备份数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnableEncryption {
+    pub passphrase: String,
+}
+command!(EnableEncryption => "enable_encryption", usize);
+
+/// 返回解密了的备份数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DisableEncryption {
+    pub passphrase: String,
+}
+command!(DisableEncryption => "disable_encryption", usize);
+
+// 自动备份和磁盘空间
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetAutoBackup;
+command!(GetAutoBackup => "get_auto_backup", AutoBackupStatus);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveAutoBackup {
+    pub config: AutoBackupConfig,
+}
+command!(SaveAutoBackup => "save_auto_backup", ());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SetAutoBackupPaused {
+    pub paused: bool,
+}
+command!(SetAutoBackupPaused => "set_auto_backup_paused", ());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetDiskSpace;
+command!(GetDiskSpace => "get_disk_space", DiskSpace);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveDiskSpaceConfig {
+    pub config: DiskSpaceConfig,
+}
+command!(SaveDiskSpaceConfig => "save_disk_space_config", ());
+
+// 快捷键、通知和语言
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetHotkeys;
+command!(GetHotkeys => "get_hotkeys", HotkeyConfig);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveHotkeys {
+    pub config: HotkeyConfig,
+}
+command!(SaveHotkeys => "save_hotkeys", ());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetNotifications;
+command!(GetNotifications => "get_notifications", NotificationConfig);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveNotifications {
+    pub config: NotificationConfig,
+}
+command!(SaveNotifications => "save_notifications", ());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetLanguage;
+command!(GetLanguage => "get_language", String);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SetLanguage {
+    pub language: String,
+}
+command!(SetLanguage => "set_language", ());
+
+// 远程存储
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetRemoteConfig;
+command!(GetRemoteConfig => "get_remote_config", Option<RemoteConfig>);
+
+/// secret 为 None 时保留原来的密码，为空字符串时删除
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveRemoteConfig {
+    pub config: Option<RemoteConfig>,
+    pub secret: Option<String>,
+}
+command!(SaveRemoteConfig => "save_remote_config", ());
+
+/// 测试连接，返回远端已有的备份数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TestRemote;
+command!(TestRemote => "test_remote", usize);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncBackups;
+command!(SyncBackups => "sync_backups", SyncReport);
+
+// 日志
+
+/// 从最新的一条往前数，跳过 offset 条后取 limit 条
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReadLogs {
+    pub filter: Option<LogFilter>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+command!(ReadLogs => "read_logs", LogPage);
+
+/// 返回这次跟踪的编号，停止时传回来
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StartLogTail {
+    pub filter: Option<LogFilter>,
+}
+command!(StartLogTail => "start_log_tail", u64);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StopLogTail {
+    pub generation: u64,
+}
+command!(StopLogTail => "stop_log_tail", ());
+
+/// 导出去掉了个人信息的日志，取消选择时返回 None
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportSanitizedLog;
+command!(ExportSanitizedLog => "export_sanitized_log", Option<String>);
+
+// 设置文件
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetSettingsInfo;
+command!(GetSettingsInfo => "get_settings_info", SettingsInfo);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DismissSettingsProblems;
+command!(DismissSettingsProblems => "dismiss_settings_problems", ());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportSettings;
+command!(ExportSettings => "export_settings", Option<String>);
+
+/// 选择要导入的文件，返回和当前设置的差异
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PreviewSettingsImport;
+command!(PreviewSettingsImport => "preview_settings_import", Option<ImportPreview>);
+
+/// 导入选中的设置项，返回实际导入了的
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportSettings {
+    pub file: String,
+    pub keys: Vec<String>,
+}
+command!(ImportSettings => "import_settings", Vec<String>);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResetSettingsSection {
+    pub key: String,
+}
+command!(ResetSettingsSection => "reset_settings_section", ());
+
+// 更新
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CheckForUpdate;
+command!(CheckForUpdate => "check_for_update", Option<UpdateInfo>);
+
+/// 返回是否要等正在进行的备份或还原结束
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstallUpdate;
+command!(InstallUpdate => "install_update", bool);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetUpdateConfig;
+command!(GetUpdateConfig => "get_update_config", UpdateConfig);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveUpdateConfig {
+    pub config: UpdateConfig,
+}
+command!(SaveUpdateConfig => "save_update_config", ());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetVersion;
+command!(GetVersion => "get_version", String);
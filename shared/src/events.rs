@@ -0,0 +1,81 @@
+//! 后端通过 app.emit 推送给前端的事件。结构体就是事件的 payload
+use serde::{Deserialize, Serialize};
+use crate::backup::Backup;
+use crate::logs::LogEntry;
+use crate::settings::UpdateProgress;
+use crate::Event;
+
+macro_rules! event {
+    ($name:ident => $event:literal) => {
+        impl Event for $name {
+            const NAME: &'static str = $event;
+        }
+    };
+}
+
+// 备份和还原
+
+/// 新增了一个备份，包括手动、自动、导入和从远程拉取的
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupCreated {
+    pub backup: Backup,
+}
+event!(BackupCreated => "backup://created");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupDeleted {
+    pub id: i32,
+}
+event!(BackupDeleted => "backup://deleted");
+
+/// 批量删除、打标签或置顶之后发出一次，ids 为成功处理的备份
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupsChanged {
+    pub ids: Vec<i32>,
+}
+event!(BackupsChanged => "backup://changed");
+
+/// 开始覆盖存档，mod 不一致或需要口令时不会发出
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestoreStarted {
+    pub backup_id: i32,
+}
+event!(RestoreStarted => "restore://started");
+
+/// 和 RestoreStarted 成对发出
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestoreFinished {
+    pub backup_id: i32,
+    /// 失败时的错误信息
+    pub error: Option<String>,
+}
+event!(RestoreFinished => "restore://finished");
+
+// 路径和游戏
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveDirChanged {
+    pub path: String,
+}
+event!(SaveDirChanged => "save-dir://changed");
+
+/// Noita 启动或退出
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameStatusChanged {
+    pub running: bool,
+}
+event!(GameStatusChanged => "game://status");
+
+// 日志和更新
+
+/// 实时跟踪到的新日志
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogTail(pub Vec<LogEntry>);
+event!(LogTail => "log://tail");
+
+/// 要求前端打开日志面板，例如点击了通知上的“查看日志”
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShowLog;
+event!(ShowLog => "log://show");
+
+event!(UpdateProgress => "update://progress");
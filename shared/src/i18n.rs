@@ -0,0 +1,136 @@
+//! 多语言文案和可翻译的消息。文案在仓库根目录的 locales 下，
+//! 后端返回的错误只带错误码和参数，两边按各自的当前语言用同一份文案翻译
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+
+const ZH_CN: &str = include_str!("../../locales/zh-CN.json");
+const EN: &str = include_str!("../../locales/en.json");
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    ZhCn,
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::En];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::En => "en",
+        }
+    }
+
+    /// 语言名称总是用该语言本身显示
+    pub fn name(self) -> &'static str {
+        match self {
+            Locale::ZhCn => "简体中文",
+            Locale::En => "English",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Locale {
+        if code.to_ascii_lowercase().starts_with("en") {
+            Locale::En
+        } else {
+            Locale::ZhCn
+        }
+    }
+
+    fn catalog(self) -> &'static HashMap<String, String> {
+        static ZH: OnceLock<HashMap<String, String>> = OnceLock::new();
+        static EN_CATALOG: OnceLock<HashMap<String, String>> = OnceLock::new();
+        let (cell, source) = match self {
+            Locale::ZhCn => (&ZH, ZH_CN),
+            Locale::En => (&EN_CATALOG, EN),
+        };
+        cell.get_or_init(|| serde_json::from_str(source).unwrap_or_default())
+    }
+
+    /// 取一条文案，这个语言缺失时退回中文，都没有时原样返回 key
+    pub fn t(self, key: &str) -> String {
+        self.catalog()
+            .get(key)
+            .or_else(|| Locale::ZhCn.catalog().get(key))
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    }
+
+    /// 带参数的文案，参数写成 {name}
+    pub fn tf(self, key: &str, params: &[(&str, String)]) -> String {
+        let mut text = self.t(key);
+        for (name, value) in params {
+            text = text.replace(&format!("{{{}}}", name), value);
+        }
+        text
+    }
+
+    /// 翻译命令返回的字符串，不是消息格式时原样返回
+    pub fn translate(self, text: &str) -> String {
+        match serde_json::from_str::<Message>(text) {
+            Ok(message) => message.text(self),
+            Err(_) => text.to_string(),
+        }
+    }
+}
+
+/// 一条可翻译的消息：错误码加参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    pub code: String,
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+}
+
+pub fn msg(code: &str) -> Message {
+    Message { code: code.to_string(), params: BTreeMap::new() }
+}
+
+impl Message {
+    pub fn with(mut self, name: &str, value: impl Display) -> Self {
+        self.params.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// 翻译成指定语言。参数本身也可能是一条消息，例如包装了下层错误
+    pub fn text(&self, locale: Locale) -> String {
+        let params: Vec<(&str, String)> =
+            self.params.iter().map(|(name, value)| (name.as_str(), locale.translate(value))).collect();
+        locale.tf(&self.code, &params)
+    }
+}
+
+/// 命令返回 Err(String) 时用 JSON 形式传给前端
+impl From<Message> for String {
+    fn from(message: Message) -> String {
+        serde_json::to_string(&message).unwrap_or(message.code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogs_have_same_keys() {
+        let zh = Locale::ZhCn.catalog();
+        let en = Locale::En.catalog();
+        assert!(!zh.is_empty());
+        let mut missing: Vec<_> = zh.keys().filter(|k| !en.contains_key(*k)).collect();
+        missing.extend(en.keys().filter(|k| !zh.contains_key(*k)));
+        assert!(missing.is_empty(), "缺少翻译: {:?}", missing);
+    }
+
+    #[test]
+    fn nested_message() {
+        let inner: String = msg("backup.not_found").with("id", 3).into();
+        let outer: String = msg("backup.delete_failed").with("id", 3).with("error", inner).into();
+        let text = Locale::En.translate(&outer);
+        assert!(!text.contains("backup.not_found"));
+        assert!(!text.contains('{'));
+        assert_eq!(Locale::En.translate("plain"), "plain");
+    }
+}
@@ -0,0 +1,29 @@
+//! 前端和后端共用的类型：所有命令的参数、返回值和命令名都定义在这里，
+//! 两边任何一边改了类型，另一边会直接编译失败
+pub mod backup;
+pub mod commands;
+pub mod events;
+pub mod i18n;
+pub mod logs;
+pub mod settings;
+pub mod storage;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// 一个后端命令。实现者本身就是命令的参数，后端对应的函数用一个 args 参数接收
+pub trait Command: Serialize {
+    /// 命令名，和后端的函数名一致
+    const NAME: &'static str;
+    type Output: DeserializeOwned;
+}
+
+/// 后端命令函数的返回类型。函数签名写成 Reply<命令>，
+/// 返回值和 Command::Output 不一致时后端会编译失败
+pub type Reply<C> = Result<<C as Command>::Output, String>;
+
+/// 一个后端推送的事件。实现者本身就是事件的 payload
+pub trait Event: Serialize + DeserializeOwned {
+    /// 事件名，前端按这个名字监听
+    const NAME: &'static str;
+}
@@ -0,0 +1,37 @@
+//! 应用内日志查看相关的类型
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// 一条日志，多行的日志会合并到同一条里
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// 本地时间，格式 2024-01-01 12:00:00
+    pub time: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// Unix 时间戳（秒），只在后端筛选时使用
+    #[serde(skip)]
+    pub timestamp: i64,
+}
+
+/// 日志筛选条件，都为空时不筛选
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LogFilter {
+    /// 最低级别，例如 warn 时只保留 WARN 和 ERROR
+    pub level: Option<String>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub from: Option<OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub to: Option<OffsetDateTime>,
+    /// 在内容和 target 中搜索，不区分大小写
+    pub search: Option<String>,
+}
+
+/// 一页日志，按时间从旧到新排列
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogPage {
+    pub entries: Vec<LogEntry>,
+    /// 符合条件的总条数
+    pub total: usize,
+}
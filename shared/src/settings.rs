@@ -0,0 +1,140 @@
+//! 设置文件、快捷键、通知和更新相关的类型
+use serde::{Deserialize, Serialize};
+
+/// 设置文件的位置和加载时发现的问题
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingsInfo {
+    pub path: String,
+    pub portable: bool,
+    pub version: u64,
+    /// 每一条都是消息格式，由前端翻译
+    pub problems: Vec<String>,
+}
+
+/// 一个字段的变化，值都转成了文字
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    /// 配置段里的字段名，路径这类单个值时为空
+    pub field: String,
+    pub current: Option<String>,
+    pub imported: Option<String>,
+}
+
+/// 导入文件里的一项设置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportItem {
+    pub key: String,
+    /// 和当前设置不同的地方，为空表示相同
+    pub changes: Vec<Change>,
+    /// 格式不对，不能导入
+    pub error: Option<String>,
+    /// 可以导入但需要注意，例如路径在这台电脑上不存在
+    pub warning: Option<String>,
+    /// 不能直接导入，需要用户按这里的说明手动修改
+    #[serde(default)]
+    pub manual: Option<String>,
+}
+
+impl ImportItem {
+    /// 有变化、格式正确并且可以直接导入
+    pub fn importable(&self) -> bool {
+        self.error.is_none() && self.manual.is_none() && !self.changes.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportPreview {
+    pub file: String,
+    pub items: Vec<ImportItem>,
+    /// 读取文件时发现的问题
+    pub problems: Vec<String>,
+}
+
+/// 设置中的 hotkeys 配置段，快捷键为空表示不使用
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HotkeyConfig {
+    pub quick_save: Option<String>,
+    pub restore_latest: Option<String>,
+    pub restore_previous: Option<String>,
+    /// 游戏运行时也允许快捷读档
+    #[serde(default)]
+    pub allow_load_while_running: bool,
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        HotkeyConfig {
+            quick_save: Some("CommandOrControl+Shift+F5".to_string()),
+            restore_latest: Some("CommandOrControl+Shift+F9".to_string()),
+            restore_previous: Some("CommandOrControl+Shift+F10".to_string()),
+            allow_load_while_running: false,
+        }
+    }
+}
+
+/// 设置中的 notifications 配置段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub backup_created: bool,
+    pub duplicate_skipped: bool,
+    pub restore_finished: bool,
+    pub failure: bool,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        NotificationConfig {
+            backup_created: true,
+            duplicate_skipped: true,
+            restore_finished: true,
+            failure: true,
+        }
+    }
+}
+
+/// 更新渠道
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+/// 设置中的 update 配置段
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateConfig {
+    pub channel: Channel,
+}
+
+/// 检查到的新版本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    /// 更新说明
+    pub notes: Option<String>,
+    pub date: Option<String>,
+    pub channel: Channel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateStage {
+    /// 等待正在进行的备份或还原结束
+    Waiting,
+    Downloading,
+    Installing,
+    Failed,
+}
+
+/// 下载和安装更新的进度，通过 update://progress 事件发出
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateProgress {
+    pub stage: UpdateStage,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    /// 失败时的错误，消息格式
+    pub error: Option<String>,
+}
@@ -0,0 +1,45 @@
+//! 远程存储相关的类型
+use serde::{Deserialize, Serialize};
+
+/// 设置中的 remote 配置段，密码和密钥不在这里，保存在系统凭据管理器中
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum RemoteConfig {
+    /// 另一块硬盘或网络共享目录
+    Local { path: String },
+    Sftp {
+        host: String,
+        port: Option<u16>,
+        username: String,
+        /// 远端目录
+        root: String,
+    },
+    Webdav {
+        /// 目录地址，例如 https://dav.example.com/noita
+        url: String,
+        username: Option<String>,
+    },
+    S3 {
+        /// 例如 https://s3.amazonaws.com 或 MinIO 的 http://127.0.0.1:9000
+        endpoint: String,
+        region: String,
+        bucket: String,
+        access_key: String,
+        prefix: Option<String>,
+        /// MinIO 等自建服务一般需要 path-style 地址
+        #[serde(default = "default_path_style")]
+        path_style: bool,
+    },
+}
+
+fn default_path_style() -> bool {
+    true
+}
+
+/// 一次同步的结果，列表中是备份名称
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncReport {
+    pub pushed: Vec<String>,
+    pub pulled: Vec<String>,
+    pub failed: Vec<String>,
+}
@@ -0,0 +1,126 @@
+/// 定时自动备份，窗口关到托盘后也会继续运行
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use chrono::{DateTime, Local};
+use log::{debug, info, warn};
+use tauri::AppHandle;
+use svld_shared::commands::{GetAutoBackup, SaveAutoBackup, SaveBackup, SetAutoBackupPaused};
+use svld_shared::Reply;
+use crate::backup::commands::save_backup;
+use crate::units::settings::{ConfigManager, Section};
+use crate::units::i18n::{self, msg};
+use crate::units::{game, tray};
+
+pub use svld_shared::backup::{AutoBackupConfig, AutoBackupStatus, SaveOutcome};
+
+pub const CONFIG_SECTION: &str = "auto_backup";
+
+/// 检查是否到点的间隔
+const TICK: Duration = Duration::from_secs(30);
+
+impl Section for AutoBackupConfig {
+    const NAME: &'static str = CONFIG_SECTION;
+}
+
+/// 托盘里的暂停开关，只在本次运行中有效
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// 一次自动备份的时间和结果
+type LastRun = (DateTime<Local>, Result<SaveOutcome, String>);
+
+/// 上一次自动备份
+static LAST: Mutex<Option<LastRun>> = Mutex::new(None);
+
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::Relaxed)
+}
+
+pub fn set_paused(paused: bool) {
+    PAUSED.store(paused, Ordering::Relaxed);
+}
+
+/// 托盘菜单里显示的一行状态
+pub fn status_line() -> String {
+    let config = AutoBackupConfig::load();
+    if !config.enabled {
+        return i18n::t("auto.status_off");
+    }
+    if is_paused() {
+        return i18n::t("auto.status_paused");
+    }
+    let message = match &*LAST.lock().unwrap() {
+        Some((time, Ok(SaveOutcome::Saved { .. }))) => msg("auto.status_last").with("time", time.format("%H:%M")),
+        Some((time, Ok(SaveOutcome::Duplicate { .. }))) => msg("auto.status_skipped").with("time", time.format("%H:%M")),
+        Some((time, Err(_))) => msg("auto.status_failed").with("time", time.format("%H:%M")),
+        None => msg("auto.status_every").with("minutes", config.interval_minutes),
+    };
+    i18n::text(&message)
+}
+
+/// 启动后台循环，在 setup 中调用一次
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_attempt: Option<DateTime<Local>> = None;
+        loop {
+            tokio::time::sleep(TICK).await;
+
+            let config = AutoBackupConfig::load();
+            if !config.enabled || is_paused() {
+                continue;
+            }
+            let interval = chrono::Duration::minutes(config.interval_minutes.max(1) as i64);
+            if last_attempt.is_some_and(|t| Local::now() - t < interval) {
+                continue;
+            }
+            if config.only_while_running && !game::is_noita_running() {
+                continue;
+            }
+
+            last_attempt = Some(Local::now());
+            debug!("[auto_backup] 开始自动备份");
+            let result = save_backup(SaveBackup::default()).await;
+            match &result {
+                Ok(outcome) => info!("[auto_backup] {}", i18n::translate(outcome.message())),
+                Err(e) => warn!("[auto_backup] 未完成: {}", i18n::translate(e)),
+            }
+            *LAST.lock().unwrap() = Some((Local::now(), result));
+            tray::refresh(&app);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_auto_backup() -> Reply<GetAutoBackup> {
+    let last = LAST.lock().unwrap();
+    Ok(AutoBackupStatus {
+        config: AutoBackupConfig::load(),
+        paused: is_paused(),
+        last_run: last.as_ref().map(|(time, _)| time.format("%Y-%m-%d %H:%M:%S").to_string()),
+        last_result: last.as_ref().map(|(_, result)| match result {
+            Ok(outcome) => outcome.message().to_string(),
+            Err(e) => e.clone(),
+        }),
+    })
+}
+
+#[tauri::command]
+pub fn save_auto_backup(app: AppHandle, args: SaveAutoBackup) -> Reply<SaveAutoBackup> {
+    let SaveAutoBackup { config } = args;
+    debug!("[save_auto_backup] {:?}", config);
+    if config.interval_minutes == 0 {
+        return Err(msg("auto.invalid_interval").into());
+    }
+    ConfigManager::save_section(CONFIG_SECTION, Some(&config))?;
+    tray::refresh(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_auto_backup_paused(app: AppHandle, args: SetAutoBackupPaused) -> Reply<SetAutoBackupPaused> {
+    let SetAutoBackupPaused { paused } = args;
+    debug!("[set_auto_backup_paused] {}", paused);
+    set_paused(paused);
+    tray::refresh(&app);
+    Ok(())
+}
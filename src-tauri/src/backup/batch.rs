@@ -0,0 +1,251 @@
+//! 对多个备份执行同一个操作。数据库的改动放在一个事务里，每一项用保存点隔开，
+//! 某一项失败只回滚这一项，最后汇总成一个结果
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use log::{debug, error, info};
+use sqlx::{Connection, SqliteConnection};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+use svld_shared::backup::{BatchFailure, BatchResult};
+use svld_shared::commands::{BatchDeleteBackups, BatchExportBackups, BatchPinBackups, BatchTagBackups};
+use svld_shared::Reply;
+use svld_shared::events::BackupsChanged;
+use crate::backup::commands::{connect, export_name, normalize_tags, write_export, ExportPassphrases};
+use crate::backup::fs_ops::remove_directory;
+use crate::backup::history;
+use crate::db::{Backup, Db};
+use crate::units::{events, path};
+use crate::units::i18n::{self, msg};
+
+/// 在数据库上对每一项执行的操作
+enum Op<'a> {
+    /// 删除记录，文件夹在事务提交后再删
+    Delete { force: bool, data_root: &'a Path },
+    Tag { add: &'a [String], remove: &'a [String] },
+    Pin(bool),
+}
+
+async fn find(conn: &mut SqliteConnection, id: i32) -> Result<Backup, String> {
+    Db::get_backup_by_id(conn, id)
+        .await
+        .map_err(|e| {
+            error!("获取存档出错: {}", e);
+            e.to_string()
+        })?
+        .ok_or_else(|| msg("backup.not_found").with("id", id).into())
+}
+
+/// 处理一项，删除时返回需要删掉的备份文件夹
+async fn apply(conn: &mut SqliteConnection, id: i32, op: &Op<'_>) -> Result<Option<PathBuf>, String> {
+    let backup = find(conn, id).await?;
+    match op {
+        Op::Delete { force, data_root } => {
+            if backup.pinned && !force {
+                return Err(msg("backup.pinned").into());
+            }
+            Db::delete_backup(conn, id)
+                .await
+                .map_err(|e| String::from(msg("backup.delete_failed").with("id", id).with("error", e)))?;
+            // 当前存档基于被删除的备份时，改为基于它的父节点
+            if history::get_head(conn).await.map_err(|e| e.to_string())? == Some(id) {
+                history::set_head(conn, backup.parent_id).await.map_err(|e| e.to_string())?;
+            }
+            Ok(Some(backup.resolve_dir(data_root)))
+        }
+        Op::Tag { add, remove } => {
+            let mut tags = backup.tags;
+            tags.extend(add.iter().cloned());
+            let tags: Vec<String> = normalize_tags(&tags).into_iter().filter(|t| !remove.contains(t)).collect();
+            Db::set_tags(conn, id, &tags).await.map_err(|e| e.to_string())?;
+            Ok(None)
+        }
+        Op::Pin(pinned) => {
+            Db::set_pinned(conn, id, *pinned).await.map_err(|e| e.to_string())?;
+            Ok(None)
+        }
+    }
+}
+
+/// 在一个事务中逐项执行 op，返回汇总结果和需要删除的文件夹
+async fn run_batch(
+    conn: &mut SqliteConnection,
+    ids: &[i32],
+    op: &Op<'_>,
+) -> anyhow::Result<(BatchResult, Vec<(i32, PathBuf)>)> {
+    let mut result = BatchResult::default();
+    let mut dirs = Vec::new();
+    let mut tx = conn.begin().await?;
+    let mut seen = HashSet::new();
+    for &id in ids.iter().filter(|id| seen.insert(**id)) {
+        let mut item = tx.begin().await?;
+        match apply(&mut item, id, op).await {
+            Ok(dir) => {
+                item.commit().await?;
+                result.succeeded.push(id);
+                dirs.extend(dir.map(|dir| (id, dir)));
+            }
+            Err(error) => {
+                item.rollback().await?;
+                result.failed.push(BatchFailure { id, error });
+            }
+        }
+    }
+    tx.commit().await?;
+    Ok((result, dirs))
+}
+
+/// 执行数据库上的批量操作，有成功的项时通知前端
+async fn run(ids: &[i32], op: Op<'_>) -> Result<(BatchResult, Vec<(i32, PathBuf)>), String> {
+    let mut conn = connect().await?;
+    let (result, dirs) = run_batch(&mut conn, ids, &op).await.map_err(|e| {
+        error!("批量操作失败: {}", e);
+        e.to_string()
+    })?;
+    if !result.succeeded.is_empty() {
+        events::emit(BackupsChanged { ids: result.succeeded.clone() });
+    }
+    Ok((result, dirs))
+}
+
+#[tauri::command]
+pub async fn batch_delete_backups(args: BatchDeleteBackups) -> Reply<BatchDeleteBackups> {
+    let BatchDeleteBackups { ids, force } = args;
+    info!("[batch_delete_backups] 删除 {:?}", ids);
+    let data_root = PathBuf::from(path::get_data_path()?);
+    let (mut result, dirs) = run(&ids, Op::Delete { force, data_root: &data_root }).await?;
+
+    // 记录已经删掉了，文件夹删不掉时只能告诉用户手动清理
+    for (id, dir) in dirs {
+        if !dir.exists() {
+            info!("备份文件夹不存在，跳过删除: {}", dir.display());
+            continue;
+        }
+        if let Err(e) = remove_directory(&dir) {
+            error!("删除备份文件夹 {} 失败: {}", dir.display(), e);
+            result.succeeded.retain(|succeeded| *succeeded != id);
+            let error = msg("batch.folder_left").with("path", dir.display()).with("error", e).into();
+            result.failed.push(BatchFailure { id, error });
+        }
+    }
+    info!("批量删除完成，成功 {} 个，失败 {} 个", result.succeeded.len(), result.failed.len());
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn batch_tag_backups(args: BatchTagBackups) -> Reply<BatchTagBackups> {
+    let BatchTagBackups { ids, add, remove } = args;
+    debug!("[batch_tag_backups] {:?} add = {:?} remove = {:?}", ids, add, remove);
+    let add = normalize_tags(&add);
+    let remove = normalize_tags(&remove);
+    let (result, _) = run(&ids, Op::Tag { add: &add, remove: &remove }).await?;
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn batch_pin_backups(args: BatchPinBackups) -> Reply<BatchPinBackups> {
+    let BatchPinBackups { ids, pinned } = args;
+    debug!("[batch_pin_backups] {:?} pinned = {}", ids, pinned);
+    let (result, _) = run(&ids, Op::Pin(pinned)).await?;
+    Ok(result)
+}
+
+/// 文件名中不能出现的字符换成下划线
+fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_control() || r#"\/:*?"<>|"#.contains(c) { '_' } else { c })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn batch_export_backups(app: AppHandle, args: BatchExportBackups) -> Reply<BatchExportBackups> {
+    let BatchExportBackups { ids, passphrase } = args;
+    debug!("[batch_export_backups] {:?}", ids);
+    let passphrases = ExportPassphrases::resolve(passphrase)?;
+
+    let Some(folder) = app
+        .dialog()
+        .file()
+        .set_title(i18n::t("export.folder_dialog_title"))
+        .blocking_pick_folder()
+    else {
+        return Ok(None);
+    };
+    let folder = folder.into_path().map_err(|e| e.to_string())?;
+
+    let mut conn = connect().await?;
+    let mut result = BatchResult::default();
+    let mut seen = HashSet::new();
+    for &id in ids.iter().filter(|id| seen.insert(**id)) {
+        let outcome = match find(&mut conn, id).await {
+            Ok(backup) => {
+                // 同名时加上 id 区分，不覆盖已有的文件
+                let stem = file_stem(&export_name(&backup));
+                let mut target = folder.join(format!("{}.svld", stem));
+                if target.exists() {
+                    target = folder.join(format!("{}_{}.svld", stem, id));
+                }
+                write_export(backup, target, &passphrases).await
+            }
+            Err(e) => Err(e),
+        };
+        match outcome {
+            Ok(()) => result.succeeded.push(id),
+            Err(error) => result.failed.push(BatchFailure { id, error }),
+        }
+    }
+    info!(
+        "批量导出到 {} 完成，成功 {} 个，失败 {} 个",
+        folder.display(),
+        result.succeeded.len(),
+        result.failed.len()
+    );
+    Ok(Some(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::OffsetDateTime;
+
+    fn sample(digest: &str) -> Backup {
+        Backup {
+            id: 0,
+            name: Some(digest.to_string()),
+            digest: digest.repeat(16),
+            size: 1,
+            path: String::new(),
+            save_time: OffsetDateTime::now_utc(),
+            more_info: None,
+            notes: None,
+            pinned: false,
+            parent_id: None,
+            run_id: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_batch_keeps_going_after_failures() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db_file = tmp.path().join("svld.db");
+        let mut conn = Db::new(db_file.to_string_lossy().to_string()).await.unwrap();
+
+        let a = Db::store_backup(&sample("a"), &mut conn).await.unwrap();
+        let pinned = Db::store_backup(&Backup { pinned: true, ..sample("b") }, &mut conn).await.unwrap();
+
+        let add = vec!["old".to_string()];
+        let (tagged, _) = run_batch(&mut conn, &[a, pinned], &Op::Tag { add: &add, remove: &[] }).await.unwrap();
+        assert_eq!(tagged.succeeded, vec![a, pinned]);
+
+        let op = Op::Delete { force: false, data_root: tmp.path() };
+        let (result, dirs) = run_batch(&mut conn, &[a, pinned, 999, a], &op).await.unwrap();
+        assert_eq!(result.succeeded, vec![a]);
+        assert_eq!(result.failed.iter().map(|f| f.id).collect::<Vec<_>>(), vec![pinned, 999]);
+        assert_eq!(dirs.len(), 1);
+
+        // 失败的那一项没有被改动
+        assert!(Db::get_backup_by_id(&mut conn, a).await.unwrap().is_none());
+        let kept = Db::get_backup_by_id(&mut conn, pinned).await.unwrap().unwrap();
+        assert_eq!(kept.tags, vec!["old"]);
+    }
+}
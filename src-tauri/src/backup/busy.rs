@@ -0,0 +1,60 @@
+/// 正在进行的备份、还原等操作。安装更新会重启程序，要等这些操作结束
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use crate::units::i18n::msg;
+
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+/// 开始安装更新后不再接受新的操作
+static CLOSING: AtomicBool = AtomicBool::new(false);
+
+/// 操作进行中持有，drop 时结束
+pub struct BusyGuard(());
+
+/// 开始一个操作，正在安装更新时拒绝。先计数再检查，
+/// 和 close 的先置位再读计数配合，两边至少有一边能看到对方
+pub fn begin() -> Result<BusyGuard, String> {
+    ACTIVE.fetch_add(1, Ordering::SeqCst);
+    let guard = BusyGuard(());
+    if CLOSING.load(Ordering::SeqCst) {
+        return Err(msg("update.closing").into());
+    }
+    Ok(guard)
+}
+
+impl Drop for BusyGuard {
+    fn drop(&mut self) {
+        ACTIVE.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub fn is_busy() -> bool {
+    ACTIVE.load(Ordering::SeqCst) > 0
+}
+
+/// 拒绝之后的操作，返回是否还有操作在进行
+pub fn close() -> bool {
+    CLOSING.store(true, Ordering::SeqCst);
+    is_busy()
+}
+
+/// 安装失败时重新接受操作
+pub fn reopen() {
+    CLOSING.store(false, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_close_refuses_new_operations() {
+        let running = begin().unwrap();
+        assert!(close());
+        assert!(begin().is_err());
+        // 被拒绝的操作不计入
+        drop(running);
+        assert!(!is_busy());
+        reopen();
+        assert!(begin().is_ok());
+        assert!(!is_busy());
+    }
+}
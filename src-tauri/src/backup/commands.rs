@@ -1,25 +1,59 @@
-use std::fs;
 use std::path::{Path, PathBuf};
-use crate::db::{Backup, Db};
+use crate::db::{Backup, BackupCursor, BackupFilter, Db};
+use crate::backup::meta_data;
 use crate::backup::service::*;
 use crate::backup::fs_ops::*;
+use crate::backup::{busy, crypto, history, map, mods, relocate, restore, runs, salakieli, space};
+use crate::backup::crypto::{CryptoError, EncryptionConfig, OpenedBackup};
+use crate::backup::restore::ScopeConflict;
+use crate::backup::mods::ModDiff;
+use crate::backup::runs::BackupRun;
+use svld_shared::commands::{
+    DeleteBackup, DisableEncryption, EnableEncryption, ExportBackup, GetAllBackups, GetAllTags, GetBackupThumbnail,
+    GetEncryptionStatus, ImportBackup, ListBackups, LoadBackup, LockEncryption, MoveDataPath, PinBackup,
+    PreviewRestore, ReadSalakieli, SaveBackup, SearchBackups, SetBackupNotes, SetBackupTags, UnlockEncryption,
+    UnpinBackup,
+};
+use svld_shared::Reply;
+use svld_shared::events::{BackupCreated, BackupDeleted, RestoreFinished, RestoreStarted};
+use crate::storage::archive;
+use base64::Engine;
 use chrono::Local;
 use log::{debug, error, info};
+use sqlx::SqliteConnection;
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
-use crate::units::path;
+use crate::units::{events, path};
 use crate::units::db_path;
 use crate::units::path::get_save_path;
+use crate::units::notify::{self, Category, NotifyAction, Severity};
+use crate::units::i18n::{self, msg};
+
+pub use svld_shared::backup::{BackupPage, BackupQuery, EncryptionStatus, ImportOutcome, LoadOutcome, SaveOutcome};
+
+/// list_backups 一页最多返回的条数
+const MAX_PAGE_SIZE: u32 = 200;
 
 /// 在数据库里留档
 #[tauri::command]
-pub async fn save_backup(name : Option<&str>) -> Result<String, String> {
+pub async fn save_backup(args: SaveBackup) -> Reply<SaveBackup> {
+    let SaveBackup { name } = args;
     debug!("[save_back_up] {}", Local::now());
+    let _busy = busy::begin()?;
+    // 开启加密后备份必须能立即加密，否则明文会留在备份目录里
+    let encryption = EncryptionConfig::load();
+    let passphrase = crypto::resolve_passphrase(None);
+    if encryption.enabled && passphrase.is_none() {
+        return Err(msg("backup.locked").into());
+    }
     // 先保存到本地，获取备份名称和 digest
     let (backup_name, digest) = match save_local().await{
         Ok((name, digest)) => (name, digest),
         Err(e) => {
             error!("保存时出错: {}",e);
+            notify::send(Category::Failure, Severity::Error, msg("backup.save_failed").with("error", &e), Some(NotifyAction::ViewLog));
             return Err(e);
         }
     };
@@ -42,10 +76,16 @@ pub async fn save_backup(name : Option<&str>) -> Result<String, String> {
         error!("查询数据库失败: {}", e);
         e.to_string()
     })? {
-        let existing_name = existing_backup.name.as_deref().unwrap_or("未命名");
-        let msg = format!("该存档内容已备份过，名称为: {}", existing_name);
-        info!("{}", msg);
-        return Err(msg);
+        // 当前存档和已有备份完全相同，之后的备份就从它继续
+        if let Err(e) = history::set_head(&mut conn, Some(existing_backup.id)).await {
+            error!("更新当前存档节点失败: {}", e);
+        }
+        let existing_name = existing_backup.name.clone().unwrap_or_else(|| i18n::t("common.unnamed"));
+        let message = msg("backup.duplicate").with("name", existing_name);
+        info!("{}", i18n::text(&message));
+        let existing_dir = existing_backup.resolve_dir(Path::new(&path::get_data_path()?));
+        notify::send(Category::DuplicateSkipped, Severity::Info, message.clone(), Some(NotifyAction::Open(existing_dir)));
+        return Ok(SaveOutcome::Duplicate { message: message.into(), id: existing_backup.id });
     }
 
     // 计算备份大小
@@ -59,44 +99,149 @@ pub async fn save_backup(name : Option<&str>) -> Result<String, String> {
         }
     };
     let save_time = OffsetDateTime::now_utc();
+    let meta = meta_data::read(&backup_path_buf);
 
     let slot_name: String = name
         .filter(|n| !n.trim().is_empty())
-        .map(|n| n.to_string())
         .unwrap_or_else(|| {
             let time_str = save_time
                 .format(&Rfc3339)
                 .unwrap_or_default();
-            format!("存档_{}", time_str)
+            i18n::text(&msg("backup.default_name").with("time", time_str))
         });
 
+    let run_id = runs::run_id(&meta);
+    let parent_id = history::resolve_parent(&mut conn, run_id.as_deref()).await.map_err(|e| {
+        error!("查询父节点失败: {}", e);
+        e.to_string()
+    })?;
+
     let backup = Backup {
         id: 0,
         name: Some(slot_name),
         digest,
         size,
-        path:data_path,
+        path: backup_name,
         save_time,
-        more_info: None,
+        more_info: Some(meta),
+        notes: None,
+        pinned: false,
+        parent_id,
+        run_id,
+        tags: Vec::new(),
     };
 
-    match Db::store_backup(&backup, &mut conn).await {
-        Ok(_) => {},
+    let backup_id = match Db::store_backup(&backup, &mut conn).await {
+        Ok(id) => id,
         Err(e) => {
             error!("存储数据库失败: {}",e);
             return Err(e.to_string());
         }
+    };
+
+    if let Err(e) = history::set_head(&mut conn, Some(backup_id)).await {
+        error!("更新当前存档节点失败: {}", e);
+    }
+
+    // 缩略图生成失败不影响备份本身
+    if let Err(e) = store_thumbnail(&mut conn, backup_id, backup_path_buf.clone(), backup.more_info.as_ref().and_then(|meta| meta.location)).await {
+        error!("生成地图缩略图失败: {}", e);
+    }
+
+    if let (true, Some(passphrase)) = (encryption.enabled, passphrase) {
+        seal_backup_dir(backup_path_buf.clone(), passphrase).await.map_err(|e| {
+            let message = msg("backup.seal_failed").with("error", e);
+            notify::send(Category::Failure, Severity::Error, message.clone(), Some(NotifyAction::ViewLog));
+            String::from(message)
+        })?;
     }
 
     info!(
         "[{}] 存档保存成功: {}",Local::now(),
-        backup.name.as_ref().unwrap_or(&"未命名".to_string())
+        backup.name.clone().unwrap_or_else(|| i18n::t("common.unnamed"))
+    );
+    notify::send(
+        Category::BackupCreated,
+        Severity::Info,
+        msg("notify.backup_created").with("name", backup.name.clone().unwrap_or_else(|| i18n::t("common.unnamed"))),
+        Some(NotifyAction::Open(backup_path_buf)),
     );
-    Ok("存档保存成功".to_string())
+    events::emit(BackupCreated { backup: Backup { id: backup_id, ..backup } });
+    Ok(SaveOutcome::Saved { message: msg("backup.saved").into() })
 }
 
+async fn seal_backup_dir(backup_dir: PathBuf, passphrase: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        crypto::seal_dir(&backup_dir, &passphrase).map_err(|e| {
+            error!("加密备份 {} 失败: {}", backup_dir.display(), e);
+            e.to_string()
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 取得可以直接读取的备份内容，加密的备份会先解密到临时目录
+async fn open_backup_dir(backup_dir: PathBuf, passphrase: Option<String>) -> Result<OpenedBackup, String> {
+    tokio::task::spawn_blocking(move || crypto::open_backup(&backup_dir, passphrase.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| {
+            error!("打开备份失败: {}", e);
+            e.to_string()
+        })
+}
+
+/// 渲染备份的地图缩略图并存入数据库
+async fn store_thumbnail(
+    conn: &mut SqliteConnection,
+    id: i32,
+    backup_dir: PathBuf,
+    player: Option<(f64, f64)>,
+) -> Result<Option<Vec<u8>>, String> {
+    // 地形块较多时解码比较耗时，放到阻塞线程里做
+    let png = tokio::task::spawn_blocking(move || map::render_thumbnail(&backup_dir, player))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    if let Some(png) = &png {
+        Db::set_thumbnail(conn, id, png).await.map_err(|e| e.to_string())?;
+    }
+    Ok(png)
+}
+
+/// 备份的地图缩略图 (data URL)，旧备份第一次查看时生成
 #[tauri::command]
-pub async fn get_all_backups() -> Result<Vec<Backup>, String> {
+pub async fn get_backup_thumbnail(args: GetBackupThumbnail) -> Reply<GetBackupThumbnail> {
+    let GetBackupThumbnail { id } = args;
+    let mut conn = connect().await?;
+    let png = match Db::get_thumbnail(&mut conn, id).await.map_err(|e| e.to_string())? {
+        Some(png) => Some(png),
+        None => {
+            let backup = Db::get_backup_by_id(&mut conn, id)
+                .await
+                .map_err(|e| {
+                    error!("获取存档出错: {}", e);
+                    e.to_string()
+                })?
+                .ok_or_else(|| String::from(msg("backup.not_found").with("id", id)))?;
+            let backup_dir = backup.resolve_dir(Path::new(&path::get_data_path()?));
+            let player = backup.more_info.as_ref().and_then(|meta| meta.location);
+            store_thumbnail(&mut conn, id, backup_dir, player).await.map_err(|e| {
+                error!("生成地图缩略图失败: {}", e);
+                e
+            })?
+        }
+    };
+
+    Ok(png.map(|png| {
+        format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(png))
+    }))
+}
+
+/// 按游戏局分组返回所有备份
+#[tauri::command]
+pub async fn get_all_backups() -> Reply<GetAllBackups> {
     debug!("[get_all_backups] {}",Local::now());
     let db_path = db_path::get_db_path().map_err(|e| {
         error!("获取数据库路径失败: {}", e);
@@ -111,16 +256,21 @@ pub async fn get_all_backups() -> Result<Vec<Backup>, String> {
         }
     };
 
-    Db::get_all_backup(&mut conn).await.map_err(|e| {
+    let backups = Db::get_all_backup(&mut conn).await.map_err(|e| {
         error!("获取已有存档失败: {}", e);  // 记录错误日志
         e.to_string()  // 将错误转换为字符串后返回
-    })
+    })?;
 
+    Ok(runs::group_by_run(backups))
 }
 
+/// scope 为还原范围：full、world+player、persistent、stats 或 save00 下的相对路径，为空时还原整个存档
 #[tauri::command]
-pub async fn load_backup(backup_id: i32) -> Result<String, String> {
-    debug!("[load_backup] {} ", Local::now());
+pub async fn load_backup(args: LoadBackup) -> Reply<LoadBackup> {
+    let LoadBackup { backup_id, force, scope, passphrase } = args;
+    debug!("[load_backup] {} scope = {:?}", Local::now(), scope);
+    let _busy = busy::begin()?;
+    let subtrees = restore::resolve_scope(&scope.unwrap_or_default())?;
     // 连接数据库查找备份
     let db_path = db_path::get_db_path().map_err(|e| {
         error!("获取数据库路径失败: {}", e);
@@ -141,7 +291,7 @@ pub async fn load_backup(backup_id: i32) -> Result<String, String> {
 
     let backup = match backup {
         Some(b) => b,
-        None => return Err(format!("未找到ID为{}的备份", backup_id)),
+        None => return Err(msg("backup.not_found").with("id", backup_id).into()),
     };
 
     // 获取游戏存档路径（目标路径）
@@ -149,15 +299,46 @@ pub async fn load_backup(backup_id: i32) -> Result<String, String> {
     let target_path = Path::new(&save_path);
 
     // 构建备份文件路径
-    let digest_prefix = &backup.digest[..12]; // 使用前12位
-    let backup_name = format!("backup_{}", digest_prefix);
-    let backup_path = Path::new(&backup.path).join(&backup_name);
+    let data_path = path::get_data_path()?;
+    let backup_path = backup.resolve_dir(Path::new(&data_path));
 
     // 验证备份文件是否存在
     if !backup_path.exists() {
         error!("备份文件不存在: {}",backup_path.display());
-        return Err(format!("备份文件不存在: {}", backup_path.display()));
+        return Err(msg("backup.files_missing").with("path", backup_path.display()).into());
+    }
+
+    // 加密的备份先完整解密到临时目录，口令错误时不会动到当前存档
+    let given = passphrase.filter(|p| !p.is_empty());
+    let passphrase = crypto::resolve_passphrase(given.clone());
+    if crypto::is_sealed(&backup_path) && passphrase.is_none() {
+        return Ok(LoadOutcome::PassphraseRequired);
+    }
+    let opened = open_backup_dir(backup_path, passphrase).await?;
+    let backup_path = opened.path();
+    if let Some(given) = given {
+        // 和设置的口令一致时顺便解锁，之后不用再输入
+        let _ = crypto::unlock(&given);
+    }
+
+    // 备份时的 mod 列表，旧备份没有记录时直接从备份文件夹读取；
+    // 不还原 mod_config.xml 时 mod 列表不会变化，不需要检查
+    let backup_mods = restore::restores(&subtrees, mods::MOD_CONFIG)
+        .then(|| backup
+        .more_info
+        .as_ref()
+        .and_then(|meta| meta.mods.clone())
+        .or_else(|| mods::read_enabled_mods(backup_path)))
+        .flatten();
+    if let (Some(backup_mods), false) = (backup_mods, force.unwrap_or(false)) {
+        let current_mods = mods::read_enabled_mods(target_path).unwrap_or_default();
+        let diff = ModDiff::compare(&backup_mods, &current_mods);
+        if !diff.is_empty() {
+            info!("备份 {} 的 mod 与当前不一致: {:?}", backup_id, diff);
+            return Ok(LoadOutcome::ModMismatch { diff });
+        }
     }
+
     /* hash实现不对，有bug，反正出错了也修不了，不修了
     let current_digest = calculate_hash(&backup_path).map_err(|e| {
         error!("完整性校验失败: {}",e);
@@ -169,42 +350,70 @@ pub async fn load_backup(backup_id: i32) -> Result<String, String> {
         return Err(format!("备份文件已损坏，哈希值不匹配"));
     }
     */
-    // 删除目标目录（如果存在）
-    if target_path.exists() {
-        fs::remove_dir_all(target_path).map_err(|e| {
-            error!("删除文件失败 {}",e);
-            format!("无法删除目标目录 {}: {}", target_path.display(), e)
-        })?;
+    let summary = restore::conflict_summary(backup_path, target_path, &subtrees);
+
+    // 还原会先删除当前存档，空间不够时必须在删除之前拦下
+    let required = space::restore_required(backup_path, target_path, &subtrees);
+    space::ensure_space(target_path, required).map_err(|e| {
+        error!("{}", i18n::text(&e));
+        String::from(e)
+    })?;
+
+    // 用备份替换目标位置的对应部分
+    events::emit(RestoreStarted { backup_id });
+    if let Err(e) = restore::restore_from_dir(backup_path, target_path, &subtrees) {
+        error!("加载备份失败 {}", e);
+        events::emit(RestoreFinished { backup_id, error: Some(e.to_string()) });
+        return Err(e.to_string());
     }
 
-    // 确保父目录存在
-    if let Some(parent) = target_path.parent() {
-        if !parent.exists() {
-            fs::create_dir_all(parent).map_err(|e| {
-                error!("创建父目录失败 {}",e);
-                format!("无法创建父目录 {}: {}", parent.display(), e)
-            })?;
+    // 只还原解锁进度或统计时，当前这一局并没有回到该备份
+    let restores_run = restore::restores(&subtrees, "world");
+    if restores_run {
+        if let Err(e) = history::set_head(&mut conn, Some(backup_id)).await {
+            error!("更新当前存档节点失败: {}", e);
         }
     }
+    events::emit(RestoreFinished { backup_id, error: None });
 
-    // 复制备份到目标位置
-    copy_directory(&backup_path, target_path).map_err(|e| {
-        error!("加载备份失败 {}", e);
-        e.to_string()
-    })?;
+    let backup_name = backup.name.clone().unwrap_or_else(|| i18n::t("common.unnamed"));
+    let success_msg: String = msg("backup.loaded").with("name", &backup_name).with("path", target_path.display()).into();
 
-    let success_msg = format!(
-        "成功加载备份: {} -> {}",
-        backup.name.as_ref().unwrap_or(&"未命名".to_string()),
-        target_path.display()
+    debug!("{}", success_msg);
+    notify::send(
+        Category::RestoreFinished,
+        Severity::Info,
+        msg("notify.restore_finished").with("name", backup_name),
+        Some(NotifyAction::Open(target_path.to_path_buf())),
     );
+    Ok(LoadOutcome::Loaded { message: success_msg, summary })
+}
 
-    debug!("{}", success_msg);
-    Ok(success_msg)
+/// 预览按范围还原会改动哪些内容，不修改任何文件
+#[tauri::command]
+pub async fn preview_restore(args: PreviewRestore) -> Reply<PreviewRestore> {
+    let PreviewRestore { backup_id, scope } = args;
+    debug!("[preview_restore] {} scope = {:?}", backup_id, scope);
+    let subtrees = restore::resolve_scope(&scope.unwrap_or_default())?;
+    let mut conn = connect().await?;
+    let backup = Db::get_backup_by_id(&mut conn, backup_id)
+        .await
+        .map_err(|e| {
+            error!("获取存档出错: {}", e);
+            e.to_string()
+        })?
+        .ok_or_else(|| String::from(msg("backup.not_found").with("id", backup_id)))?;
+
+    let backup_path = backup.resolve_dir(Path::new(&path::get_data_path()?));
+    let opened = open_backup_dir(backup_path, crypto::resolve_passphrase(None)).await?;
+    let save_path = get_save_path()?;
+    Ok(restore::conflict_summary(opened.path(), Path::new(&save_path), &subtrees))
 }
 
+/// 置顶的备份需要 force = true 才能删除
 #[tauri::command]
-pub async fn delete_backup(id : i32) -> Result<(), String> {
+pub async fn delete_backup(args: DeleteBackup) -> Reply<DeleteBackup> {
+    let DeleteBackup { id, force } = args;
     info!("[delete_backup]:删除 {}", id);
 
     let db_path = db_path::get_db_path().map_err(|e| {
@@ -228,14 +437,18 @@ pub async fn delete_backup(id : i32) -> Result<(), String> {
         Some(b) => b,
         None => {
             error!("未找到ID为{}的备份", id);
-            return Err(format!("未找到ID为{}的备份", id));
+            return Err(msg("backup.not_found").with("id", id).into());
         }
     };
 
-    // 构建备份文件路径 (命名方式: backup_{digest前12位})
-    let digest_prefix = &backup.digest[..12];
-    let backup_name = format!("backup_{}", digest_prefix);
-    let backup_path = Path::new(&backup.path).join(&backup_name);
+    if backup.pinned && !force.unwrap_or(false) {
+        info!("存档 {} 已置顶，拒绝删除", id);
+        return Err(msg("backup.pinned").into());
+    }
+
+    // 构建备份文件路径
+    let data_path = path::get_data_path()?;
+    let backup_path = backup.resolve_dir(Path::new(&data_path));
 
     // 删除实际存档
     if backup_path.exists() {
@@ -251,13 +464,525 @@ pub async fn delete_backup(id : i32) -> Result<(), String> {
     // 删除数据库记录
     Db::delete_backup(&mut conn, id).await.map_err(|e| {
         error!("删除存档 {}失败：{}", id,e);
-        format!("删除存档 {} 失败: {}", id, e)
+        String::from(msg("backup.delete_failed").with("id", id).with("error", e))
     })?;
 
+    // 当前存档基于被删除的备份时，改为基于它的父节点
+    let head = history::get_head(&mut conn).await.map_err(|e| e.to_string())?;
+    if head == Some(id) {
+        if let Err(e) = history::set_head(&mut conn, backup.parent_id).await {
+            error!("更新当前存档节点失败: {}", e);
+        }
+    }
+
     info!("成功删除存档 ID: {}", id);
+    events::emit(BackupDeleted { id });
     Ok(())
 }
 
+pub(crate) async fn connect() -> Result<SqliteConnection, String> {
+    let db_path = db_path::get_db_path().map_err(|e| {
+        error!("获取数据库路径失败: {}", e);
+        e.to_string()
+    })?;
+    Db::new(db_path).await.map_err(|e| {
+        error!("建立数据库连接出错: {}", e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+pub async fn set_backup_notes(args: SetBackupNotes) -> Reply<SetBackupNotes> {
+    let SetBackupNotes { id, notes } = args;
+    debug!("[set_backup_notes] id = {}", id);
+    let mut conn = connect().await?;
+    let notes = notes.filter(|n| !n.trim().is_empty());
+    Db::set_notes(&mut conn, id, notes.as_deref()).await.map_err(|e| {
+        error!("保存备注失败: {}", e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+pub async fn set_backup_tags(args: SetBackupTags) -> Reply<SetBackupTags> {
+    let SetBackupTags { id, tags } = args;
+    debug!("[set_backup_tags] id = {}, tags = {:?}", id, tags);
+    let mut conn = connect().await?;
+    Db::set_tags(&mut conn, id, &normalize_tags(&tags)).await.map_err(|e| {
+        error!("保存标签失败: {}", e);
+        e.to_string()
+    })
+}
+
+/// 去掉首尾空白和空标签，排序并去重
+pub(crate) fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+#[tauri::command]
+pub async fn get_all_tags() -> Reply<GetAllTags> {
+    let mut conn = connect().await?;
+    Db::get_all_tags(&mut conn).await.map_err(|e| {
+        error!("获取标签失败: {}", e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+pub async fn pin_backup(args: PinBackup) -> Reply<PinBackup> {
+    let PinBackup { id } = args;
+    debug!("[pin_backup] id = {}", id);
+    let mut conn = connect().await?;
+    Db::set_pinned(&mut conn, id, true).await.map_err(|e| {
+        error!("置顶失败: {}", e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+pub async fn unpin_backup(args: UnpinBackup) -> Reply<UnpinBackup> {
+    let UnpinBackup { id } = args;
+    debug!("[unpin_backup] id = {}", id);
+    let mut conn = connect().await?;
+    Db::set_pinned(&mut conn, id, false).await.map_err(|e| {
+        error!("取消置顶失败: {}", e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+pub async fn search_backups(args: SearchBackups) -> Reply<SearchBackups> {
+    let SearchBackups { query } = args;
+    debug!("[search_backups] {:?}", query);
+    let mut conn = connect().await?;
+
+    let backups = Db::search_backups(&mut conn, &backup_filter(&query)).await.map_err(|e| {
+        error!("搜索存档失败: {}", e);
+        e.to_string()
+    })?;
+
+    Ok(backups.into_iter().filter(|b| matches_query(b, &query)).collect())
+}
+
+/// 按 sort 分页列出符合 query 的备份
+#[tauri::command]
+pub async fn list_backups(args: ListBackups) -> Reply<ListBackups> {
+    let ListBackups { query, sort, descending, cursor, limit } = args;
+    debug!("[list_backups] {:?} {:?} descending = {} cursor = {:?}", query, sort, descending, cursor);
+    let limit = limit.clamp(1, MAX_PAGE_SIZE);
+    let mut after = match cursor {
+        Some(cursor) => Some(BackupCursor::decode(&cursor).ok_or_else(|| {
+            error!("无法解析翻页游标: {}", cursor);
+            String::from(msg("backup.invalid_cursor"))
+        })?),
+        None => None,
+    };
+    let mut conn = connect().await?;
+    let filter = backup_filter(&query);
+
+    // 日期和元信息条件在内存中过滤，一批可能凑不满一页，需要继续往后取
+    let mut items = Vec::new();
+    loop {
+        let batch = Db::list_backups(&mut conn, &filter, sort, descending, after.as_ref(), limit)
+            .await
+            .map_err(|e| {
+                error!("获取存档列表失败: {}", e);
+                e.to_string()
+            })?;
+        let exhausted = batch.len() < limit as usize;
+        for backup in batch {
+            after = Some(BackupCursor::of(&backup, sort));
+            if matches_query(&backup, &query) {
+                items.push(backup);
+                if items.len() == limit as usize {
+                    return Ok(BackupPage { items, next_cursor: after.map(|c| c.encode()) });
+                }
+            }
+        }
+        if exhausted {
+            return Ok(BackupPage { items, next_cursor: None });
+        }
+    }
+}
+
+/// query 中可以交给 SQL 的条件
+fn backup_filter(query: &BackupQuery) -> BackupFilter<'_> {
+    BackupFilter {
+        text: query.text.as_deref().map(str::trim).filter(|t| !t.is_empty()),
+        tag: query.tag.as_deref().map(str::trim).filter(|t| !t.is_empty()),
+        min_size: query.min_size,
+        max_size: query.max_size,
+    }
+}
+
+/// 检查 SQL 处理不了的日期和元信息条件
+fn matches_query(backup: &Backup, query: &BackupQuery) -> bool {
+    if query.from.is_some_and(|from| backup.save_time < from) || query.to.is_some_and(|to| backup.save_time > to) {
+        return false;
+    }
+    if query.meta.is_empty() {
+        return true;
+    }
+    let meta = backup.more_info.clone().unwrap_or_default();
+    query.meta.iter().all(|f| meta.matches(f))
+}
+
+/// 解密查看 .salakieli 文件，backup_id 为空时读取当前存档
+#[tauri::command]
+pub async fn read_salakieli(args: ReadSalakieli) -> Reply<ReadSalakieli> {
+    let ReadSalakieli { backup_id, file_name } = args;
+    debug!("[read_salakieli] {:?} {}", backup_id, file_name);
+    if !salakieli::is_supported(&file_name) {
+        return Err(msg("salakieli.unsupported").with("file", file_name).into());
+    }
+
+    let opened = match backup_id {
+        Some(id) => {
+            let mut conn = connect().await?;
+            let backup = Db::get_backup_by_id(&mut conn, id)
+                .await
+                .map_err(|e| {
+                    error!("获取存档出错: {}", e);
+                    e.to_string()
+                })?
+                .ok_or_else(|| String::from(msg("backup.not_found").with("id", id)))?;
+            let backup_dir = backup.resolve_dir(Path::new(&path::get_data_path()?));
+            Some(open_backup_dir(backup_dir, crypto::resolve_passphrase(None)).await?)
+        }
+        None => None,
+    };
+    let save_dir = match &opened {
+        Some(opened) => opened.path().to_path_buf(),
+        None => PathBuf::from(get_save_path()?),
+    };
+
+    salakieli::read(&save_dir, &file_name)
+        .map_err(|e| {
+            error!("解密 {} 失败: {}", file_name, e);
+            e.to_string()
+        })?
+        .ok_or_else(|| msg("salakieli.not_found").with("path", save_dir.join(&file_name).display()).into())
+}
+
+/// 导出备份为单个文件，给出口令或开启了备份加密时导出的文件是加密的
+/// 返回导出的文件路径，取消选择时返回 None
+#[tauri::command]
+pub async fn export_backup(app: AppHandle, args: ExportBackup) -> Reply<ExportBackup> {
+    let ExportBackup { backup_id, passphrase } = args;
+    debug!("[export_backup] {}", backup_id);
+    let mut conn = connect().await?;
+    let backup = Db::get_backup_by_id(&mut conn, backup_id)
+        .await
+        .map_err(|e| {
+            error!("获取存档出错: {}", e);
+            e.to_string()
+        })?
+        .ok_or_else(|| String::from(msg("backup.not_found").with("id", backup_id)))?;
+    let passphrases = ExportPassphrases::resolve(passphrase)?;
+
+    let default_name = format!("{}.svld", export_name(&backup));
+    let Some(target) = app
+        .dialog()
+        .file()
+        .set_title(i18n::t("export.dialog_title"))
+        .set_file_name(&default_name)
+        .add_filter(i18n::t("file.filter_backup"), &["svld"])
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let target = target.into_path().map_err(|e| e.to_string())?;
+
+    write_export(backup, target.clone(), &passphrases).await?;
+    info!("备份 {} 已导出到 {}", backup_id, target.display());
+    Ok(Some(target.to_string_lossy().to_string()))
+}
+
+/// 导出时用到的口令
+pub(crate) struct ExportPassphrases {
+    /// 解密本地加密的备份，优先用已解锁的口令
+    open: Option<String>,
+    /// 加密导出的文件，没有指定时开启了加密就用已解锁的口令
+    export: Option<String>,
+}
+
+impl ExportPassphrases {
+    pub(crate) fn resolve(passphrase: Option<String>) -> Result<ExportPassphrases, String> {
+        let given = passphrase.filter(|p| !p.is_empty());
+        let session = crypto::resolve_passphrase(None);
+        let export = match given.clone() {
+            Some(p) => Some(p),
+            None if EncryptionConfig::load().enabled => {
+                Some(session.clone().ok_or_else(|| String::from(msg("export.locked")))?)
+            }
+            None => None,
+        };
+        Ok(ExportPassphrases { open: session.or(given), export })
+    }
+}
+
+/// 导出文件的默认名称，不含扩展名
+pub(crate) fn export_name(backup: &Backup) -> String {
+    backup.name.clone().unwrap_or_else(|| backup.dir_name())
+}
+
+/// 把备份打包成 target，需要时再加密
+pub(crate) async fn write_export(backup: Backup, target: PathBuf, passphrases: &ExportPassphrases) -> Result<(), String> {
+    let backup_dir = backup.resolve_dir(Path::new(&path::get_data_path()?));
+    let opened = open_backup_dir(backup_dir, passphrases.open.clone()).await?;
+    let export_passphrase = passphrases.export.clone();
+    tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+        match export_passphrase {
+            Some(passphrase) => {
+                let zip_path = target.with_extension("zip.part");
+                let result = archive::pack(opened.path(), &backup, &zip_path)
+                    .and_then(|_| crypto::encrypt_file(&zip_path, &target, &passphrase));
+                let _ = std::fs::remove_file(&zip_path);
+                result
+            }
+            None => archive::pack(opened.path(), &backup, &target),
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| {
+        error!("导出备份失败: {}", e);
+        e.to_string()
+    })
+}
+
+/// 解压导出的文件到备份目录，返回其中的数据库记录和新的文件夹名
+fn import_file(file: &Path, data_root: &Path, passphrase: Option<&str>) -> anyhow::Result<(Backup, String)> {
+    // 放在备份目录下，解压完直接改名
+    let temp = data_root.join(format!(".import_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&temp);
+    std::fs::create_dir_all(&temp)?;
+
+    let result = (|| {
+        let zip_path = if crypto::is_encrypted(file) {
+            let zip_path = temp.join("backup.zip");
+            crypto::decrypt_file(file, &zip_path, passphrase.ok_or(CryptoError::PassphraseRequired)?)?;
+            zip_path
+        } else {
+            file.to_path_buf()
+        };
+        let record = archive::unpack(&zip_path, &temp.join("save00"))?;
+        if record.digest.len() < 12 {
+            anyhow::bail!("备份记录已损坏");
+        }
+
+        let dir_name = format!("backup_{}", &record.digest[..12]);
+        let target = data_root.join(&dir_name);
+        if target.exists() {
+            anyhow::bail!("该备份已存在: {}", record.name.as_deref().unwrap_or(&dir_name));
+        }
+        std::fs::rename(temp.join("save00"), &target)?;
+        Ok((record, dir_name))
+    })();
+    let _ = std::fs::remove_dir_all(&temp);
+    result
+}
+
+/// 导入 export_backup 导出的文件，path 为空时弹出文件选择框
+#[tauri::command]
+pub async fn import_backup(app: AppHandle, args: ImportBackup) -> Reply<ImportBackup> {
+    let ImportBackup { path, passphrase } = args;
+    debug!("[import_backup] {:?}", path);
+    let _busy = busy::begin()?;
+    // 和 save_backup 一样，开启加密后导入的备份也要立即加密
+    let encryption = EncryptionConfig::load();
+    if encryption.enabled && crypto::resolve_passphrase(None).is_none() {
+        return Err(msg("backup.locked").into());
+    }
+    let file = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let picked = app
+                .dialog()
+                .file()
+                .set_title(i18n::t("import.dialog_title"))
+                .add_filter(i18n::t("file.filter_backup"), &["svld", "zip"])
+                .blocking_pick_file();
+            match picked {
+                Some(picked) => picked.into_path().map_err(|e| e.to_string())?,
+                None => return Ok(ImportOutcome::Cancelled),
+            }
+        }
+    };
+
+    let given = passphrase.filter(|p| !p.is_empty());
+    let passphrase = crypto::resolve_passphrase(given.clone());
+    if crypto::is_encrypted(&file) && passphrase.is_none() {
+        return Ok(ImportOutcome::PassphraseRequired { path: file.to_string_lossy().to_string() });
+    }
+
+    let data_root = PathBuf::from(path::get_data_path()?);
+    let import_root = data_root.clone();
+    let file_path = file.to_string_lossy().to_string();
+    let imported = tokio::task::spawn_blocking(move || import_file(&file, &import_root, passphrase.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?;
+    let (record, dir_name) = match imported {
+        Ok(imported) => imported,
+        // 已解锁的口令和导出时用的不一样，让用户输入
+        Err(e) if given.is_none() && CryptoError::DecryptFailed.matches(&e) => {
+            return Ok(ImportOutcome::PassphraseRequired { path: file_path });
+        }
+        Err(e) => {
+            error!("导入备份失败: {}", e);
+            return Err(e.to_string());
+        }
+    };
+
+    let mut conn = connect().await?;
+    if Db::get_backup_by_digest(&mut conn, &record.digest).await.map_err(|e| e.to_string())?.is_some() {
+        let _ = remove_directory(&data_root.join(&dir_name));
+        return Err(msg("import.exists").into());
+    }
+    // 父节点 id 只在原来的机器上有意义，导入的备份成为根节点
+    let backup = Backup { id: 0, path: dir_name.clone(), parent_id: None, ..record };
+    let id = Db::store_backup(&backup, &mut conn).await.map_err(|e| {
+        error!("保存导入的备份记录失败: {}", e);
+        e.to_string()
+    })?;
+    Db::set_tags(&mut conn, id, &backup.tags).await.map_err(|e| e.to_string())?;
+
+    if let (true, Some(passphrase)) = (encryption.enabled, crypto::resolve_passphrase(None)) {
+        seal_backup_dir(data_root.join(&dir_name), passphrase).await?;
+    }
+
+    let message: String = msg("import.imported").with("name", backup.name.clone().unwrap_or_else(|| i18n::t("common.unnamed"))).into();
+    info!("{}", message);
+    events::emit(BackupCreated { backup: Backup { id, ..backup } });
+    Ok(ImportOutcome::Imported { message })
+}
+
+#[tauri::command]
+pub fn get_encryption_status() -> Reply<GetEncryptionStatus> {
+    let config = EncryptionConfig::load();
+    Ok(EncryptionStatus {
+        enabled: config.enabled,
+        has_passphrase: config.verifier.is_some(),
+        unlocked: crypto::is_unlocked(),
+    })
+}
+
+#[tauri::command]
+pub fn unlock_encryption(args: UnlockEncryption) -> Reply<UnlockEncryption> {
+    let UnlockEncryption { passphrase } = args;
+    crypto::unlock(&passphrase).map_err(|e| {
+        info!("解锁备份加密失败: {}", e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+pub fn lock_encryption() -> Reply<LockEncryption> {
+    crypto::lock();
+    Ok(())
+}
+
+/// 对所有备份文件夹执行加密或解密，返回处理成功的数量
+async fn convert_all_backups(passphrase: String, seal: bool) -> Result<(usize, usize), String> {
+    let mut conn = connect().await?;
+    let backups = Db::get_all_backup(&mut conn).await.map_err(|e| {
+        error!("获取已有存档失败: {}", e);
+        e.to_string()
+    })?;
+    let data_root = PathBuf::from(path::get_data_path()?);
+    let dirs: Vec<PathBuf> = backups.iter().map(|b| b.resolve_dir(&data_root)).filter(|d| d.exists()).collect();
+
+    tokio::task::spawn_blocking(move || {
+        let (mut done, mut failed) = (0, 0);
+        for dir in dirs {
+            let result = if seal { crypto::seal_dir(&dir, &passphrase) } else { crypto::unseal_dir(&dir, &passphrase) };
+            match result {
+                Ok(()) => done += 1,
+                Err(e) => {
+                    error!("处理备份 {} 失败: {}", dir.display(), e);
+                    failed += 1;
+                }
+            }
+        }
+        (done, failed)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 开启备份加密，并加密已有的备份，返回加密的备份数
+#[tauri::command]
+pub async fn enable_encryption(args: EnableEncryption) -> Reply<EnableEncryption> {
+    let EnableEncryption { passphrase } = args;
+    info!("[enable_encryption]");
+    if passphrase.chars().count() < 8 {
+        return Err(msg("encryption.too_short").into());
+    }
+    let mut config = EncryptionConfig::load();
+    if !config.verify(&passphrase) {
+        // 旧口令加密的备份还在时不能换口令，否则它们再也打不开
+        let data_root = PathBuf::from(path::get_data_path()?);
+        let mut conn = connect().await?;
+        let backups = Db::get_all_backup(&mut conn).await.map_err(|e| e.to_string())?;
+        if backups.iter().any(|b| crypto::is_sealed(&b.resolve_dir(&data_root))) {
+            return Err(msg("encryption.mismatch").into());
+        }
+        config.set_passphrase(&passphrase).map_err(|e| e.to_string())?;
+    }
+    config.enabled = true;
+    config.save()?;
+    crypto::unlock(&passphrase).map_err(|e| e.to_string())?;
+
+    let (sealed, failed) = convert_all_backups(passphrase, true).await?;
+    info!("备份加密已开启，加密了 {} 个备份，失败 {} 个", sealed, failed);
+    if failed > 0 {
+        return Err(msg("encryption.enable_partial").with("count", failed).into());
+    }
+    Ok(sealed)
+}
+
+/// 关闭备份加密，已加密的备份全部解密回普通文件夹
+#[tauri::command]
+pub async fn disable_encryption(args: DisableEncryption) -> Reply<DisableEncryption> {
+    let DisableEncryption { passphrase } = args;
+    info!("[disable_encryption]");
+    let mut config = EncryptionConfig::load();
+    if !config.verify(&passphrase) {
+        return Err(msg("encryption.wrong_passphrase").into());
+    }
+    config.enabled = false;
+    config.save()?;
+
+    let (unsealed, failed) = convert_all_backups(passphrase, false).await?;
+    info!("备份加密已关闭，解密了 {} 个备份，失败 {} 个", unsealed, failed);
+    if failed > 0 {
+        return Err(msg("encryption.disable_partial").with("count", failed).into());
+    }
+    // 没有加密的备份了，口令也不再需要
+    config.verifier = None;
+    config.save()?;
+    crypto::lock();
+    Ok(unsealed)
+}
+
+/// 更换备份目录，并把已有备份一起搬过去
+#[tauri::command]
+pub async fn move_data_path(args: MoveDataPath) -> Reply<MoveDataPath> {
+    let MoveDataPath { new_path } = args;
+    info!("[move_data_path] 迁移备份目录到: {}", new_path);
+    let _busy = busy::begin()?;
+    relocate::relocate_repository(&new_path).await.map_err(|e| {
+        error!("迁移备份目录失败: {}", e);
+        e
+    })
+}
 
 #[cfg(test)]
 mod tests{
@@ -0,0 +1,601 @@
+/// 备份加密：口令经 Argon2id 派生密钥，内容用 XChaCha20-Poly1305 分块加密
+///
+/// 文件格式：MAGIC | m_cost | t_cost | p_cost (u32 LE) | salt | nonce 前缀，之后是若干 [u32 LE 长度 | 密文] 块。
+/// 每块的 nonce 为 前缀 + 块序号 (u32 BE) + 是否最后一块，文件头作为附加数据参与认证，
+/// 口令错误、内容被改动或文件被截断都会解密失败
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use anyhow::{anyhow, bail, Context};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use crate::storage::archive;
+use crate::units::i18n::{msg, Message};
+use crate::units::settings::ConfigManager;
+
+const MAGIC: &[u8; 8] = b"SVLDENC1";
+const SALT_LEN: usize = 16;
+const PREFIX_LEN: usize = 19;
+const HEADER_LEN: usize = MAGIC.len() + 12 + SALT_LEN + PREFIX_LEN;
+const CHUNK_SIZE: usize = 1 << 20;
+const TAG_LEN: usize = 16;
+/// 文件头里的 Argon2 参数上限，防止构造的文件耗尽内存
+const MAX_M_COST: u32 = 1 << 20;
+const MAX_T_COST: u32 = 16;
+
+/// 加密后的备份文件夹里只有这一个文件
+pub const SEALED_NAME: &str = "backup.svlde";
+/// 加密时先写到这个名字，写完再改名成 SEALED_NAME
+const SEALING_NAME: &str = "backup.svlde.part";
+pub const CONFIG_SECTION: &str = "encryption";
+
+/// 本次运行中已解锁的口令，只保存在内存里
+static SESSION: Mutex<Option<String>> = Mutex::new(None);
+static TEMP_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// 会显示给用户的加密错误，转成字符串时是可翻译的消息，调用方按类型区分而不是比较文字
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CryptoError {
+    /// 还没有设置口令
+    NoPassphrase,
+    /// 解锁时口令和设置的不一致
+    WrongPassphrase,
+    /// 备份已加密但没有口令
+    PassphraseRequired,
+    /// 解密失败，口令错误或内容被改动
+    DecryptFailed,
+    NotEncrypted,
+    BadParams,
+    Truncated,
+    Corrupted,
+}
+
+impl CryptoError {
+    pub fn message(self) -> Message {
+        msg(match self {
+            CryptoError::NoPassphrase => "encryption.no_passphrase",
+            CryptoError::WrongPassphrase => "encryption.wrong_passphrase",
+            CryptoError::PassphraseRequired => "encryption.passphrase_required",
+            CryptoError::DecryptFailed => "crypto.decrypt_failed",
+            CryptoError::NotEncrypted => "crypto.not_encrypted",
+            CryptoError::BadParams => "crypto.bad_params",
+            CryptoError::Truncated => "crypto.truncated",
+            CryptoError::Corrupted => "crypto.corrupted",
+        })
+    }
+
+    /// 错误链中是否有这种加密错误
+    pub fn matches(self, error: &anyhow::Error) -> bool {
+        error.downcast_ref::<CryptoError>() == Some(&self)
+    }
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from(self.message()))
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
+/// 设置中的 encryption 配置段
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    /// 新备份和导出是否加密
+    pub enabled: bool,
+    /// 口令的 Argon2 哈希 (PHC 字符串)，解锁时用来校验口令
+    pub verifier: Option<String>,
+}
+
+impl EncryptionConfig {
+    pub fn load() -> EncryptionConfig {
+        ConfigManager::load_section(CONFIG_SECTION).unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        ConfigManager::save_section(CONFIG_SECTION, Some(self))
+    }
+
+    pub fn verify(&self, passphrase: &str) -> bool {
+        let Some(verifier) = &self.verifier else {
+            return false;
+        };
+        PasswordHash::new(verifier)
+            .map(|hash| Argon2::default().verify_password(passphrase.as_bytes(), &hash).is_ok())
+            .unwrap_or(false)
+    }
+
+    pub fn set_passphrase(&mut self, passphrase: &str) -> anyhow::Result<()> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let salt = SaltString::encode_b64(&salt).map_err(|e| anyhow!("生成盐值失败: {}", e))?;
+        let hash = Argon2::default()
+            .hash_password(passphrase.as_bytes(), &salt)
+            .map_err(|e| anyhow!("计算口令哈希失败: {}", e))?;
+        self.verifier = Some(hash.to_string());
+        Ok(())
+    }
+}
+
+/// 校验口令并在本次运行中记住它
+pub fn unlock(passphrase: &str) -> anyhow::Result<()> {
+    let config = EncryptionConfig::load();
+    if config.verifier.is_none() {
+        bail!(CryptoError::NoPassphrase);
+    }
+    if !config.verify(passphrase) {
+        bail!(CryptoError::WrongPassphrase);
+    }
+    *SESSION.lock().unwrap() = Some(passphrase.to_string());
+    Ok(())
+}
+
+pub fn lock() {
+    *SESSION.lock().unwrap() = None;
+}
+
+pub fn is_unlocked() -> bool {
+    SESSION.lock().unwrap().is_some()
+}
+
+/// 调用方给出的口令优先，其次是已解锁的口令
+pub fn resolve_passphrase(given: Option<String>) -> Option<String> {
+    given.filter(|p| !p.is_empty()).or_else(|| SESSION.lock().unwrap().clone())
+}
+
+/// 测试时用最小的参数，否则每次派生密钥都要几秒
+fn default_params() -> Params {
+    if cfg!(test) {
+        Params::new(Params::MIN_M_COST, 1, 1, None).unwrap()
+    } else {
+        Params::default()
+    }
+}
+
+fn cipher(passphrase: &str, salt: &[u8], params: Params) -> anyhow::Result<XChaCha20Poly1305> {
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("派生密钥失败: {}", e))?;
+    Ok(XChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+fn nonce(prefix: &[u8], index: u32, last: bool) -> XNonce {
+    let mut nonce = [0u8; 24];
+    nonce[..PREFIX_LEN].copy_from_slice(prefix);
+    nonce[PREFIX_LEN..PREFIX_LEN + 4].copy_from_slice(&index.to_be_bytes());
+    nonce[23] = last as u8;
+    XNonce::from(nonce)
+}
+
+/// 读满 buf 或读到文件末尾，返回读到的字节数
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// 文件是否是加密格式
+pub fn is_encrypted(path: &Path) -> bool {
+    let mut magic = [0u8; MAGIC.len()];
+    File::open(path).and_then(|mut f| f.read_exact(&mut magic)).is_ok() && &magic == MAGIC
+}
+
+pub fn encrypt_file(src: &Path, dst: &Path, passphrase: &str) -> anyhow::Result<()> {
+    let params = default_params();
+    let mut salt = [0u8; SALT_LEN];
+    let mut prefix = [0u8; PREFIX_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut prefix);
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&params.m_cost().to_le_bytes());
+    header.extend_from_slice(&params.t_cost().to_le_bytes());
+    header.extend_from_slice(&params.p_cost().to_le_bytes());
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&prefix);
+    let cipher = cipher(passphrase, &salt, params)?;
+
+    let mut reader = BufReader::new(File::open(src).with_context(|| format!("无法读取 {}", src.display()))?);
+    let mut writer = BufWriter::new(File::create(dst).with_context(|| format!("无法创建 {}", dst.display()))?);
+    writer.write_all(&header)?;
+
+    // 多读一块才能知道当前块是不是最后一块
+    let mut current = vec![0u8; CHUNK_SIZE];
+    let mut next = vec![0u8; CHUNK_SIZE];
+    let mut len = read_full(&mut reader, &mut current)?;
+    let mut index = 0u32;
+    loop {
+        let next_len = read_full(&mut reader, &mut next)?;
+        let last = next_len == 0;
+        let ciphertext = cipher
+            .encrypt(&nonce(&prefix, index, last), Payload { msg: &current[..len], aad: &header })
+            .map_err(|_| anyhow!("加密失败"))?;
+        writer.write_all(&(ciphertext.len() as u32).to_le_bytes())?;
+        writer.write_all(&ciphertext)?;
+        if last {
+            break;
+        }
+        std::mem::swap(&mut current, &mut next);
+        len = next_len;
+        index = index.checked_add(1).context("文件过大")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// 解密失败时不会留下输出文件
+pub fn decrypt_file(src: &Path, dst: &Path, passphrase: &str) -> anyhow::Result<()> {
+    let result = decrypt_to(src, dst, passphrase);
+    if result.is_err() {
+        let _ = fs::remove_file(dst);
+    }
+    result
+}
+
+fn decrypt_to(src: &Path, dst: &Path, passphrase: &str) -> anyhow::Result<()> {
+    let mut reader = BufReader::new(File::open(src).with_context(|| format!("无法读取 {}", src.display()))?);
+    let mut header = [0u8; HEADER_LEN];
+    reader.read_exact(&mut header).map_err(|_| CryptoError::NotEncrypted)?;
+    if &header[..MAGIC.len()] != MAGIC {
+        bail!(CryptoError::NotEncrypted);
+    }
+    let field = |i: usize| {
+        let start = MAGIC.len() + i * 4;
+        u32::from_le_bytes(header[start..start + 4].try_into().unwrap())
+    };
+    let (m_cost, t_cost, p_cost) = (field(0), field(1), field(2));
+    if m_cost > MAX_M_COST || t_cost > MAX_T_COST {
+        bail!(CryptoError::BadParams);
+    }
+    let params = Params::new(m_cost, t_cost, p_cost, None).map_err(|_| CryptoError::BadParams)?;
+    let salt = &header[MAGIC.len() + 12..MAGIC.len() + 12 + SALT_LEN];
+    let prefix = &header[HEADER_LEN - PREFIX_LEN..];
+    let cipher = cipher(passphrase, salt, params)?;
+
+    let mut writer = BufWriter::new(File::create(dst).with_context(|| format!("无法创建 {}", dst.display()))?);
+    let mut index = 0u32;
+    loop {
+        let mut len = [0u8; 4];
+        reader.read_exact(&mut len).map_err(|_| CryptoError::Truncated)?;
+        let len = u32::from_le_bytes(len) as usize;
+        if len > CHUNK_SIZE + TAG_LEN {
+            bail!(CryptoError::Corrupted);
+        }
+        let mut ciphertext = vec![0u8; len];
+        reader.read_exact(&mut ciphertext).map_err(|_| CryptoError::Truncated)?;
+
+        let payload = |msg| Payload { msg, aad: &header[..] };
+        let (plain, last) = match cipher.decrypt(&nonce(prefix, index, false), payload(&ciphertext)) {
+            Ok(plain) => (plain, false),
+            Err(_) => {
+                let plain = cipher
+                    .decrypt(&nonce(prefix, index, true), payload(&ciphertext))
+                    .map_err(|_| CryptoError::DecryptFailed)?;
+                (plain, true)
+            }
+        };
+        writer.write_all(&plain)?;
+        if last {
+            if reader.read(&mut [0u8; 1])? != 0 {
+                bail!(CryptoError::Corrupted);
+            }
+            break;
+        }
+        index = index.checked_add(1).ok_or(CryptoError::Corrupted)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// 备份文件夹是否已加密
+pub fn is_sealed(backup_dir: &Path) -> bool {
+    backup_dir.join(SEALED_NAME).is_file()
+}
+
+/// 和 backup_dir 在同一个目录下的临时路径，保证之后可以直接改名
+fn sibling(backup_dir: &Path, suffix: &str) -> PathBuf {
+    let name = backup_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    backup_dir.with_file_name(format!(".{}.{}", name, suffix))
+}
+
+/// 把备份文件夹打包加密成单个文件，并删除明文。
+/// 加密文件先在文件夹里写完并改名就位，之后才删明文，中途中断时由 recover 收尾
+pub fn seal_dir(backup_dir: &Path, passphrase: &str) -> anyhow::Result<()> {
+    if is_sealed(backup_dir) {
+        return Ok(());
+    }
+    let zip_path = sibling(backup_dir, "zip");
+    let sealing_path = backup_dir.join(SEALING_NAME);
+    let _ = fs::remove_file(&sealing_path);
+    let result = archive::pack_dir(backup_dir, &zip_path).and_then(|_| encrypt_file(&zip_path, &sealing_path, passphrase));
+    let _ = fs::remove_file(&zip_path);
+    if let Err(e) = result {
+        let _ = fs::remove_file(&sealing_path);
+        return Err(e);
+    }
+
+    fs::rename(&sealing_path, backup_dir.join(SEALED_NAME))?;
+    remove_plaintext(backup_dir)
+}
+
+fn has_plaintext(backup_dir: &Path) -> bool {
+    fs::read_dir(backup_dir)
+        .map(|entries| entries.flatten().any(|entry| entry.file_name() != SEALED_NAME))
+        .unwrap_or(false)
+}
+
+/// 加密文件已经就位，删除文件夹里其余的内容
+fn remove_plaintext(backup_dir: &Path) -> anyhow::Result<()> {
+    for entry in fs::read_dir(backup_dir)? {
+        let entry = entry?;
+        if entry.file_name() == SEALED_NAME {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            fs::remove_dir_all(entry.path())?;
+        } else {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// 把加密的备份还原成普通文件夹。
+/// 明文完整解压后才把加密的文件夹挪开、换上明文，任何一步中断都能由 recover 恢复
+pub fn unseal_dir(backup_dir: &Path, passphrase: &str) -> anyhow::Result<()> {
+    if !is_sealed(backup_dir) {
+        return Ok(());
+    }
+    let zip_path = sibling(backup_dir, "zip");
+    let plain_dir = sibling(backup_dir, "plain");
+    let old_dir = sibling(backup_dir, "old");
+    let _ = fs::remove_dir_all(&plain_dir);
+    let _ = fs::remove_dir_all(&old_dir);
+    let result = decrypt_file(&backup_dir.join(SEALED_NAME), &zip_path, passphrase)
+        .and_then(|_| archive::unpack_dir(&zip_path, &plain_dir));
+    let _ = fs::remove_file(&zip_path);
+    if let Err(e) = result {
+        let _ = fs::remove_dir_all(&plain_dir);
+        return Err(e);
+    }
+
+    fs::rename(backup_dir, &old_dir)?;
+    if let Err(e) = fs::rename(&plain_dir, backup_dir) {
+        let _ = fs::rename(&old_dir, backup_dir);
+        let _ = fs::remove_dir_all(&plain_dir);
+        return Err(e.into());
+    }
+    fs::remove_dir_all(&old_dir)?;
+    Ok(())
+}
+
+/// 启动时处理加密或解密中断后留下的临时文件
+pub fn recover() {
+    match crate::units::path::get_data_path() {
+        Ok(data_root) => recover_dir(Path::new(&data_root)),
+        Err(e) => warn!("获取备份目录失败，跳过加密临时文件的清理: {}", e),
+    }
+}
+
+fn recover_dir(data_root: &Path) {
+    let Ok(entries) = fs::read_dir(data_root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let result = if name.starts_with("backup_") && path.is_dir() {
+            let sealing_path = path.join(SEALING_NAME);
+            if is_sealed(&path) && has_plaintext(&path) {
+                remove_plaintext(&path)
+            } else if !is_sealed(&path) && sealing_path.exists() {
+                fs::remove_file(&sealing_path).map_err(Into::into)
+            } else {
+                continue;
+            }
+        } else {
+            match name.strip_prefix('.').and_then(|n| n.rsplit_once('.')) {
+                Some((dir_name, suffix @ ("zip" | "plain" | "old" | "svlde")))
+                    if dir_name.starts_with("backup_") && path.exists() =>
+                {
+                    recover_sibling(&data_root.join(dir_name), &path, suffix)
+                }
+                _ => continue,
+            }
+        };
+        match result {
+            Ok(()) => info!("已处理中断的加密操作留下的 {}", name),
+            Err(e) => warn!("处理 {} 失败: {}", path.display(), e),
+        }
+    }
+}
+
+fn recover_sibling(backup_dir: &Path, path: &Path, suffix: &str) -> anyhow::Result<()> {
+    let dir_missing = || !backup_dir.exists();
+    match suffix {
+        // 打包或解密的中间文件
+        "zip" => fs::remove_file(path)?,
+        // 明文只在完整解压后才会出现在备份文件夹不在的情况下
+        "plain" if dir_missing() => fs::rename(path, backup_dir)?,
+        "plain" => fs::remove_dir_all(path)?,
+        // 解密时挪开的旧文件夹：明文还没换上时先换上，都没有时放回原处
+        "old" if dir_missing() => {
+            let plain_dir = sibling(backup_dir, "plain");
+            if plain_dir.exists() {
+                fs::rename(&plain_dir, backup_dir)?;
+                fs::remove_dir_all(path)?;
+            } else {
+                fs::rename(path, backup_dir)?;
+            }
+        }
+        "old" => fs::remove_dir_all(path)?,
+        // 旧版本先删掉明文才把加密文件放进去，文件夹已经空了时加密文件是唯一的副本
+        "svlde" if dir_missing() || fs::read_dir(backup_dir)?.next().is_none() => {
+            fs::create_dir_all(backup_dir)?;
+            fs::rename(path, backup_dir.join(SEALED_NAME))?;
+        }
+        "svlde" => fs::remove_file(path)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// 可以直接读取的备份内容，加密的备份会解密到临时目录，用完即删
+pub struct OpenedBackup {
+    path: PathBuf,
+    temp_root: Option<PathBuf>,
+}
+
+impl OpenedBackup {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for OpenedBackup {
+    fn drop(&mut self) {
+        if let Some(root) = &self.temp_root {
+            let _ = fs::remove_dir_all(root);
+        }
+    }
+}
+
+pub fn open_backup(backup_dir: &Path, passphrase: Option<&str>) -> anyhow::Result<OpenedBackup> {
+    if !is_sealed(backup_dir) {
+        return Ok(OpenedBackup { path: backup_dir.to_path_buf(), temp_root: None });
+    }
+    let passphrase = passphrase.ok_or(CryptoError::PassphraseRequired)?;
+
+    let root = std::env::temp_dir().join(format!(
+        "svld_open_{}_{}",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(&root)?;
+    let opened = OpenedBackup { path: root.join("save00"), temp_root: Some(root.clone()) };
+    let zip_path = root.join("backup.zip");
+    decrypt_file(&backup_dir.join(SEALED_NAME), &zip_path, passphrase)?;
+    archive::unpack_dir(&zip_path, &opened.path)?;
+    fs::remove_file(&zip_path)?;
+    Ok(opened)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+
+        // 刚好两块多一点，覆盖多块和最后一块的情况
+        let data: Vec<u8> = (0..CHUNK_SIZE * 2 + 100).map(|i| (i % 251) as u8).collect();
+        let plain = root.join("plain.bin");
+        let sealed = root.join("sealed.bin");
+        let opened = root.join("opened.bin");
+        fs::write(&plain, &data).unwrap();
+
+        encrypt_file(&plain, &sealed, "口令").unwrap();
+        assert!(is_encrypted(&sealed));
+        assert!(!is_encrypted(&plain));
+        decrypt_file(&sealed, &opened, "口令").unwrap();
+        assert_eq!(fs::read(&opened).unwrap(), data);
+
+        // 口令错误时不留下输出
+        fs::remove_file(&opened).unwrap();
+        let err = decrypt_file(&sealed, &opened, "wrong").unwrap_err();
+        assert!(CryptoError::DecryptFailed.matches(&err));
+        assert!(!opened.exists());
+
+        // 去掉最后一块
+        let bytes = fs::read(&sealed).unwrap();
+        let truncated = root.join("truncated.bin");
+        fs::write(&truncated, &bytes[..HEADER_LEN + 2 * (4 + CHUNK_SIZE + TAG_LEN)]).unwrap();
+        assert!(decrypt_file(&truncated, &opened, "口令").is_err());
+    }
+
+    #[test]
+    fn test_seal_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let backup_dir = root.join("backup_0123456789ab");
+        fs::create_dir_all(backup_dir.join("world")).unwrap();
+        fs::write(backup_dir.join("player.xml"), "<Entity/>").unwrap();
+        fs::write(backup_dir.join("world").join("area_0.bin"), [1u8, 2, 3]).unwrap();
+
+        seal_dir(&backup_dir, "口令").unwrap();
+        assert!(is_sealed(&backup_dir));
+        assert!(!backup_dir.join("player.xml").exists());
+
+        assert!(open_backup(&backup_dir, None).is_err());
+        assert!(open_backup(&backup_dir, Some("wrong")).is_err());
+        let temp_root = {
+            let opened = open_backup(&backup_dir, Some("口令")).unwrap();
+            assert_eq!(fs::read_to_string(opened.path().join("player.xml")).unwrap(), "<Entity/>");
+            opened.temp_root.clone().unwrap()
+        };
+        assert!(!temp_root.exists());
+
+        unseal_dir(&backup_dir, "口令").unwrap();
+        assert!(!is_sealed(&backup_dir));
+        assert_eq!(fs::read(backup_dir.join("world").join("area_0.bin")).unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_recover() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let sealed_file = |dir: &Path| {
+            fs::create_dir_all(dir).unwrap();
+            fs::write(dir.join("player.xml"), "<Entity/>").unwrap();
+            seal_dir(dir, "口令").unwrap();
+            fs::read(dir.join(SEALED_NAME)).unwrap()
+        };
+
+        // 加密文件已就位但明文还没删完
+        let a = root.join("backup_aaaaaaaaaaaa");
+        sealed_file(&a);
+        fs::write(a.join("player.xml"), "<Entity/>").unwrap();
+        // 加密到一半
+        let b = root.join("backup_bbbbbbbbbbbb");
+        fs::create_dir_all(&b).unwrap();
+        fs::write(b.join("player.xml"), "<Entity/>").unwrap();
+        fs::write(b.join(SEALING_NAME), [0u8; 4]).unwrap();
+        // 解密时旧文件夹已挪开，明文还没换上
+        let c = root.join("backup_cccccccccccc");
+        sealed_file(&c);
+        fs::rename(&c, sibling(&c, "old")).unwrap();
+        fs::create_dir_all(sibling(&c, "plain")).unwrap();
+        fs::write(sibling(&c, "plain").join("player.xml"), "<Entity/>").unwrap();
+        // 旧版本删掉明文后中断，加密文件还在外面
+        let d = root.join("backup_dddddddddddd");
+        let data = sealed_file(&d);
+        fs::remove_dir_all(&d).unwrap();
+        fs::write(sibling(&d, "svlde"), &data).unwrap();
+        fs::write(sibling(&d, "zip"), [0u8; 4]).unwrap();
+
+        recover_dir(root);
+        assert!(is_sealed(&a) && !a.join("player.xml").exists());
+        assert!(!is_sealed(&b) && !b.join(SEALING_NAME).exists() && b.join("player.xml").exists());
+        assert!(!is_sealed(&c) && c.join("player.xml").exists());
+        assert!(is_sealed(&d));
+        let names: Vec<_> = fs::read_dir(root).unwrap().flatten().map(|e| e.file_name()).collect();
+        assert_eq!(names.len(), 4, "{:?}", names);
+    }
+}
@@ -0,0 +1,143 @@
+/// FastLZ 解压，Noita 的 .png_petri 地形块用它压缩
+/// 格式见 https://github.com/ariya/FastLZ，第一个字节的高 3 位是压缩等级（1 或 2）
+use anyhow::{anyhow, bail};
+
+/// 解压后大小的上限。一个地形块解压后只有几百 KB，文件头写得更大时说明文件已损坏
+const MAX_OUTPUT: usize = 16 << 20;
+/// 每个输入字节最多解出的字节数，等级 2 的长匹配每个 255 只多输出 255 个字节
+const MAX_RATIO: usize = 256;
+
+/// 解压整块数据，expected_len 为文件头里记录的原始大小
+pub fn decompress(input: &[u8], expected_len: usize) -> anyhow::Result<Vec<u8>> {
+    let Some(first) = input.first() else {
+        return Ok(Vec::new());
+    };
+    // 文件头不可信，按它分配内存之前先检查
+    if expected_len > MAX_OUTPUT || expected_len > input.len().saturating_mul(MAX_RATIO) {
+        bail!("FastLZ 文件头中的大小无效: {}", expected_len);
+    }
+    match (first >> 5) + 1 {
+        1 => decompress_level(input, expected_len, false),
+        2 => decompress_level(input, expected_len, true),
+        level => Err(anyhow!("未知的 FastLZ 压缩等级: {}", level)),
+    }
+}
+
+fn decompress_level(input: &[u8], expected_len: usize, level2: bool) -> anyhow::Result<Vec<u8>> {
+    let mut out: Vec<u8> = Vec::with_capacity(expected_len);
+    let mut ip = 0;
+    let next = |ip: &mut usize| -> anyhow::Result<u8> {
+        let byte = *input.get(*ip).ok_or_else(|| anyhow!("FastLZ 数据被截断"))?;
+        *ip += 1;
+        Ok(byte)
+    };
+
+    // 第一个指令的高 3 位是压缩等级，不参与解码
+    let mut ctrl = (next(&mut ip)? & 31) as usize;
+    loop {
+        if ctrl < 32 {
+            // 字面量：后面 ctrl + 1 个字节原样输出
+            let end = ip + ctrl + 1;
+            let literal = input.get(ip..end).ok_or_else(|| anyhow!("FastLZ 数据被截断"))?;
+            check_room(&out, literal.len(), expected_len)?;
+            out.extend_from_slice(literal);
+            ip = end;
+        } else {
+            // 回溯引用：从已输出的数据中复制
+            let mut len = (ctrl >> 5) - 1;
+            let mut ofs = (ctrl & 31) << 8;
+            if len == 6 {
+                if level2 {
+                    loop {
+                        let code = next(&mut ip)?;
+                        len += code as usize;
+                        if code != 255 {
+                            break;
+                        }
+                    }
+                } else {
+                    len += next(&mut ip)? as usize;
+                }
+            }
+            let code = next(&mut ip)?;
+            ofs += code as usize;
+            if level2 && code == 255 && ofs == (31 << 8) + 255 {
+                // 远距离引用
+                ofs = ((next(&mut ip)? as usize) << 8) + next(&mut ip)? as usize + 8191;
+            }
+            let start = out
+                .len()
+                .checked_sub(ofs + 1)
+                .ok_or_else(|| anyhow!("FastLZ 引用越界"))?;
+            // 先检查再复制，长匹配的长度可以远远超过文件头记录的大小
+            check_room(&out, len + 3, expected_len)?;
+            // 引用区域可能和输出重叠，只能逐字节复制
+            for i in 0..len + 3 {
+                let byte = out[start + i];
+                out.push(byte);
+            }
+        }
+
+        if ip >= input.len() {
+            break;
+        }
+        ctrl = next(&mut ip)? as usize;
+    }
+
+    if out.len() != expected_len {
+        bail!("FastLZ 解压后大小不符: {} != {}", out.len(), expected_len);
+    }
+    Ok(out)
+}
+
+fn check_room(out: &[u8], len: usize, expected_len: usize) -> anyhow::Result<()> {
+    if out.len() + len > expected_len {
+        bail!("FastLZ 解压后大小超过文件头记录的 {}", expected_len);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal_and_match() {
+        // 等级 1：字面量 "abc"，然后引用 3 个字节前的位置复制 6 个字节
+        let input = [0x02, b'a', b'b', b'c', (4 << 5) as u8, 2];
+        assert_eq!(decompress(&input, 9).unwrap(), b"abcabcabc");
+
+        // 等级 2：同样的数据，首字节带等级标记
+        let input = [0x20 | 0x02, b'a', b'b', b'c', (4 << 5) as u8, 2];
+        assert_eq!(decompress(&input, 9).unwrap(), b"abcabcabc");
+    }
+
+    #[test]
+    fn test_long_match() {
+        // 等级 2 的长匹配：len = 7 时后面跟额外长度
+        let input = [0x20, b'x', (7 << 5) as u8, 255, 3, 0];
+        let out = decompress(&input, 1 + 6 + 255 + 3 + 3).unwrap();
+        assert!(out.iter().all(|b| *b == b'x'));
+
+        assert!(decompress(&[0x00, b'a', (2 << 5) as u8, 5], 4).is_err());
+    }
+
+    #[test]
+    fn test_rejects_bad_header() {
+        let input = [0x02, b'a', b'b', b'c', (4 << 5) as u8, 2];
+        assert!(decompress(&input, usize::MAX).is_err());
+        assert!(decompress(&input, input.len() * MAX_RATIO + 1).is_err());
+        // 实际输出比文件头记录的多
+        assert!(decompress(&input, 3).is_err());
+    }
+
+    #[test]
+    fn test_long_match_over_header() {
+        // 一长串 255 让匹配长度达到上亿字节，必须在复制之前就拒绝
+        let mut input = vec![0x20, b'x', (7 << 5) as u8];
+        input.resize(input.len() + (1 << 20), 255);
+        input.extend_from_slice(&[3, 0]);
+        let err = decompress(&input, 64).unwrap_err();
+        assert!(err.to_string().contains("超过"));
+    }
+}
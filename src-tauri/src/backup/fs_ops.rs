@@ -5,6 +5,7 @@ use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::{Path, PathBuf};
 use log::error;
+use crate::units::i18n::msg;
 
 // 定义一个中间结构体用于存储文件元数据，以便在内存中排序
 struct FileMeta {
@@ -191,15 +192,53 @@ pub fn copy_directory(src: &Path, dst: &Path) -> Result<()> {
         }
 
         // 返回错误，包含失败文件数量
+        return Err(anyhow::anyhow!(String::from(msg("file.copy_partial").with("count", errors.len()))));
+    }
+
+    Ok(())
+}
+
+/// 校验复制结果：两边的文件列表和每个文件的大小必须一致
+/// 跨盘复制不会保留修改时间，所以这里不能直接比较 calculate_hash
+pub fn verify_copy(src: &Path, dst: &Path) -> Result<()> {
+    let src_files = file_manifest(src);
+    let dst_files = file_manifest(dst);
+
+    if src_files.len() != dst_files.len() {
         return Err(anyhow::anyhow!(
-            "备份完成，但有 {} 个文件复制失败 (详情请查看日志)",
-            errors.len()
+            "校验失败: 源目录有 {} 个文件，目标目录有 {} 个文件",
+            src_files.len(),
+            dst_files.len()
         ));
     }
 
+    for (s, d) in src_files.iter().zip(dst_files.iter()) {
+        if s != d {
+            return Err(anyhow::anyhow!("校验失败: 文件 {} 不一致", s.0));
+        }
+    }
+
     Ok(())
 }
 
+/// 目录下所有文件的 (相对路径, 大小)，按路径排序
+pub fn file_manifest(root: &Path) -> Vec<(String, u64)> {
+    let mut files: Vec<(String, u64)> = WalkDir::new(root)
+        .skip_hidden(false)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let rel_path = path.strip_prefix(root).ok()?.to_string_lossy().replace('\\', "/");
+            let len = entry.metadata().ok()?.len();
+            Some((rel_path, len))
+        })
+        .collect();
+    files.par_sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    files
+}
+
 pub fn remove_directory(path: &Path) -> Result<()> {
     if path.exists() {
         fs::remove_dir_all(path).with_context(|| format!("无法删除目录: {:?}", path))?;
@@ -0,0 +1,77 @@
+/// 备份之间的继承关系
+/// 每次备份都会记下当前存档是从哪个备份继续玩出来的（head），还原旧备份后再备份就形成了分支
+use chrono::Local;
+use log::{debug, error};
+use sqlx::SqliteConnection;
+use svld_shared::commands::GetBackupGraph;
+use svld_shared::Reply;
+use crate::db::Db;
+use crate::units::db_path;
+
+const HEAD_KEY: &str = "head";
+
+/// 当前存档所基于的备份
+pub async fn get_head(conn: &mut SqliteConnection) -> anyhow::Result<Option<i32>> {
+    let head = Db::get_state(conn, HEAD_KEY).await?;
+    Ok(head.and_then(|v| v.parse().ok()))
+}
+
+pub async fn set_head(conn: &mut SqliteConnection, id: Option<i32>) -> anyhow::Result<()> {
+    Db::set_state(conn, HEAD_KEY, id.map(|id| id.to_string()).as_deref()).await
+}
+
+/// 新备份的父节点：上次还原或保存的备份；如果已经开了新的一局，则取同一局最近的备份
+pub async fn resolve_parent(conn: &mut SqliteConnection, run_id: Option<&str>) -> anyhow::Result<Option<i32>> {
+    if let Some(head) = get_head(conn).await? {
+        if let Some(head_backup) = Db::get_backup_by_id(conn, head).await? {
+            let same_run = match (head_backup.run_id.as_deref(), run_id) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            };
+            if same_run {
+                return Ok(Some(head));
+            }
+        }
+    }
+
+    match run_id {
+        // 新的一局没有备份时就是一棵新树的根
+        Some(run_id) => Ok(Db::get_latest_backup_of_run(conn, run_id).await?.map(|b| b.id)),
+        None => Ok(Db::get_latest_backup(conn).await?.map(|b| b.id)),
+    }
+}
+
+pub use svld_shared::backup::{BackupGraph, BackupNode};
+
+#[tauri::command]
+pub async fn get_backup_graph() -> Reply<GetBackupGraph> {
+    debug!("[get_backup_graph] {}", Local::now());
+    let db_path = db_path::get_db_path().map_err(|e| {
+        error!("获取数据库路径失败: {}", e);
+        e.to_string()
+    })?;
+    let mut conn = Db::new(db_path).await.map_err(|e| {
+        error!("建立数据库连接出错: {}", e);
+        e.to_string()
+    })?;
+
+    let backups = Db::get_all_backup(&mut conn).await.map_err(|e| {
+        error!("获取已有存档失败: {}", e);
+        e.to_string()
+    })?;
+    let head = get_head(&mut conn).await.map_err(|e| e.to_string())?;
+
+    let mut nodes: Vec<BackupNode> = backups
+        .into_iter()
+        .map(|b| BackupNode {
+            id: b.id,
+            name: b.name,
+            save_time: b.save_time,
+            parent_id: b.parent_id,
+            pinned: b.pinned,
+        })
+        .collect();
+    nodes.sort_by(|a, b| a.save_time.cmp(&b.save_time));
+
+    Ok(BackupGraph { nodes, head })
+}
@@ -0,0 +1,301 @@
+/// 从 world/ 下的地形块渲染地图缩略图
+/// area_*.bin 里只有实体，地形像素都在 world_{x}_{y}.png_petri 中，x、y 是块左上角的世界坐标
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, bail, Context};
+use log::warn;
+use crate::backup::fastlz;
+
+/// 缩略图最长边的像素数
+const MAX_SIZE: usize = 512;
+
+/// 最多读取的地形块数，每个块解码时要占 1 MB 左右，读得越多保存备份越慢
+const MAX_CHUNKS: usize = 2048;
+
+/// 解码后的地形块
+struct Chunk {
+    x: i64,
+    y: i64,
+    width: usize,
+    height: usize,
+    /// 每个像素的 RGBA
+    pixels: Vec<[u8; 4]>,
+}
+
+/// 解压后的数据都是大端序
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| anyhow!("地形块数据被截断"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> anyhow::Result<u32> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+/// 文件名形如 world_-512_1024.png_petri
+fn chunk_position(file_name: &str) -> Option<(i64, i64)> {
+    let coords = file_name.strip_prefix("world_")?.strip_suffix(".png_petri")?;
+    let (x, y) = coords.split_once('_')?;
+    Some((x.parse().ok()?, y.parse().ok()?))
+}
+
+/// 没有游戏的材质表，按材质名生成一个稳定的颜色
+fn material_color(name: &str) -> [u8; 4] {
+    match name {
+        "air" => [0, 0, 0, 0],
+        "water" | "water_static" | "water_salt" => [56, 120, 200, 255],
+        "lava" => [230, 90, 20, 255],
+        _ => {
+            // FNV-1a
+            let hash = name
+                .bytes()
+                .fold(0x811c9dc5u32, |h, b| (h ^ b as u32).wrapping_mul(0x01000193));
+            let [r, g, b, _] = hash.to_le_bytes();
+            // 偏暗的土色，和地形的观感接近
+            [60 + r / 3, 50 + g / 3, 40 + b / 3, 255]
+        }
+    }
+}
+
+fn decode_chunk(data: &[u8], x: i64, y: i64) -> anyhow::Result<Chunk> {
+    if data.len() < 8 {
+        bail!("地形块文件过短");
+    }
+    let compressed_len = u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    let decompressed_len = u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize;
+    let compressed = data
+        .get(8..8 + compressed_len)
+        .ok_or_else(|| anyhow!("地形块文件被截断"))?;
+    let raw = fastlz::decompress(compressed, decompressed_len)?;
+
+    let mut reader = Reader { data: &raw, pos: 0 };
+    let _version = reader.u32()?;
+    let width = reader.u32()? as usize;
+    let height = reader.u32()? as usize;
+    let cells = reader.bytes(width * height)?;
+
+    let mut materials = Vec::new();
+    for _ in 0..reader.u32()? {
+        let len = reader.u32()? as usize;
+        materials.push(String::from_utf8_lossy(reader.bytes(len)?).to_string());
+    }
+    let palette: Vec<[u8; 4]> = materials.iter().map(|m| material_color(m)).collect();
+
+    let mut custom_colors = Vec::new();
+    for _ in 0..reader.u32()? {
+        let [a, r, g, b] = reader.u32()?.to_be_bytes();
+        custom_colors.push([r, g, b, a]);
+    }
+
+    // 最高位表示该像素使用自定义颜色（按出现顺序依次取），低 7 位是材质序号
+    let mut custom = custom_colors.into_iter();
+    let pixels = cells
+        .iter()
+        .map(|cell| {
+            if cell & 0x80 != 0 {
+                custom.next().unwrap_or([255, 0, 255, 255])
+            } else {
+                palette.get((cell & 0x7f) as usize).copied().unwrap_or([0, 0, 0, 0])
+            }
+        })
+        .collect();
+
+    Ok(Chunk { x, y, width, height, pixels })
+}
+
+/// 列出地形块文件，太多时只保留离玩家（没有位置时离原点）最近的 MAX_CHUNKS 个
+fn chunk_files(world_dir: &Path, player: Option<(f64, f64)>) -> Vec<(i64, i64, PathBuf)> {
+    let Ok(entries) = fs::read_dir(world_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(i64, i64, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let (x, y) = chunk_position(&entry.file_name().to_string_lossy())?;
+            Some((x, y, entry.path()))
+        })
+        .collect();
+    if files.len() > MAX_CHUNKS {
+        let (cx, cy) = player.map(|(x, y)| (x as i64, y as i64)).unwrap_or((0, 0));
+        files.sort_by_key(|(x, y, _)| (x - cx).unsigned_abs().max((y - cy).unsigned_abs()));
+        files.truncate(MAX_CHUNKS);
+    }
+    files
+}
+
+fn read_chunk(path: &Path, x: i64, y: i64) -> Option<Chunk> {
+    let data = fs::read(path).ok()?;
+    match decode_chunk(&data, x, y) {
+        Ok(chunk) => Some(chunk),
+        Err(e) => {
+            warn!("跳过无法解析的地形块 {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// 把一个块按 scale 缩小后画进缩略图，超出范围的部分裁掉
+fn paint_chunk(image: &mut [[u8; 4]], width: usize, height: usize, scale: usize, origin: (i64, i64), chunk: &Chunk) {
+    let (Ok(left), Ok(top)) = (usize::try_from(chunk.x - origin.0), usize::try_from(chunk.y - origin.1)) else {
+        return;
+    };
+    for oy in top.div_ceil(scale)..(top + chunk.height).div_ceil(scale).min(height) {
+        let sy = oy * scale - top;
+        for ox in left.div_ceil(scale)..(left + chunk.width).div_ceil(scale).min(width) {
+            let sx = ox * scale - left;
+            image[oy * width + ox] = chunk.pixels[sy * chunk.width + sx];
+        }
+    }
+}
+
+/// 渲染存档目录下已探索区域的缩略图，并标出玩家位置；没有地形块时返回 None。
+/// 块是逐个解码、缩小后立即丢弃的，探索范围再大内存占用也只有一个块
+pub fn render_thumbnail(save_dir: &Path, player: Option<(f64, f64)>) -> anyhow::Result<Option<Vec<u8>>> {
+    let files = chunk_files(&save_dir.join("world"), player);
+    let mut chunks = files.iter().filter_map(|(x, y, path)| read_chunk(path, *x, *y));
+    let Some(first) = chunks.next() else {
+        return Ok(None);
+    };
+
+    // 游戏里的块大小都一样，用第一个块的尺寸和所有块的坐标确定地图范围
+    let min_x = files.iter().map(|(x, _, _)| *x).min().unwrap_or(0);
+    let min_y = files.iter().map(|(_, y, _)| *y).min().unwrap_or(0);
+    let max_x = files.iter().map(|(x, _, _)| *x).max().unwrap_or(0) + first.width as i64;
+    let max_y = files.iter().map(|(_, y, _)| *y).max().unwrap_or(0) + first.height as i64;
+    let world_w = (max_x - min_x) as usize;
+    let world_h = (max_y - min_y) as usize;
+
+    // 每 scale 个世界像素取一个
+    let scale = world_w.max(world_h).div_ceil(MAX_SIZE).max(1);
+    let width = world_w.div_ceil(scale);
+    let height = world_h.div_ceil(scale);
+    let mut image = vec![[0u8; 4]; width * height];
+
+    paint_chunk(&mut image, width, height, scale, (min_x, min_y), &first);
+    drop(first);
+    for chunk in chunks {
+        paint_chunk(&mut image, width, height, scale, (min_x, min_y), &chunk);
+    }
+
+    if let Some((px, py)) = player {
+        let cx = ((px as i64 - min_x) / scale as i64) as isize;
+        let cy = ((py as i64 - min_y) / scale as i64) as isize;
+        for dy in -3isize..=3 {
+            for dx in -3isize..=3 {
+                let (x, y) = (cx + dx, cy + dy);
+                if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+                    continue;
+                }
+                let edge = dx.abs() == 3 || dy.abs() == 3;
+                image[y as usize * width + x as usize] = if edge { [255, 255, 255, 255] } else { [230, 30, 30, 255] };
+            }
+        }
+    }
+
+    let mut png_data = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut png_data, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().context("写入 PNG 头失败")?;
+        writer
+            .write_image_data(&image.concat())
+            .context("写入 PNG 数据失败")?;
+    }
+    Ok(Some(png_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 只用字面量指令的 FastLZ 等级 1 编码，足够构造测试数据
+    fn fastlz_literal(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for block in data.chunks(32) {
+            out.push((block.len() - 1) as u8);
+            out.extend_from_slice(block);
+        }
+        out
+    }
+
+    fn petri_file(width: u32, height: u32, cells: &[u8], materials: &[&str], colors: &[u32]) -> Vec<u8> {
+        let mut raw = Vec::new();
+        for v in [24, width, height] {
+            raw.extend_from_slice(&v.to_be_bytes());
+        }
+        raw.extend_from_slice(cells);
+        raw.extend_from_slice(&(materials.len() as u32).to_be_bytes());
+        for m in materials {
+            raw.extend_from_slice(&(m.len() as u32).to_be_bytes());
+            raw.extend_from_slice(m.as_bytes());
+        }
+        raw.extend_from_slice(&(colors.len() as u32).to_be_bytes());
+        for c in colors {
+            raw.extend_from_slice(&c.to_be_bytes());
+        }
+
+        let compressed = fastlz_literal(&raw);
+        let mut file = Vec::new();
+        file.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        file.extend_from_slice(&(raw.len() as u32).to_le_bytes());
+        file.extend_from_slice(&compressed);
+        file
+    }
+
+    #[test]
+    fn test_render_thumbnail() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        fs::create_dir_all(dir.join("world")).unwrap();
+
+        // 两个 4x4 的块，左边全是 air，右边第一个像素是自定义颜色
+        let mut cells = vec![1u8; 16];
+        cells[0] = 0x80;
+        fs::write(dir.join("world").join("world_0_0.png_petri"), petri_file(4, 4, &[0; 16], &["air"], &[])).unwrap();
+        fs::write(
+            dir.join("world").join("world_4_0.png_petri"),
+            petri_file(4, 4, &cells, &["air", "rock_static"], &[0xff102030]),
+        )
+        .unwrap();
+        fs::write(dir.join("world").join("area_0.bin"), [0u8; 4]).unwrap();
+
+        let png_data = render_thumbnail(dir, None).unwrap().unwrap();
+        let decoder = png::Decoder::new(png_data.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!((info.width, info.height), (8, 4));
+        assert_eq!(&buf[0..4], &[0, 0, 0, 0]);
+        assert_eq!(&buf[16..20], &[0x10, 0x20, 0x30, 0xff]);
+        assert_eq!(&buf[20..24], &material_color("rock_static"));
+
+        assert!(render_thumbnail(&dir.join("missing"), None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_chunk_limit() {
+        let tmp = tempfile::tempdir().unwrap();
+        let world = tmp.path().join("world");
+        fs::create_dir_all(&world).unwrap();
+        for i in 0..=MAX_CHUNKS as i64 {
+            fs::write(world.join(format!("world_{}_0.png_petri", i * 512)), []).unwrap();
+        }
+
+        let files = chunk_files(&world, Some((512.0 * MAX_CHUNKS as f64, 0.0)));
+        assert_eq!(files.len(), MAX_CHUNKS);
+        assert!(files.iter().all(|(x, _, _)| *x != 0));
+    }
+}
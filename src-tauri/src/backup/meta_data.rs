@@ -1,14 +1,166 @@
-use std::path::{Path, PathBuf};
+use std::fs;
+use std::path::Path;
+use svld_shared::backup::biome_at;
+use crate::backup::{mods, salakieli};
 
-pub struct MeteData{
-    location : (i64,i64) ,
-    hp : f64,
-    orbs: i8,
+pub use svld_shared::backup::MetaData;
+
+/// 读取存档目录（save00 或它的备份）下的 player.xml 和 world_state.xml
+/// 文件缺失或格式不对时对应字段为 None，不影响备份本身
+pub fn read(save_dir: &Path) -> MetaData {
+    let mut meta = MetaData::default();
+
+    if let Ok(player) = fs::read_to_string(save_dir.join("player.xml")) {
+        // 游戏内部血量单位是显示值的 1/25
+        meta.hp = xml_attr(&player, "DamageModelComponent", "hp")
+            .and_then(|v| v.parse::<f64>().ok())
+            .map(|v| v * 25.0);
+        meta.max_hp = xml_attr(&player, "DamageModelComponent", "max_hp")
+            .and_then(|v| v.parse::<f64>().ok())
+            .map(|v| v * 25.0);
+        meta.gold = xml_attr(&player, "WalletComponent", "money").and_then(|v| v.parse().ok());
+        let x = xml_attr(&player, "_Transform", "position.x").and_then(|v| v.parse().ok());
+        let y = xml_attr(&player, "_Transform", "position.y").and_then(|v| v.parse().ok());
+        meta.location = x.zip(y);
+        meta.biome = meta.location.and_then(biome_at);
+    }
+
+    if let Ok(world_state) = fs::read_to_string(save_dir.join("world_state.xml")) {
+        meta.orbs = xml_block(&world_state, "orbs_found_thisrun")
+            .map(|block| block.matches("<primitive").count() as u32);
+        // 形如 ??STA/sessions/20240102-123456，取最后一段
+        meta.session = xml_attr(&world_state, "WorldStateComponent", "session_stat_file")
+            .and_then(|file| file.rsplit(['/', '\\']).next())
+            .filter(|id| !id.is_empty())
+            .map(|id| id.to_string());
+    }
+
+    meta.mods = mods::read_enabled_mods(save_dir);
+
+    // 不同版本存放的位置不一样，两个文件都找一遍
+    for file_name in ["magic_numbers.salakieli", "session_numbers.salakieli"] {
+        let Ok(Some(xml)) = salakieli::read(save_dir, file_name) else {
+            continue;
+        };
+        if meta.seed.is_none() {
+            meta.seed = attr_in(&xml, "WORLD_SEED").and_then(|v| v.parse().ok());
+        }
+        if meta.ng_plus.is_none() {
+            meta.ng_plus = attr_in(&xml, "NEW_GAME_PLUS_COUNT").and_then(|v| v.parse().ok());
+        }
+    }
+
+    if meta.session.is_none() {
+        meta.session = latest_session(save_dir);
+    }
+
+    meta
 }
 
-impl MeteData {
-    fn get_meta_data(path : &Path){
-        let player : PathBuf = path.join("/player.xml");
+/// stats/sessions 下最新的会话文件，文件名形如 20240102-123456_stats.xml
+fn latest_session(save_dir: &Path) -> Option<String> {
+    fs::read_dir(save_dir.join("stats").join("sessions"))
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.split('_').next().filter(|id| !id.is_empty()).map(|id| id.to_string())
+        })
+        .max()
+}
+
+/// 取第一个 <tag ...> 上的属性值，Noita 的存档 xml 结构很简单，不值得引入完整的解析器
+pub(crate) fn xml_attr<'a>(xml: &'a str, tag: &str, name: &str) -> Option<&'a str> {
+    let open = format!("<{}", tag);
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        // 避免 <Foo 匹配到 <FooBar
+        if after.starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/') {
+            let end = after.find('>').unwrap_or(after.len());
+            return attr_in(&after[..end], name);
+        }
+        rest = after;
     }
+    None
 }
 
+/// 在一段标签文本中查找 name="value"
+pub(crate) fn attr_in<'a>(tag_body: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!("{}=\"", name);
+    let mut rest = tag_body;
+    while let Some(pos) = rest.find(&pattern) {
+        // 要求属性名前面是空白，避免 hp 匹配到 max_hp
+        let boundary = rest[..pos].chars().last().map_or(true, |c| c.is_whitespace());
+        let value_start = pos + pattern.len();
+        if boundary {
+            let value = &rest[value_start..];
+            return value.find('"').map(|end| &value[..end]);
+        }
+        rest = &rest[value_start..];
+    }
+    None
+}
+
+/// 取 <tag>...</tag> 之间的内容
+pub(crate) fn xml_block<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = xml[start..].find(&format!("</{}>", tag))? + start;
+    Some(&xml[start..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xml_attr() {
+        let xml = r#"<Entity name="DEBUG_NAME:player">
+            <_Transform position.x="227.5" position.y="-84" rotation="0" />
+            <DamageModelComponentExtra hp="1" />
+            <DamageModelComponent max_hp="4" hp="2.5" >
+            </DamageModelComponent>
+        </Entity>"#;
+        assert_eq!(xml_attr(xml, "_Transform", "position.x"), Some("227.5"));
+        assert_eq!(xml_attr(xml, "DamageModelComponent", "hp"), Some("2.5"));
+        assert_eq!(xml_attr(xml, "DamageModelComponent", "max_hp"), Some("4"));
+        assert_eq!(xml_attr(xml, "WalletComponent", "money"), None);
+    }
+
+    #[test]
+    fn test_biome() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("player.xml"), r#"<Entity><_Transform position.x="100" position.y="13000" /></Entity>"#).unwrap();
+        assert_eq!(read(dir.path()).biome.as_deref(), Some("The Work"));
+    }
+
+    #[test]
+    fn test_session() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        fs::create_dir_all(dir.join("stats").join("sessions")).unwrap();
+        fs::write(dir.join("stats").join("sessions").join("20240101-100000_stats.xml"), "").unwrap();
+        fs::write(dir.join("stats").join("sessions").join("20240102-120000_stats.xml"), "").unwrap();
+        assert_eq!(read(dir).session.as_deref(), Some("20240102-120000"));
+
+        fs::write(
+            dir.join("world_state.xml"),
+            r#"<Entity><WorldStateComponent session_stat_file="??STA/sessions/20240103-080000" time="0.5"></WorldStateComponent></Entity>"#,
+        )
+        .unwrap();
+        assert_eq!(read(dir).session.as_deref(), Some("20240103-080000"));
+
+        salakieli::write(dir, "magic_numbers.salakieli", r#"<MagicNumbers WORLD_SEED="987654321" ></MagicNumbers>"#).unwrap();
+        salakieli::write(dir, "session_numbers.salakieli", r#"<SessionNumbers NEW_GAME_PLUS_COUNT="1" ></SessionNumbers>"#).unwrap();
+        let meta = read(dir);
+        assert_eq!(meta.seed, Some(987654321));
+        assert_eq!(meta.ng_plus, Some(1));
+    }
+
+    #[test]
+    fn test_orbs() {
+        let xml = "<WorldStateComponent><orbs_found_thisrun><primitive value=\"0\"/><primitive value=\"3\"/></orbs_found_thisrun></WorldStateComponent>";
+        let block = xml_block(xml, "orbs_found_thisrun").unwrap();
+        assert_eq!(block.matches("<primitive").count(), 2);
+    }
+}
@@ -1,4 +1,17 @@
 pub mod fs_ops;
 pub mod service;
 pub mod commands;
+pub mod batch;
+pub mod relocate;
+pub mod history;
+pub mod runs;
+pub mod mods;
+pub mod salakieli;
+pub mod restore;
+pub mod fastlz;
+pub mod map;
+pub mod crypto;
+pub mod auto;
+pub mod space;
+pub mod busy;
 mod meta_data;
\ No newline at end of file
@@ -0,0 +1,66 @@
+/// 存档使用的 mod 列表
+/// save00/mod_config.xml 里按加载顺序列出所有 mod，enabled="1" 的才会生效
+use std::fs;
+use std::path::Path;
+use crate::backup::meta_data::attr_in;
+
+pub use svld_shared::backup::ModDiff;
+
+/// save00 下记录 mod 列表的文件
+pub const MOD_CONFIG: &str = "mod_config.xml";
+
+/// 读取存档目录下启用的 mod，按加载顺序排列；没有 mod_config.xml 时返回 None
+pub fn read_enabled_mods(save_dir: &Path) -> Option<Vec<String>> {
+    let content = fs::read_to_string(save_dir.join(MOD_CONFIG)).ok()?;
+    Some(parse_enabled_mods(&content))
+}
+
+fn parse_enabled_mods(xml: &str) -> Vec<String> {
+    let mut mods = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find("<Mod") {
+        let after = &rest[start + 4..];
+        let end = after.find('>').unwrap_or(after.len());
+        // 跳过根节点 <Mods>
+        if after.starts_with(|c: char| c.is_whitespace()) {
+            let body = &after[..end];
+            if attr_in(body, "enabled") == Some("1") {
+                if let Some(name) = attr_in(body, "name").filter(|n| !n.is_empty()) {
+                    mods.push(name.to_string());
+                }
+            }
+        }
+        rest = &after[end..];
+    }
+    mods
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_compare() {
+        let xml = r#"<Mods>
+  <Mod enabled="1" name="nightmare" settings_fold_open="0" workshop_item_id="0" >
+  </Mod>
+  <Mod enabled="0" name="daily_practice" settings_fold_open="0" workshop_item_id="0" >
+  </Mod>
+  <Mod enabled="1" name="grahamsperks" settings_fold_open="0" workshop_item_id="2200000000" >
+  </Mod>
+</Mods>"#;
+        let backup = parse_enabled_mods(xml);
+        assert_eq!(backup, vec!["nightmare", "grahamsperks"]);
+
+        assert!(ModDiff::compare(&backup, &backup).is_empty());
+
+        let current = vec!["grahamsperks".to_string(), "nightmare".to_string(), "copis_things".to_string()];
+        let diff = ModDiff::compare(&backup, &current);
+        assert_eq!(diff.added, vec!["copis_things"]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.reordered, vec!["nightmare", "grahamsperks"]);
+
+        let diff = ModDiff::compare(&backup, &[]);
+        assert_eq!(diff.removed, vec!["nightmare", "grahamsperks"]);
+    }
+}
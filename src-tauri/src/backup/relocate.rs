@@ -0,0 +1,237 @@
+/// 迁移备份仓库：把所有 backup_* 文件夹搬到新的备份根目录
+/// 迁移过程记录在数据库旁边的 relocate.json 中，中途中断后用相同的目标路径再次调用即可继续
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use crate::backup::fs_ops::*;
+use crate::backup::space;
+use crate::db::{Backup, Db};
+use crate::units::i18n::msg;
+use crate::units::{db_path, path};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MoveJournal {
+    from: String,
+    to: String,
+    /// 已经迁移完成的文件夹名
+    done: Vec<String>,
+}
+
+fn journal_path() -> Result<PathBuf, String> {
+    let db_path = db_path::get_db_path().map_err(|e| e.to_string())?;
+    Ok(Path::new(&db_path).with_file_name("relocate.json"))
+}
+
+fn load_journal(path: &Path) -> Result<Option<MoveJournal>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("读取迁移记录失败: {}", e))?;
+    let journal = serde_json::from_str(&content).map_err(|e| format!("迁移记录已损坏: {}", e))?;
+    Ok(Some(journal))
+}
+
+fn save_journal(path: &Path, journal: &MoveJournal) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(journal).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| format!("写入迁移记录失败: {}", e))
+}
+
+/// 移动单个备份文件夹，可以重复调用
+fn move_backup_dir(src: &Path, dst: &Path) -> Result<(), String> {
+    if !src.exists() {
+        if dst.exists() {
+            // 上次已经搬完，只是还没来得及记录
+            return Ok(());
+        }
+        warn!("备份文件夹不存在，跳过: {}", src.display());
+        return Ok(());
+    }
+
+    if dst.exists() {
+        // 上次复制到一半被中断
+        if verify_copy(src, dst).is_ok() {
+            return remove_directory(src).map_err(|e| e.to_string());
+        }
+        remove_directory(dst).map_err(|e| e.to_string())?;
+    }
+
+    // 同一个分区直接改名
+    if fs::rename(src, dst).is_ok() {
+        return Ok(());
+    }
+
+    // 跨分区：复制、校验，再删除原文件夹
+    let required = calculate_directory_size(src).map_err(|e| e.to_string())? as u64;
+    space::ensure_space(dst, required).map_err(String::from)?;
+    copy_directory(src, dst).map_err(|e| e.to_string())?;
+    verify_copy(src, dst).map_err(|e| {
+        error!("迁移校验失败 {}: {}", src.display(), e);
+        e.to_string()
+    })?;
+    remove_directory(src).map_err(|e| e.to_string())
+}
+
+/// 还没迁移的备份文件夹的总大小，包括数据库里没有记录的 backup_* 文件夹
+fn pending_size(backups: &[Backup], old_root: &Path, done: &[String]) -> u64 {
+    let mut dirs: Vec<PathBuf> = backups
+        .iter()
+        .filter(|backup| !done.contains(&backup.dir_name()))
+        .map(|backup| backup.resolve_dir(old_root))
+        .collect();
+    if let Ok(entries) = fs::read_dir(old_root) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+            if name.starts_with("backup_") && path.is_dir() && !done.contains(&name) && !dirs.contains(&path) {
+                dirs.push(path);
+            }
+        }
+    }
+    dirs.iter()
+        .filter(|dir| dir.exists())
+        .map(|dir| calculate_directory_size(dir).unwrap_or(0) as u64)
+        .sum()
+}
+
+/// 把备份仓库迁移到 new_root，并更新数据库记录和配置
+pub async fn relocate_repository(new_root: &str) -> Result<(), String> {
+    let journal_file = journal_path()?;
+    let journal = match load_journal(&journal_file)? {
+        Some(journal) if journal.to != new_root => {
+            return Err(msg("relocate.pending").with("path", journal.to).into());
+        }
+        Some(journal) => {
+            info!("继续上次未完成的迁移: {} -> {}", journal.from, journal.to);
+            journal
+        }
+        None => MoveJournal {
+            from: path::get_data_path()?,
+            to: new_root.to_string(),
+            done: Vec::new(),
+        },
+    };
+    let mut journal = journal;
+
+    let old_root = PathBuf::from(&journal.from);
+    let new_root_path = PathBuf::from(&journal.to);
+
+    if old_root == new_root_path {
+        return path::set_data_path(new_root);
+    }
+
+    fs::create_dir_all(&new_root_path).map_err(|e| String::from(msg("path.create_failed").with("error", e)))?;
+    let probe_file = new_root_path.join(".write_probe");
+    fs::File::create(&probe_file)
+        .and_then(|_| fs::remove_file(&probe_file))
+        .map_err(|e| String::from(msg("path.not_writable").with("path", new_root_path.display()).with("error", e)))?;
+
+    let db_path = db_path::get_db_path().map_err(|e| e.to_string())?;
+    let mut conn = Db::new(db_path).await.map_err(|e| {
+        error!("建立数据库连接出错: {}", e);
+        e.to_string()
+    })?;
+    let backups = Db::get_all_backup(&mut conn).await.map_err(|e| e.to_string())?;
+
+    // 跨磁盘时每个备份都要复制，开始之前确认目标磁盘放得下全部备份，而不是搬到一半才失败
+    if !space::same_disk(&old_root, &new_root_path) {
+        let required = pending_size(&backups, &old_root, &journal.done);
+        space::ensure_space(&new_root_path, required).map_err(String::from)?;
+    }
+
+    save_journal(&journal_file, &journal)?;
+
+    for backup in &backups {
+        let dir_name = backup.dir_name();
+        if !journal.done.contains(&dir_name) {
+            let src = backup.resolve_dir(&old_root);
+            let dst = new_root_path.join(&dir_name);
+            info!("迁移备份: {} -> {}", src.display(), dst.display());
+            move_backup_dir(&src, &dst)?;
+            journal.done.push(dir_name.clone());
+            save_journal(&journal_file, &journal)?;
+        }
+        // 统一改成相对路径，以后再换目录就不用改数据库了
+        if backup.path != dir_name {
+            Db::update_backup_path(&mut conn, backup.id, &dir_name)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    // 数据库里没有记录的 backup_* 文件夹也一起带走
+    if old_root.exists() {
+        let entries = fs::read_dir(&old_root).map_err(|e| String::from(msg("relocate.read_failed").with("error", e)))?;
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with("backup_") || !entry.path().is_dir() || journal.done.contains(&name) {
+                continue;
+            }
+            move_backup_dir(&entry.path(), &new_root_path.join(&name))?;
+            journal.done.push(name);
+            save_journal(&journal_file, &journal)?;
+        }
+    }
+
+    path::set_data_path(new_root)?;
+    fs::remove_file(&journal_file).map_err(|e| format!("删除迁移记录失败: {}", e))?;
+
+    info!("备份仓库迁移完成: {} -> {}", journal.from, journal.to);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_backup_dir_resume() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let src = root.join("old").join("backup_000000000000");
+        let dst = root.join("new").join("backup_000000000000");
+        fs::create_dir_all(src.join("world")).unwrap();
+        fs::write(src.join("player.xml"), "<Entity/>").unwrap();
+        fs::write(src.join("world").join("area_0.bin"), [1u8, 2, 3]).unwrap();
+
+        // 模拟上次复制到一半被中断
+        fs::create_dir_all(&dst).unwrap();
+        fs::write(dst.join("player.xml"), "<Ent").unwrap();
+
+        move_backup_dir(&src, &dst).unwrap();
+        assert!(!src.exists());
+        assert_eq!(fs::read_to_string(dst.join("player.xml")).unwrap(), "<Entity/>");
+        assert_eq!(fs::read(dst.join("world").join("area_0.bin")).unwrap(), vec![1, 2, 3]);
+
+        // 再调用一次不应出错
+        move_backup_dir(&src, &dst).unwrap();
+    }
+
+    #[test]
+    fn test_pending_size() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        for (name, len) in [("backup_000000000000", 10), ("backup_111111111111", 20), ("backup_222222222222", 40)] {
+            fs::create_dir_all(root.join(name)).unwrap();
+            fs::write(root.join(name).join("player.xml"), vec![0u8; len]).unwrap();
+        }
+        let backup = Backup {
+            id: 1,
+            name: None,
+            digest: "0".repeat(64),
+            size: 10,
+            path: "backup_000000000000".to_string(),
+            save_time: time::OffsetDateTime::UNIX_EPOCH,
+            more_info: None,
+            notes: None,
+            pinned: false,
+            parent_id: None,
+            run_id: None,
+            tags: Vec::new(),
+        };
+
+        // 数据库里的和没有记录的都算，已经迁移过的不算
+        assert_eq!(pending_size(std::slice::from_ref(&backup), root, &[]), 70);
+        assert_eq!(pending_size(&[backup], root, &["backup_222222222222".to_string()]), 30);
+    }
+}
@@ -0,0 +1,232 @@
+/// 按范围还原存档
+/// save00 里既有本局的世界和角色，也有跨局的解锁进度（persistent），还原时可以只替换其中一部分
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use crate::backup::fs_ops::*;
+use crate::units::i18n::msg;
+
+/// 预设范围，其余的值按 save00 下的相对路径处理
+const PRESETS: [(&str, &[&str]); 3] = [
+    // 本局的世界、角色和种子
+    (
+        "world+player",
+        &["world", "player.xml", "world_state.xml", "magic_numbers.salakieli", "session_numbers.salakieli"],
+    ),
+    // 解锁的法术、天赋、成就和标记
+    ("persistent", &["persistent"]),
+    ("stats", &["stats"]),
+];
+
+/// 把范围解析成 save00 下的子路径，None 表示整个存档
+pub fn resolve_scope(scope: &[String]) -> Result<Option<Vec<String>>, String> {
+    if scope.is_empty() || scope.iter().any(|s| s == "full") {
+        return Ok(None);
+    }
+
+    let mut subtrees: Vec<String> = Vec::new();
+    for item in scope {
+        let item = item.trim().replace('\\', "/");
+        let items = match PRESETS.iter().find(|(name, _)| *name == item) {
+            Some((_, subtrees)) => subtrees.iter().map(|s| s.to_string()).collect(),
+            None => {
+                // 只允许 save00 内部的相对路径
+                let valid = !item.is_empty()
+                    && Path::new(&item).components().all(|c| matches!(c, Component::Normal(_)));
+                if !valid {
+                    return Err(msg("restore.invalid_scope").with("scope", item).into());
+                }
+                vec![item.trim_end_matches('/').to_string()]
+            }
+        };
+        for subtree in items {
+            if !subtrees.contains(&subtree) {
+                subtrees.push(subtree);
+            }
+        }
+    }
+    Ok(Some(subtrees))
+}
+
+/// 按 subtrees 还原时是否会覆盖 save00 下的 path
+pub fn restores(subtrees: &Option<Vec<String>>, path: &str) -> bool {
+    match subtrees {
+        None => true,
+        Some(subtrees) => subtrees
+            .iter()
+            .any(|s| path == s || path.strip_prefix(s.as_str()).is_some_and(|rest| rest.starts_with('/'))),
+    }
+}
+
+pub use svld_shared::backup::ScopeConflict;
+
+fn manifest(path: &Path) -> HashMap<String, u64> {
+    if path.is_file() {
+        let len = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        return HashMap::from([(String::new(), len)]);
+    }
+    if !path.exists() {
+        return HashMap::new();
+    }
+    file_manifest(path).into_iter().collect()
+}
+
+/// manifest 中的文件名对应的路径，空的名字表示 root 本身就是这个文件
+fn manifest_path(root: &Path, name: &str) -> PathBuf {
+    if name.is_empty() {
+        root.to_path_buf()
+    } else {
+        root.join(name)
+    }
+}
+
+/// 两个文件的内容是否相同，大小相同时逐块比较，读取失败时当作不同
+fn same_content(a: &Path, b: &Path) -> bool {
+    let (Ok(mut a), Ok(mut b)) = (File::open(a), File::open(b)) else {
+        return false;
+    };
+    let mut buf_a = [0u8; 64 * 1024];
+    let mut buf_b = [0u8; 64 * 1024];
+    loop {
+        let Ok(n) = a.read(&mut buf_a) else {
+            return false;
+        };
+        if n == 0 {
+            return b.read(&mut buf_b).is_ok_and(|m| m == 0);
+        }
+        if b.read_exact(&mut buf_b[..n]).is_err() || buf_a[..n] != buf_b[..n] {
+            return false;
+        }
+    }
+}
+
+/// 比较备份和当前存档，只列出有变化的子路径
+pub fn conflict_summary(backup_dir: &Path, save_dir: &Path, subtrees: &Option<Vec<String>>) -> Vec<ScopeConflict> {
+    let subtrees = match subtrees {
+        Some(subtrees) => subtrees.clone(),
+        None => vec![String::new()],
+    };
+
+    subtrees
+        .into_iter()
+        .filter_map(|subtree| {
+            let backup_root = backup_dir.join(&subtree);
+            let current_root = save_dir.join(&subtree);
+            let backup_files = manifest(&backup_root);
+            let current_files = manifest(&current_root);
+            let conflict = ScopeConflict {
+                // 大小一样时再比较内容
+                overwritten: backup_files
+                    .iter()
+                    .filter(|(name, len)| {
+                        current_files.get(*name).is_some_and(|l| {
+                            l != *len
+                                || !same_content(&manifest_path(&backup_root, name), &manifest_path(&current_root, name))
+                        })
+                    })
+                    .count(),
+                deleted: current_files.keys().filter(|name| !backup_files.contains_key(*name)).count(),
+                added: backup_files.keys().filter(|name| !current_files.contains_key(*name)).count(),
+                subtree: if subtree.is_empty() { "save00".to_string() } else { subtree },
+            };
+            (conflict.overwritten + conflict.deleted + conflict.added > 0).then_some(conflict)
+        })
+        .collect()
+}
+
+fn remove_path(path: &Path) -> anyhow::Result<()> {
+    if path.is_dir() {
+        remove_directory(path)
+    } else if path.exists() {
+        Ok(fs::remove_file(path)?)
+    } else {
+        Ok(())
+    }
+}
+
+/// 用备份替换存档的指定部分，备份里没有的子路径会从存档中删除
+pub fn restore_from_dir(backup_dir: &Path, save_dir: &Path, subtrees: &Option<Vec<String>>) -> anyhow::Result<()> {
+    let Some(subtrees) = subtrees else {
+        remove_path(save_dir)?;
+        if let Some(parent) = save_dir.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        return copy_directory(backup_dir, save_dir);
+    };
+
+    fs::create_dir_all(save_dir)?;
+    for subtree in subtrees {
+        let src = backup_dir.join(subtree);
+        let dst = save_dir.join(subtree);
+        remove_path(&dst)?;
+        if src.is_dir() {
+            copy_directory(&src, &dst)?;
+        } else if src.is_file() {
+            if let Some(parent) = dst.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(&src, &dst)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_scope() {
+        let scope = |items: &[&str]| resolve_scope(&items.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+        assert_eq!(scope(&[]).unwrap(), None);
+        assert_eq!(scope(&["stats", "full"]).unwrap(), None);
+        assert_eq!(
+            scope(&["persistent", "stats", "persistent/flags/"]).unwrap(),
+            Some(vec!["persistent".to_string(), "stats".to_string(), "persistent/flags".to_string()])
+        );
+        assert!(scope(&["../save01"]).is_err());
+        assert!(scope(&["/etc"]).is_err());
+
+        assert!(restores(&None, "mod_config.xml"));
+        assert!(!restores(&scope(&["persistent", "stats"]).unwrap(), "mod_config.xml"));
+        assert!(restores(&scope(&["mod_config.xml"]).unwrap(), "mod_config.xml"));
+        assert!(restores(&scope(&["persistent"]).unwrap(), "persistent/flags/a"));
+        assert!(!restores(&scope(&["persist"]).unwrap(), "persistent/flags/a"));
+    }
+
+    #[test]
+    fn test_partial_restore() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let backup = root.join("backup");
+        let save = root.join("save00");
+        fs::create_dir_all(backup.join("world")).unwrap();
+        fs::create_dir_all(backup.join("persistent").join("flags")).unwrap();
+        fs::write(backup.join("player.xml"), "old player").unwrap();
+        fs::write(backup.join("world").join("area_0.bin"), "old").unwrap();
+        fs::write(backup.join("persistent").join("flags").join("a"), "").unwrap();
+
+        fs::create_dir_all(save.join("world")).unwrap();
+        fs::create_dir_all(save.join("persistent").join("flags")).unwrap();
+        // 大小相同、内容不同的文件也算覆盖
+        fs::write(save.join("player.xml"), "new player").unwrap();
+        fs::write(save.join("world").join("area_1.bin"), "new").unwrap();
+        fs::write(save.join("persistent").join("flags").join("a"), "").unwrap();
+        fs::write(save.join("persistent").join("flags").join("b"), "").unwrap();
+
+        let subtrees = resolve_scope(&["world+player".to_string()]).unwrap();
+        let summary = conflict_summary(&backup, &save, &subtrees);
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0], ScopeConflict { subtree: "world".to_string(), overwritten: 0, deleted: 1, added: 1 });
+        assert_eq!(summary[1].subtree, "player.xml");
+        assert_eq!(summary[1].overwritten, 1);
+
+        restore_from_dir(&backup, &save, &subtrees).unwrap();
+        assert_eq!(fs::read_to_string(save.join("player.xml")).unwrap(), "old player");
+        assert!(save.join("world").join("area_0.bin").exists());
+        assert!(!save.join("world").join("area_1.bin").exists());
+        // 解锁进度没有被回退
+        assert!(save.join("persistent").join("flags").join("b").exists());
+    }
+}
@@ -0,0 +1,78 @@
+/// 识别备份属于哪一局游戏，并按局分组
+use crate::backup::meta_data::MetaData;
+use crate::db::Backup;
+
+pub use svld_shared::backup::BackupRun;
+
+/// 从存档元信息推出局标识：种子相同的局可能玩了很多次，所以和会话一起使用
+pub fn run_id(meta: &MetaData) -> Option<String> {
+    match (meta.seed, &meta.session) {
+        (Some(seed), Some(session)) => Some(format!("{}-{}", seed, session)),
+        (None, Some(session)) => Some(session.clone()),
+        (Some(seed), None) => Some(seed.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// 按局分组，最近玩过的局排在前面；识别不出的备份单独成一组
+pub fn group_by_run(backups: Vec<Backup>) -> Vec<BackupRun> {
+    let mut runs: Vec<BackupRun> = Vec::new();
+    for backup in backups {
+        match runs.iter_mut().find(|r| r.run_id == backup.run_id) {
+            Some(run) => {
+                run.last_save_time = run.last_save_time.max(backup.save_time);
+                run.backups.push(backup);
+            }
+            None => runs.push(BackupRun {
+                run_id: backup.run_id.clone(),
+                last_save_time: backup.save_time,
+                backups: vec![backup],
+            }),
+        }
+    }
+
+    for run in runs.iter_mut() {
+        run.backups
+            .sort_by(|a, b| b.pinned.cmp(&a.pinned).then(b.save_time.cmp(&a.save_time)));
+    }
+    runs.sort_by(|a, b| b.last_save_time.cmp(&a.last_save_time));
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::{Duration, OffsetDateTime};
+
+    fn backup(id: i32, run_id: Option<&str>, minutes: i64) -> Backup {
+        Backup {
+            id,
+            name: None,
+            digest: format!("{:016}", id),
+            size: 0,
+            path: String::new(),
+            save_time: OffsetDateTime::UNIX_EPOCH + Duration::minutes(minutes),
+            more_info: None,
+            notes: None,
+            pinned: false,
+            parent_id: None,
+            run_id: run_id.map(|r| r.to_string()),
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_group_by_run() {
+        let runs = group_by_run(vec![
+            backup(1, Some("a"), 1),
+            backup(2, Some("b"), 2),
+            backup(3, Some("a"), 3),
+            backup(4, None, 0),
+        ]);
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0].run_id.as_deref(), Some("a"));
+        assert_eq!(runs[0].backups.iter().map(|b| b.id).collect::<Vec<_>>(), vec![3, 1]);
+        assert_eq!(runs[1].run_id.as_deref(), Some("b"));
+        assert_eq!(runs[2].run_id, None);
+    }
+}
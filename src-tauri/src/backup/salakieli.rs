@@ -0,0 +1,90 @@
+/// Noita 的 .salakieli 文件编解码
+/// 整个文件是 AES-128-CTR 加密的 xml，密钥和初始向量按文件名固定，加密和解密是同一个操作
+use std::fs;
+use std::path::Path;
+use aes::Aes128;
+use anyhow::{anyhow, Context};
+use ctr::cipher::{KeyIvInit, StreamCipher};
+
+type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+
+/// (文件名, 密钥, 初始向量)
+const KEYS: [(&str, &[u8; 16], &[u8; 16]); 2] = [
+    ("magic_numbers.salakieli", b"SecretsOfTheAllS", b"ThreeEyesAreWatc"),
+    ("session_numbers.salakieli", b"KnowledgeIsTheHi", b"WhoWouldntGiveEv"),
+];
+
+fn keys_for(file_name: &str) -> Option<(&'static [u8; 16], &'static [u8; 16])> {
+    KEYS.iter()
+        .find(|(name, _, _)| *name == file_name)
+        .map(|(_, key, iv)| (*key, *iv))
+}
+
+/// 是否是已知密钥的 .salakieli 文件
+pub fn is_supported(file_name: &str) -> bool {
+    keys_for(file_name).is_some()
+}
+
+fn apply(file_name: &str, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let (key, iv) = keys_for(file_name).ok_or_else(|| anyhow!("不支持的文件: {}", file_name))?;
+    let mut buf = data.to_vec();
+    Aes128Ctr::new(key.into(), iv.into()).apply_keystream(&mut buf);
+    Ok(buf)
+}
+
+pub fn decrypt(file_name: &str, data: &[u8]) -> anyhow::Result<String> {
+    let plain = apply(file_name, data)?;
+    String::from_utf8(plain).context("解密结果不是有效的文本，文件可能已损坏")
+}
+
+pub fn encrypt(file_name: &str, xml: &str) -> anyhow::Result<Vec<u8>> {
+    apply(file_name, xml.as_bytes())
+}
+
+/// 读取并解密存档目录下的 .salakieli 文件，文件不存在时返回 None
+pub fn read(save_dir: &Path, file_name: &str) -> anyhow::Result<Option<String>> {
+    let path = save_dir.join(file_name);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = fs::read(&path).with_context(|| format!("读取 {} 失败", path.display()))?;
+    decrypt(file_name, &data).map(Some)
+}
+
+/// 加密后写回存档目录
+pub fn write(save_dir: &Path, file_name: &str, xml: &str) -> anyhow::Result<()> {
+    let data = encrypt(file_name, xml)?;
+    let path = save_dir.join(file_name);
+    fs::write(&path, data).with_context(|| format!("写入 {} 失败", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let xml = r#"<MagicNumbers WORLD_SEED="1234567890" _DEBUG_DONT_SAVE_MAGIC_NUMBERS="0" ></MagicNumbers>"#;
+        let encrypted = encrypt("magic_numbers.salakieli", xml).unwrap();
+        assert_ne!(encrypted.as_slice(), xml.as_bytes());
+        assert_eq!(encrypted.len(), xml.len());
+        assert_eq!(decrypt("magic_numbers.salakieli", &encrypted).unwrap(), xml);
+
+        // 两个文件的密钥不同
+        let other = encrypt("session_numbers.salakieli", xml).unwrap();
+        assert_ne!(other, encrypted);
+        assert!(encrypt("unknown.salakieli", xml).is_err());
+    }
+
+    #[test]
+    fn test_read_write() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+
+        let xml = r#"<SessionNumbers NEW_GAME_PLUS_COUNT="2" ></SessionNumbers>"#;
+        write(dir, "session_numbers.salakieli", xml).unwrap();
+        assert_ne!(fs::read(dir.join("session_numbers.salakieli")).unwrap(), xml.as_bytes());
+        assert_eq!(read(dir, "session_numbers.salakieli").unwrap().as_deref(), Some(xml));
+        assert_eq!(read(dir, "magic_numbers.salakieli").unwrap(), None);
+    }
+}
@@ -1,7 +1,10 @@
 use crate::units::path;
 use std::fs;
 use std::path::{Path, PathBuf};
+use log::error;
 use crate::backup::fs_ops::*;
+use crate::backup::space;
+use crate::units::i18n::{self, msg};
 
 /// 把目标存档直接复制到本地
 /// 返回 (backup_name, digest)
@@ -11,7 +14,7 @@ pub async fn save_local() -> Result<(String, String), String> {
 
     // 验证源路径
     if !source_path.exists() {
-        return Err(format!("存档路径不存在: {}", save_path));
+        return Err(msg("backup.save_path_missing").with("path", save_path).into());
     }
 
     // 先计算 digest 用作文件名
@@ -33,6 +36,13 @@ pub async fn save_local() -> Result<(String, String), String> {
         return Ok((backup_name, digest));
     }
 
+    // 先估算需要的空间，不够时直接报错，避免复制到一半失败
+    let required = calculate_directory_size(source_path).map_err(|e| e.to_string())? as u64;
+    space::ensure_space(&backup_root, required).map_err(|e| {
+        error!("{}", i18n::text(&e));
+        String::from(e)
+    })?;
+
     // 复制存档目录
     copy_directory(source_path, &backup_path).map_err(|e| e.to_string())?;
 
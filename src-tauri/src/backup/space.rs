@@ -0,0 +1,166 @@
+/// 备份和还原之前检查目标磁盘的剩余空间，避免复制到一半才失败
+use std::path::{Path, PathBuf};
+use log::{debug, warn};
+use sysinfo::{Disk, Disks};
+use svld_shared::commands::{GetDiskSpace, SaveDiskSpaceConfig};
+use svld_shared::Reply;
+use crate::backup::fs_ops::calculate_directory_size;
+use crate::units::i18n::{msg, Message};
+use crate::units::path;
+use crate::units::settings::{ConfigManager, Section};
+
+pub use svld_shared::backup::{DiskSpace, DiskSpaceConfig};
+
+pub const CONFIG_SECTION: &str = "disk_space";
+
+const MB: u64 = 1024 * 1024;
+/// 复制时文件系统还有额外的开销，多留一点余量
+const MARGIN: u64 = 16 * MB;
+
+impl Section for DiskSpaceConfig {
+    const NAME: &'static str = CONFIG_SECTION;
+}
+
+/// 去掉 canonicalize 在 Windows 上加的 \\?\ 前缀，否则和磁盘的挂载点对不上
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    match path.to_str().and_then(|p| p.strip_prefix(r"\\?\")) {
+        Some(rest) if !rest.starts_with("UNC") => PathBuf::from(rest),
+        _ => path,
+    }
+}
+
+/// 路径所在的磁盘
+fn disk_of<'a>(disks: &'a Disks, path: &Path) -> Option<&'a Disk> {
+    // 目标目录可能还没创建，用最近的已存在的上级目录
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let path = strip_verbatim(existing.canonicalize().ok()?);
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+}
+
+/// 路径所在磁盘的剩余空间，找不到对应的磁盘时返回 None
+pub fn available_space(path: &Path) -> Option<u64> {
+    let disks = Disks::new_with_refreshed_list();
+    disk_of(&disks, path).map(|disk| disk.available_space())
+}
+
+/// 两个路径是否在同一个磁盘上，判断不出来时当作不在
+pub fn same_disk(a: &Path, b: &Path) -> bool {
+    let disks = Disks::new_with_refreshed_list();
+    match (disk_of(&disks, a), disk_of(&disks, b)) {
+        (Some(a), Some(b)) => a.mount_point() == b.mount_point(),
+        _ => false,
+    }
+}
+
+pub fn format_size(bytes: u64) -> String {
+    let size = bytes as f64;
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    const GB: f64 = MB * 1024.0;
+    if size < KB {
+        format!("{} B", bytes)
+    } else if size < MB {
+        format!("{:.1} KB", size / KB)
+    } else if size < GB {
+        format!("{:.1} MB", size / MB)
+    } else {
+        format!("{:.2} GB", size / GB)
+    }
+}
+
+fn check(dest: &Path, required: u64, available: u64) -> Result<(), Message> {
+    if required.saturating_add(MARGIN) <= available {
+        return Ok(());
+    }
+    Err(msg("space.insufficient")
+        .with("path", dest.display())
+        .with("required", format_size(required))
+        .with("available", format_size(available)))
+}
+
+/// 检查 dest 所在磁盘能否再写入 required 字节，拿不到剩余空间时不拦截
+pub fn ensure_space(dest: &Path, required: u64) -> Result<(), Message> {
+    let Some(available) = available_space(dest) else {
+        warn!("无法获取 {} 所在磁盘的剩余空间，跳过检查", dest.display());
+        return Ok(());
+    };
+    debug!("[ensure_space] {} 需要 {} 剩余 {}", dest.display(), required, available);
+    check(dest, required, available)
+}
+
+fn size_of(path: &Path) -> u64 {
+    if !path.exists() {
+        return 0;
+    }
+    calculate_directory_size(path).unwrap_or(0) as u64
+}
+
+/// 还原需要额外占用的空间。每个子路径先删除再复制，只有比当前存档大出的部分需要新空间
+pub fn restore_required(backup_dir: &Path, save_dir: &Path, subtrees: &Option<Vec<String>>) -> u64 {
+    let subtrees = match subtrees {
+        Some(subtrees) => subtrees.clone(),
+        None => vec![String::new()],
+    };
+    subtrees
+        .iter()
+        .map(|subtree| size_of(&backup_dir.join(subtree)).saturating_sub(size_of(&save_dir.join(subtree))))
+        .sum()
+}
+
+pub fn data_disk_space() -> Result<DiskSpace, String> {
+    let data_path = path::get_data_path()?;
+    let config = DiskSpaceConfig::load();
+    let available = available_space(Path::new(&data_path));
+    let low = config.warn_below_mb > 0 && available.is_some_and(|a| a < config.warn_below_mb * MB);
+    Ok(DiskSpace { available, low, config })
+}
+
+#[tauri::command]
+pub fn get_disk_space() -> Reply<GetDiskSpace> {
+    data_disk_space()
+}
+
+#[tauri::command]
+pub fn save_disk_space_config(args: SaveDiskSpaceConfig) -> Reply<SaveDiskSpaceConfig> {
+    let SaveDiskSpaceConfig { config } = args;
+    debug!("[save_disk_space_config] {:?}", config);
+    ConfigManager::save_section(CONFIG_SECTION, Some(&config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn refuses_when_space_is_short() {
+        let dest = Path::new("D:/Noita-svld");
+        assert!(check(dest, 100 * MB, 200 * MB).is_ok());
+        let e = check(dest, 100 * MB, 110 * MB).unwrap_err();
+        assert_eq!(e.code, "space.insufficient");
+        assert_eq!(e.params["required"], "100.0 MB");
+        assert_eq!(e.params["available"], "110.0 MB");
+    }
+
+    #[test]
+    fn restore_counts_only_growth() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let (backup, save) = (root.join("backup"), root.join("save00"));
+        for dir in ["world", "stats"] {
+            fs::create_dir_all(backup.join(dir)).unwrap();
+            fs::create_dir_all(save.join(dir)).unwrap();
+        }
+        fs::write(backup.join("world/a.bin"), vec![0u8; 300]).unwrap();
+        fs::write(save.join("world/a.bin"), vec![0u8; 100]).unwrap();
+        fs::write(backup.join("stats/s.xml"), vec![0u8; 10]).unwrap();
+        fs::write(save.join("stats/s.xml"), vec![0u8; 50]).unwrap();
+
+        assert_eq!(restore_required(&backup, &save, &Some(vec!["world".into(), "stats".into()])), 200);
+        assert_eq!(restore_required(&backup, &save, &None), 160);
+    }
+}
@@ -1,48 +1,44 @@
 use anyhow::Result;
-use serde::{Deserialize, Serialize};
 use sqlx::{Connection, SqliteConnection};
-use sqlx::{FromRow, Row};
+use sqlx::Row;
+use std::collections::HashMap;
 use std::path::Path;
 use time::OffsetDateTime;
 use log::{info, error};
 use urlencoding::encode;
-use sqlx::sqlite::{SqliteConnectOptions};
+use sqlx::sqlite::{Sqlite, SqliteArguments, SqliteConnectOptions, SqliteRow};
+use sqlx::query::Query;
 use sqlx::ConnectOptions; // 引入 trait 以使用 connect_with
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Backup {
-    pub id: i32,
-    pub name: Option<String>,
-    pub digest: String,
-    pub size: i64,
-    pub path: String,
-    #[serde(with = "time::serde::rfc3339")]
-    pub save_time: OffsetDateTime,
-    pub more_info: Option<String>,
-}
+pub use svld_shared::backup::{Backup, BackupSort, MetaData};
 
-impl FromRow<'_, sqlx::sqlite::SqliteRow> for Backup {
-    fn from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Self, sqlx::Error> {
-        let save_time_str: String = row.try_get("save_time")?;
-        let save_time = OffsetDateTime::parse(
-            &save_time_str,
-            &time::format_description::well_known::Rfc3339,
-        )
-        .map_err(|e| sqlx::Error::ColumnDecode {
-            index: "save_time".to_string(),
-            source: Box::new(e),
-        })?;
+/// 从查询 BACKUP_COLUMNS 的结果中读出备份记录，tags 需要之后单独填充
+fn backup_from_row(row: SqliteRow) -> Result<Backup, sqlx::Error> {
+    let save_time_str: String = row.try_get("save_time")?;
+    let save_time = OffsetDateTime::parse(
+        &save_time_str,
+        &time::format_description::well_known::Rfc3339,
+    )
+    .map_err(|e| sqlx::Error::ColumnDecode {
+        index: "save_time".to_string(),
+        source: Box::new(e),
+    })?;
 
-        Ok(Backup {
-            id: row.try_get("id")?,
-            name: row.try_get("name")?,
-            digest: row.try_get("digest")?,
-            size: row.try_get("size")?,
-            path: row.try_get("path")?,
-            save_time,
-            more_info: row.try_get("more_info")?,
-        })
-    }
+    Ok(Backup {
+        id: row.try_get("id")?,
+        name: row.try_get("name")?,
+        digest: row.try_get("digest")?,
+        size: row.try_get("size")?,
+        path: row.try_get("path")?,
+        save_time,
+        more_info: row.try_get::<Option<String>, _>("more_info")?.as_deref().and_then(MetaData::from_json),
+        notes: row.try_get("notes")?,
+        pinned: row.try_get("pinned")?,
+        parent_id: row.try_get("parent_id")?,
+        run_id: row.try_get("run_id")?,
+        tags: Vec::new(),
+    })
 }
 
 const SCHEMA_SQL: &str = r"
@@ -57,6 +53,126 @@ CREATE TABLE IF NOT EXISTS backups (
 );
 ";
 
+/// 数据库升级脚本，按顺序执行，已执行到第几条记录在 PRAGMA user_version 中
+const MIGRATIONS: &[&str] = &[
+    "ALTER TABLE backups ADD COLUMN notes TEXT",
+    r"CREATE TABLE IF NOT EXISTS backup_tags (
+        backup_id INTEGER NOT NULL,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (backup_id, tag)
+    )",
+    "ALTER TABLE backups ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0",
+    "ALTER TABLE backups ADD COLUMN parent_id INTEGER",
+    r"CREATE TABLE IF NOT EXISTS app_state (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    )",
+    "ALTER TABLE backups ADD COLUMN run_id TEXT",
+    r"CREATE TABLE IF NOT EXISTS backup_thumbnails (
+        backup_id INTEGER PRIMARY KEY,
+        png BLOB NOT NULL
+    )",
+    "CREATE INDEX IF NOT EXISTS idx_backups_save_time ON backups (save_time)",
+];
+
+const BACKUP_COLUMNS: &str = "id, name, digest, size, path, save_time, more_info, notes, pinned, parent_id, run_id";
+
+/// search_backups 中可以直接交给 SQL 的条件
+#[derive(Debug, Default)]
+pub struct BackupFilter<'a> {
+    pub text: Option<&'a str>,
+    pub tag: Option<&'a str>,
+    pub min_size: Option<i64>,
+    pub max_size: Option<i64>,
+}
+
+impl<'a> BackupFilter<'a> {
+    /// 追加 WHERE 条件，参数之后由 bind 按相同顺序绑定
+    fn push_conditions(&self, sql: &mut String) {
+        if self.text.is_some() {
+            sql.push_str(" AND (name LIKE ? ESCAPE '\\' OR notes LIKE ? ESCAPE '\\')");
+        }
+        if self.tag.is_some() {
+            sql.push_str(" AND id IN (SELECT backup_id FROM backup_tags WHERE tag = ?)");
+        }
+        if self.min_size.is_some() {
+            sql.push_str(" AND size >= ?");
+        }
+        if self.max_size.is_some() {
+            sql.push_str(" AND size <= ?");
+        }
+    }
+
+    fn bind<'q>(&self, mut query: Query<'q, Sqlite, SqliteArguments<'q>>) -> Query<'q, Sqlite, SqliteArguments<'q>>
+    where
+        'a: 'q,
+    {
+        if let Some(text) = self.text {
+            let pattern = format!(
+                "%{}%",
+                text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+            );
+            query = query.bind(pattern.clone()).bind(pattern);
+        }
+        if let Some(tag) = self.tag {
+            query = query.bind(tag);
+        }
+        if let Some(min_size) = self.min_size {
+            query = query.bind(min_size);
+        }
+        if let Some(max_size) = self.max_size {
+            query = query.bind(max_size);
+        }
+        query
+    }
+}
+
+/// 各排序方式对应的排序列，最后再按 id 排保证顺序唯一
+fn sort_columns(sort: BackupSort) -> &'static [&'static str] {
+    match sort {
+        BackupSort::Time => &["save_time"],
+        BackupSort::Size => &["size"],
+        BackupSort::Name => &["COALESCE(name, '')"],
+        BackupSort::Pinned => &["pinned", "save_time"],
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum SortValue {
+    Int(i64),
+    Text(String),
+}
+
+/// 翻页位置：上一页最后一条备份的排序值和 id
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupCursor {
+    keys: Vec<SortValue>,
+    id: i32,
+}
+
+impl BackupCursor {
+    pub fn of(backup: &Backup, sort: BackupSort) -> BackupCursor {
+        let time = || SortValue::Text(backup.save_time.format(&time::format_description::well_known::Rfc3339).unwrap_or_default());
+        let keys = match sort {
+            BackupSort::Time => vec![time()],
+            BackupSort::Size => vec![SortValue::Int(backup.size)],
+            BackupSort::Name => vec![SortValue::Text(backup.name.clone().unwrap_or_default())],
+            BackupSort::Pinned => vec![SortValue::Int(backup.pinned as i64), time()],
+        };
+        BackupCursor { keys, id: backup.id }
+    }
+
+    /// 交给前端的游标字符串，前端只需要原样传回
+    pub fn encode(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn decode(text: &str) -> Option<BackupCursor> {
+        serde_json::from_str(text).ok()
+    }
+}
+
 pub struct Db {}
 
 impl Db {
@@ -100,37 +216,64 @@ impl Db {
         
         info!("数据库表已创建/确认");
 
+        Self::migrate(&mut conn).await?;
+
         Ok(conn)
     }
 
-    pub async fn store_backup(backup: &Backup, conn: &mut SqliteConnection) -> anyhow::Result<()> {
+    async fn migrate(conn: &mut SqliteConnection) -> anyhow::Result<()> {
+        let version: i64 = sqlx::query_scalar("PRAGMA user_version")
+            .fetch_one(&mut *conn)
+            .await?;
+
+        // 每一步和版本号放在同一个事务里，中途失败时下次启动会从这一步重新执行
+        for (index, sql) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            let mut tx = conn.begin().await?;
+            sqlx::query(sql).execute(&mut *tx).await?;
+            sqlx::query(&format!("PRAGMA user_version = {}", index + 1))
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            info!("数据库已升级到版本 {}", index + 1);
+        }
+        Ok(())
+    }
+
+    /// 返回新记录的 id
+    pub async fn store_backup(backup: &Backup, conn: &mut SqliteConnection) -> anyhow::Result<i32> {
         let save_time_str = backup
             .save_time
             .format(&time::format_description::well_known::Rfc3339)
             .unwrap();
 
-        sqlx::query(
-            r#"INSERT INTO backups (name, digest, size,path, save_time, more_info)
-               VALUES (?, ?, ?, ?, ?, ?)"#,
+        let result = sqlx::query(
+            r#"INSERT INTO backups (name, digest, size,path, save_time, more_info, notes, pinned, parent_id, run_id)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
         )
             .bind(&backup.name)
             .bind(&backup.digest)
             .bind(backup.size)
             .bind(&backup.path)
             .bind(save_time_str)
-            .bind(&backup.more_info)
+            .bind(backup.more_info.as_ref().and_then(|meta| serde_json::to_string(meta).ok()))
+            .bind(&backup.notes)
+            .bind(backup.pinned)
+            .bind(backup.parent_id)
+            .bind(&backup.run_id)
             .execute(conn)
             .await?;
-        Ok(())
+        Ok(result.last_insert_rowid() as i32)
     }
 
     pub async fn get_all_backup(conn: &mut SqliteConnection) -> anyhow::Result<Vec<Backup>> {
-        let backups = sqlx::query_as::<_, Backup>(
-            r#"SELECT id, name, digest, size,path, save_time, more_info FROM backups"#,
+        let mut backups = sqlx::query(
+            &format!("SELECT {} FROM backups ORDER BY pinned DESC, save_time DESC", BACKUP_COLUMNS),
         )
-            .fetch_all(conn)
+            .try_map(backup_from_row)
+            .fetch_all(&mut *conn)
             .await?;
 
+        Self::fill_tags(conn, &mut backups).await?;
         Ok(backups)
     }
 
@@ -138,17 +281,39 @@ impl Db {
         conn: &mut SqliteConnection,
         id: i32,
     ) -> anyhow::Result<Option<Backup>> {
-        let backup = sqlx::query_as::<_, Backup>(
-            r#"SELECT id, name, digest, size, path, save_time, more_info FROM backups WHERE id = ?"#,
+        let backup = sqlx::query(
+            &format!("SELECT {} FROM backups WHERE id = ?", BACKUP_COLUMNS),
         )
             .bind(id)
-            .fetch_optional(conn)
+            .try_map(backup_from_row)
+            .fetch_optional(&mut *conn)
             .await?;
 
-        Ok(backup)
+        match backup {
+            Some(backup) => {
+                let mut backups = vec![backup];
+                Self::fill_tags(conn, &mut backups).await?;
+                Ok(backups.pop())
+            }
+            None => Ok(None),
+        }
     }
 
     pub async fn delete_backup(conn: &mut SqliteConnection, id: i32) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM backup_tags WHERE backup_id = ?")
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query("DELETE FROM backup_thumbnails WHERE backup_id = ?")
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+        // 子节点挂到被删除节点的父节点上，历史树不会断开
+        sqlx::query("UPDATE backups SET parent_id = (SELECT parent_id FROM backups WHERE id = ?) WHERE parent_id = ?")
+            .bind(id)
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
         sqlx::query("DELETE FROM backups WHERE id = ?")
             .bind(id)
             .execute(conn)
@@ -160,18 +325,343 @@ impl Db {
         conn: &mut SqliteConnection,
         digest: &str,
     ) -> anyhow::Result<Option<Backup>> {
-        let backup = sqlx::query_as::<_, Backup>(
-            r#"SELECT id, name, digest, size, path, save_time, more_info FROM backups WHERE digest = ?"#,
+        let backup = sqlx::query(
+            &format!("SELECT {} FROM backups WHERE digest = ?", BACKUP_COLUMNS),
         )
             .bind(digest)
+            .try_map(backup_from_row)
             .fetch_optional(conn)
             .await?;
 
         Ok(backup)
     }
     
+    pub async fn update_backup_path(conn: &mut SqliteConnection, id: i32, path: &str) -> anyhow::Result<()> {
+        sqlx::query("UPDATE backups SET path = ? WHERE id = ?")
+            .bind(path)
+            .bind(id)
+            .execute(conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_notes(conn: &mut SqliteConnection, id: i32, notes: Option<&str>) -> anyhow::Result<()> {
+        sqlx::query("UPDATE backups SET notes = ? WHERE id = ?")
+            .bind(notes)
+            .bind(id)
+            .execute(conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_pinned(conn: &mut SqliteConnection, id: i32, pinned: bool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE backups SET pinned = ? WHERE id = ?")
+            .bind(pinned)
+            .bind(id)
+            .execute(conn)
+            .await?;
+        Ok(())
+    }
+
+    /// 用新的标签列表替换原有标签
+    pub async fn set_tags(conn: &mut SqliteConnection, id: i32, tags: &[String]) -> anyhow::Result<()> {
+        let mut tx = conn.begin().await?;
+        sqlx::query("DELETE FROM backup_tags WHERE backup_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        for tag in tags {
+            sqlx::query("INSERT OR IGNORE INTO backup_tags (backup_id, tag) VALUES (?, ?)")
+                .bind(id)
+                .bind(tag)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 最近保存的一个备份
+    pub async fn get_latest_backup(conn: &mut SqliteConnection) -> anyhow::Result<Option<Backup>> {
+        let backup = sqlx::query(
+            &format!("SELECT {} FROM backups ORDER BY save_time DESC, id DESC LIMIT 1", BACKUP_COLUMNS),
+        )
+            .try_map(backup_from_row)
+            .fetch_optional(conn)
+            .await?;
+        Ok(backup)
+    }
+
+    /// 某一局游戏最近保存的备份
+    pub async fn get_latest_backup_of_run(conn: &mut SqliteConnection, run_id: &str) -> anyhow::Result<Option<Backup>> {
+        let backup = sqlx::query(
+            &format!("SELECT {} FROM backups WHERE run_id = ? ORDER BY save_time DESC, id DESC LIMIT 1", BACKUP_COLUMNS),
+        )
+            .bind(run_id)
+            .try_map(backup_from_row)
+            .fetch_optional(conn)
+            .await?;
+        Ok(backup)
+    }
+
+    pub async fn get_state(conn: &mut SqliteConnection, key: &str) -> anyhow::Result<Option<String>> {
+        let value = sqlx::query_scalar("SELECT value FROM app_state WHERE key = ?")
+            .bind(key)
+            .fetch_optional(conn)
+            .await?;
+        Ok(value)
+    }
+
+    pub async fn set_state(conn: &mut SqliteConnection, key: &str, value: Option<&str>) -> anyhow::Result<()> {
+        match value {
+            Some(value) => {
+                sqlx::query("INSERT INTO app_state (key, value) VALUES (?, ?) ON CONFLICT(key) DO UPDATE SET value = excluded.value")
+                    .bind(key)
+                    .bind(value)
+                    .execute(conn)
+                    .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM app_state WHERE key = ?")
+                    .bind(key)
+                    .execute(conn)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 地图缩略图 (PNG)
+    pub async fn get_thumbnail(conn: &mut SqliteConnection, id: i32) -> anyhow::Result<Option<Vec<u8>>> {
+        let png = sqlx::query_scalar("SELECT png FROM backup_thumbnails WHERE backup_id = ?")
+            .bind(id)
+            .fetch_optional(conn)
+            .await?;
+        Ok(png)
+    }
+
+    pub async fn set_thumbnail(conn: &mut SqliteConnection, id: i32, png: &[u8]) -> anyhow::Result<()> {
+        sqlx::query("INSERT OR REPLACE INTO backup_thumbnails (backup_id, png) VALUES (?, ?)")
+            .bind(id)
+            .bind(png)
+            .execute(conn)
+            .await?;
+        Ok(())
+    }
+
+    /// 所有用过的标签，按字母顺序
+    pub async fn get_all_tags(conn: &mut SqliteConnection) -> anyhow::Result<Vec<String>> {
+        let tags = sqlx::query_scalar("SELECT DISTINCT tag FROM backup_tags ORDER BY tag")
+            .fetch_all(conn)
+            .await?;
+        Ok(tags)
+    }
+
+    /// 按名称/备注、标签和大小筛选，日期和元信息的条件由调用方在内存中处理
+    pub async fn search_backups(
+        conn: &mut SqliteConnection,
+        filter: &BackupFilter<'_>,
+    ) -> anyhow::Result<Vec<Backup>> {
+        let mut sql = format!("SELECT {} FROM backups WHERE 1 = 1", BACKUP_COLUMNS);
+        filter.push_conditions(&mut sql);
+
+        let mut backups = filter
+            .bind(sqlx::query(&sql))
+            .try_map(backup_from_row)
+            .fetch_all(&mut *conn)
+            .await?;
+        Self::fill_tags(conn, &mut backups).await?;
+        Ok(backups)
+    }
+
+    /// 按 sort 排序后取 after 之后的至多 limit 条，after 为 None 时从头开始
+    pub async fn list_backups(
+        conn: &mut SqliteConnection,
+        filter: &BackupFilter<'_>,
+        sort: BackupSort,
+        descending: bool,
+        after: Option<&BackupCursor>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Backup>> {
+        let columns = sort_columns(sort);
+        let direction = if descending { "DESC" } else { "ASC" };
+
+        let mut sql = format!("SELECT {} FROM backups WHERE 1 = 1", BACKUP_COLUMNS);
+        filter.push_conditions(&mut sql);
+        if let Some(after) = after {
+            anyhow::ensure!(after.keys.len() == columns.len(), "游标与排序方式不匹配");
+            let placeholders = vec!["?"; columns.len() + 1].join(", ");
+            sql.push_str(&format!(
+                " AND ({}, id) {} ({})",
+                columns.join(", "),
+                if descending { "<" } else { ">" },
+                placeholders
+            ));
+        }
+        let order: Vec<String> = columns
+            .iter()
+            .chain(&["id"])
+            .map(|column| format!("{} {}", column, direction))
+            .collect();
+        sql.push_str(&format!(" ORDER BY {} LIMIT ?", order.join(", ")));
+
+        let mut query = filter.bind(sqlx::query(&sql));
+        if let Some(after) = after {
+            for key in &after.keys {
+                query = match key {
+                    SortValue::Int(value) => query.bind(*value),
+                    SortValue::Text(value) => query.bind(value.as_str()),
+                };
+            }
+            query = query.bind(after.id);
+        }
+
+        let mut backups = query
+            .bind(limit as i64)
+            .try_map(backup_from_row)
+            .fetch_all(&mut *conn)
+            .await?;
+        Self::fill_tags(conn, &mut backups).await?;
+        Ok(backups)
+    }
+
+    /// 只查询这些备份的标签，按 id 分批以免超过 SQLite 的参数数量上限
+    async fn fill_tags(conn: &mut SqliteConnection, backups: &mut [Backup]) -> anyhow::Result<()> {
+        const CHUNK: usize = 500;
+        let mut tags: HashMap<i32, Vec<String>> = HashMap::new();
+        for chunk in backups.chunks(CHUNK) {
+            let placeholders = vec!["?"; chunk.len()].join(", ");
+            let sql = format!(
+                "SELECT backup_id, tag FROM backup_tags WHERE backup_id IN ({}) ORDER BY tag",
+                placeholders
+            );
+            let mut query = sqlx::query_as::<_, (i32, String)>(&sql);
+            for backup in chunk {
+                query = query.bind(backup.id);
+            }
+            for (id, tag) in query.fetch_all(&mut *conn).await? {
+                tags.entry(id).or_default().push(tag);
+            }
+        }
+        for backup in backups.iter_mut() {
+            backup.tags = tags.remove(&backup.id).unwrap_or_default();
+        }
+        Ok(())
+    }
+
     pub async fn rename_backup(conn: &mut SqliteConnection, id: i32, name : &str) -> anyhow::Result<()> {
         Ok(())
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(digest: &str, name: &str, size: i64) -> Backup {
+        Backup {
+            id: 0,
+            name: Some(name.to_string()),
+            digest: digest.to_string(),
+            size,
+            path: format!("backup_{}", &digest[..12]),
+            save_time: OffsetDateTime::now_utc(),
+            more_info: None,
+            notes: None,
+            pinned: false,
+            parent_id: None,
+            run_id: None,
+            tags: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tags_and_search() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db_file = tmp.path().join("svld.db");
+        let mut conn = Db::new(db_file.to_string_lossy().to_string()).await.unwrap();
+
+        Db::store_backup(&sample("aaaaaaaaaaaaaaaa", "100%_run", 10), &mut conn).await.unwrap();
+        Db::store_backup(&sample("bbbbbbbbbbbbbbbb", "boss", 20), &mut conn).await.unwrap();
+        let boss = Db::get_backup_by_digest(&mut conn, "bbbbbbbbbbbbbbbb").await.unwrap().unwrap();
+
+        Db::set_tags(&mut conn, boss.id, &["good".to_string(), "ng+".to_string()]).await.unwrap();
+        Db::set_notes(&mut conn, boss.id, Some("before kolmi")).await.unwrap();
+        assert_eq!(Db::get_all_tags(&mut conn).await.unwrap(), vec!["good", "ng+"]);
+
+        let by_tag = Db::search_backups(&mut conn, &BackupFilter { tag: Some("good"), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(by_tag.len(), 1);
+        assert_eq!(by_tag[0].tags, vec!["good", "ng+"]);
+
+        // % 应按字面匹配
+        let by_text = Db::search_backups(&mut conn, &BackupFilter { text: Some("0%"), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(by_text.len(), 1);
+
+        let by_notes = Db::search_backups(&mut conn, &BackupFilter { text: Some("kolmi"), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(by_notes[0].id, boss.id);
+
+        let by_size = Db::search_backups(&mut conn, &BackupFilter { min_size: Some(15), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(by_size.len(), 1);
+
+        Db::set_pinned(&mut conn, boss.id, true).await.unwrap();
+        let all = Db::get_all_backup(&mut conn).await.unwrap();
+        assert_eq!(all[0].id, boss.id);
+        assert!(all[0].pinned);
+
+        let child = Backup { parent_id: Some(boss.id), ..sample("cccccccccccccccc", "child", 5) };
+        let child_id = Db::store_backup(&child, &mut conn).await.unwrap();
+
+        Db::delete_backup(&mut conn, boss.id).await.unwrap();
+        assert!(Db::get_all_tags(&mut conn).await.unwrap().is_empty());
+        // 子节点继承被删除节点的父节点
+        let child = Db::get_backup_by_id(&mut conn, child_id).await.unwrap().unwrap();
+        assert_eq!(child.parent_id, None);
+
+        Db::set_state(&mut conn, "head", Some("1")).await.unwrap();
+        Db::set_state(&mut conn, "head", Some("2")).await.unwrap();
+        assert_eq!(Db::get_state(&mut conn, "head").await.unwrap().as_deref(), Some("2"));
+        Db::set_state(&mut conn, "head", None).await.unwrap();
+        assert_eq!(Db::get_state(&mut conn, "head").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_list_backups_pages() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db_file = tmp.path().join("svld.db");
+        let mut conn = Db::new(db_file.to_string_lossy().to_string()).await.unwrap();
+
+        // 有两个大小相同，翻页时要靠 id 区分
+        for (digest, size) in [("a", 30), ("b", 10), ("c", 20), ("d", 20), ("e", 40)] {
+            Db::store_backup(&sample(&digest.repeat(16), digest, size), &mut conn).await.unwrap();
+        }
+
+        let filter = BackupFilter::default();
+        let mut sizes = Vec::new();
+        let mut after = None;
+        loop {
+            let page = Db::list_backups(&mut conn, &filter, BackupSort::Size, true, after.as_ref(), 2).await.unwrap();
+            sizes.extend(page.iter().map(|b| b.size));
+            match page.last() {
+                Some(last) if page.len() == 2 => after = Some(BackupCursor::decode(&BackupCursor::of(last, BackupSort::Size).encode()).unwrap()),
+                _ => break,
+            }
+        }
+        assert_eq!(sizes, vec![40, 30, 20, 20, 10]);
+
+        let by_name = Db::list_backups(&mut conn, &filter, BackupSort::Name, false, None, 10).await.unwrap();
+        assert_eq!(by_name.iter().map(|b| b.name.as_deref().unwrap()).collect::<Vec<_>>(), vec!["a", "b", "c", "d", "e"]);
+
+        // 游标和排序方式对不上时报错而不是返回错乱的结果
+        let cursor = BackupCursor::of(&by_name[0], BackupSort::Pinned);
+        assert!(Db::list_backups(&mut conn, &filter, BackupSort::Size, true, Some(&cursor), 2).await.is_err());
+    }
+}
@@ -2,12 +2,26 @@
 mod db;
 pub mod units;
 pub mod backup;
+pub mod storage;
 use anyhow::Result;
 use backup::commands::*;
+use backup::batch::*;
+use backup::history::*;
+use storage::commands::*;
 use units::path::*;
 use units::dashboard::*;
 use units::file::*;
 use units::update::*;
+use units::game::*;
+use units::hotkey::*;
+use backup::auto::*;
+use backup::space::*;
+use units::notify::*;
+use units::i18n::*;
+use units::privacy::*;
+use units::logs::*;
+use units::settings::*;
+use units::settings_io::*;
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() -> Result<()> {
     tauri::Builder::default()
@@ -21,17 +35,41 @@ pub fn run() -> Result<()> {
                     }),
                 ])
                 .level(log::LevelFilter::Debug)
+                .format(units::privacy::format_record)
                 .build(),
         )
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(units::hotkey::handle)
+                .build(),
+        )
+        .setup(|app| {
+            units::events::init(app.handle());
+            units::hotkey::register_all(app.handle(), &units::hotkey::HotkeyConfig::load());
+            units::tray::init(app.handle())?;
+            units::notify::init(app.handle());
+            // 自动备份开始前先处理上次中断的加密操作
+            backup::crypto::recover();
+            backup::auto::start(app.handle().clone());
+            units::game::watch();
+            Ok(())
+        })
+        // 关闭窗口时只是隐藏到托盘，从托盘菜单退出
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                let _ = window.hide();
+                api.prevent_close();
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             get_save_path,
             get_data_path,
             save_path_to_env,
-            save_data_path,
             select_save_path,
             verify_validation,
             verify_data_validation,
@@ -41,9 +79,60 @@ pub fn run() -> Result<()> {
             get_dashboard_stats,
             delete_backup,
             select_data_path,
+            move_data_path,
+            set_backup_notes,
+            set_backup_tags,
+            get_all_tags,
+            search_backups,
+            list_backups,
+            batch_delete_backups,
+            batch_export_backups,
+            batch_tag_backups,
+            batch_pin_backups,
+            pin_backup,
+            unpin_backup,
+            get_backup_graph,
+            read_salakieli,
+            preview_restore,
+            get_backup_thumbnail,
+            export_backup,
+            import_backup,
+            get_encryption_status,
+            unlock_encryption,
+            lock_encryption,
+            enable_encryption,
+            disable_encryption,
+            get_hotkeys,
+            save_hotkeys,
+            is_game_running,
+            get_auto_backup,
+            save_auto_backup,
+            set_auto_backup_paused,
+            get_disk_space,
+            save_disk_space_config,
+            get_notifications,
+            save_notifications,
+            get_language,
+            set_language,
+            get_remote_config,
+            save_remote_config,
+            test_remote,
+            sync_backups,
             open_backup,
-            open_log,
-            check_update,
+            read_logs,
+            start_log_tail,
+            stop_log_tail,
+            export_sanitized_log,
+            get_settings_info,
+            dismiss_settings_problems,
+            export_settings,
+            preview_settings_import,
+            import_settings,
+            reset_settings_section,
+            check_for_update,
+            install_update,
+            get_update_config,
+            save_update_config,
             get_version,
         ])
         .run(tauri::generate_context!())?;
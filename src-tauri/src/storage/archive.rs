@@ -0,0 +1,158 @@
+/// 把备份文件夹打包成 zip，数据库记录以 svld_backup.json 的形式放在包里
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::Path;
+use anyhow::{anyhow, Context};
+use jwalk::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use crate::db::Backup;
+
+const RECORD_NAME: &str = "svld_backup.json";
+
+/// 远端文件名
+pub fn archive_key(digest: &str) -> String {
+    format!("{}.zip", digest)
+}
+
+/// 从远端文件名取出 digest，不是备份包时返回 None
+pub fn digest_of(key: &str) -> Option<&str> {
+    let digest = key.strip_suffix(".zip")?;
+    (digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit())).then_some(digest)
+}
+
+pub fn pack(backup_dir: &Path, record: &Backup, zip_path: &Path) -> anyhow::Result<()> {
+    let file = File::create(zip_path).with_context(|| format!("无法创建 {}", zip_path.display()))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+
+    zip.start_file(RECORD_NAME, options)?;
+    zip.write_all(serde_json::to_string_pretty(record)?.as_bytes())?;
+    write_dir(&mut zip, backup_dir, options)?;
+    zip.finish()?;
+    Ok(())
+}
+
+/// 只打包文件，不带数据库记录
+pub fn pack_dir(backup_dir: &Path, zip_path: &Path) -> anyhow::Result<()> {
+    let file = File::create(zip_path).with_context(|| format!("无法创建 {}", zip_path.display()))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+    write_dir(&mut zip, backup_dir, options)?;
+    zip.finish()?;
+    Ok(())
+}
+
+fn write_dir(zip: &mut ZipWriter<File>, backup_dir: &Path, options: SimpleFileOptions) -> anyhow::Result<()> {
+    let mut entries: Vec<_> = WalkDir::new(backup_dir)
+        .skip_hidden(false)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.path())
+        .collect();
+    entries.sort();
+
+    for path in entries {
+        let name = path
+            .strip_prefix(backup_dir)?
+            .to_string_lossy()
+            .replace('\\', "/");
+        zip.start_file(format!("save00/{}", name), options)?;
+        let mut src = File::open(&path)?;
+        io::copy(&mut src, zip)?;
+    }
+    Ok(())
+}
+
+/// 解压到 backup_dir，返回包里的数据库记录
+pub fn unpack(zip_path: &Path, backup_dir: &Path) -> anyhow::Result<Backup> {
+    let file = File::open(zip_path)?;
+    let mut zip = ZipArchive::new(file).context("备份包已损坏")?;
+
+    let mut record = String::new();
+    zip.by_name(RECORD_NAME)
+        .map_err(|_| anyhow!("备份包中缺少 {}", RECORD_NAME))?
+        .read_to_string(&mut record)?;
+    let record: Backup = serde_json::from_str(&record)?;
+    extract(&mut zip, backup_dir)?;
+    Ok(record)
+}
+
+/// 解压 pack_dir 打出的包
+pub fn unpack_dir(zip_path: &Path, backup_dir: &Path) -> anyhow::Result<()> {
+    let file = File::open(zip_path)?;
+    let mut zip = ZipArchive::new(file).context("备份包已损坏")?;
+    extract(&mut zip, backup_dir)
+}
+
+fn extract(zip: &mut ZipArchive<File>, backup_dir: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(backup_dir)?;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        // enclosed_name 会拒绝 ../ 之类的路径
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let Ok(relative) = name.strip_prefix("save00") else {
+            continue;
+        };
+        let target = backup_dir.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut dst = File::create(&target)?;
+        io::copy(&mut entry, &mut dst)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::OffsetDateTime;
+
+    #[test]
+    fn test_pack_unpack() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let src = root.join("backup_src");
+        fs::create_dir_all(src.join("world")).unwrap();
+        fs::write(src.join("player.xml"), "<Entity/>").unwrap();
+        fs::write(src.join("world").join("area_0.bin"), [1u8, 2, 3]).unwrap();
+
+        let record = Backup {
+            id: 7,
+            name: Some("测试".to_string()),
+            digest: "ab".repeat(32),
+            size: 12,
+            path: "backup_abababababab".to_string(),
+            save_time: OffsetDateTime::UNIX_EPOCH,
+            more_info: None,
+            notes: Some("notes".to_string()),
+            pinned: true,
+            parent_id: None,
+            run_id: None,
+            tags: vec!["boss".to_string()],
+        };
+        let zip_path = root.join(archive_key(&record.digest));
+        pack(&src, &record, &zip_path).unwrap();
+        assert_eq!(digest_of(&archive_key(&record.digest)), Some(record.digest.as_str()));
+        assert_eq!(digest_of("notes.txt"), None);
+
+        let dst = root.join("backup_dst");
+        let unpacked = unpack(&zip_path, &dst).unwrap();
+        assert_eq!(unpacked.name, record.name);
+        assert_eq!(unpacked.tags, record.tags);
+        assert_eq!(fs::read_to_string(dst.join("player.xml")).unwrap(), "<Entity/>");
+        assert_eq!(fs::read(dst.join("world").join("area_0.bin")).unwrap(), vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::{debug, error, info};
+use anyhow::{anyhow, bail};
+use svld_shared::commands::{GetRemoteConfig, SaveRemoteConfig, SyncBackups, TestRemote};
+use svld_shared::Reply;
+use svld_shared::events::BackupCreated;
+use crate::backup::busy;
+use crate::backup::crypto::{self, EncryptionConfig};
+use crate::db::{Backup, Db};
+use crate::storage::{archive, load_secret, open_backend, save_secret, RemoteConfig, StorageBackend, CONFIG_SECTION};
+use crate::units::{events, path};
+use crate::units::settings::ConfigManager;
+use crate::units::db_path;
+use crate::units::i18n::{self, msg};
+
+pub use svld_shared::storage::SyncReport;
+
+fn display_name(backup: &Backup) -> String {
+    backup.name.clone().unwrap_or_else(|| backup.dir_name())
+}
+
+#[tauri::command]
+pub fn get_remote_config() -> Reply<GetRemoteConfig> {
+    Ok(ConfigManager::load_section(CONFIG_SECTION))
+}
+
+/// secret 为 None 时保留原来的密码，为空字符串时删除
+#[tauri::command]
+pub fn save_remote_config(args: SaveRemoteConfig) -> Reply<SaveRemoteConfig> {
+    let SaveRemoteConfig { config, secret } = args;
+    debug!("[save_remote_config] {:?}", config);
+    ConfigManager::save_section(CONFIG_SECTION, config.as_ref())?;
+
+    let secret = match (&config, secret) {
+        (None, _) => Some(None),
+        (Some(_), Some(secret)) if secret.is_empty() => Some(None),
+        (Some(_), Some(secret)) => Some(Some(secret)),
+        (Some(_), None) => None,
+    };
+    if let Some(secret) = secret {
+        save_secret(secret.as_deref()).map_err(|e| {
+            error!("保存远程存储凭据失败: {}", e);
+            e.to_string()
+        })?;
+    }
+    info!("远程存储配置已更新");
+    Ok(())
+}
+
+async fn connect_backend() -> Result<Box<dyn StorageBackend>, String> {
+    let config: RemoteConfig = ConfigManager::load_section(CONFIG_SECTION).ok_or_else(|| String::from(msg("remote.not_configured")))?;
+    let secret = load_secret().map_err(|e| e.to_string())?;
+    // 建立连接可能要等网络，不要占用异步线程
+    tokio::task::spawn_blocking(move || open_backend(&config, secret))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| {
+            error!("连接远程存储失败: {}", e);
+            e.to_string()
+        })
+}
+
+/// 测试连接，返回远端已有的备份数
+#[tauri::command]
+pub async fn test_remote() -> Reply<TestRemote> {
+    let backend = connect_backend().await?;
+    let objects = tokio::task::spawn_blocking(move || backend.list())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    Ok(objects.iter().filter(|o| archive::digest_of(&o.key).is_some()).count())
+}
+
+/// 打包备份，有口令时整个包加密后再上传，远端看不到备份名和备注
+fn pack_for_upload(backup: &Backup, data_root: &Path, zip_path: &Path, passphrase: Option<&str>) -> anyhow::Result<()> {
+    archive::pack(&backup.resolve_dir(data_root), backup, zip_path)?;
+    if let Some(passphrase) = passphrase {
+        let plain = zip_path.with_extension("plain");
+        fs::rename(zip_path, &plain)?;
+        let result = crypto::encrypt_file(&plain, zip_path, passphrase);
+        let _ = fs::remove_file(&plain);
+        result?;
+    }
+    Ok(())
+}
+
+/// 先解压到旁边的临时目录，核对过 digest 再改名，内容对不上远端文件名的包不会留下记录
+fn unpack_download(zip_path: &Path, backup_dir: &Path, digest: &str, passphrase: Option<&str>) -> anyhow::Result<Backup> {
+    let temp = backup_dir.with_extension("part");
+    let _ = fs::remove_dir_all(&temp);
+    let result = extract_download(zip_path, &temp, passphrase).and_then(|record| {
+        if record.digest != digest {
+            bail!("备份包内容与远端文件名不符");
+        }
+        fs::rename(&temp, backup_dir)?;
+        Ok(record)
+    });
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&temp);
+    }
+    result
+}
+
+fn extract_download(zip_path: &Path, backup_dir: &Path, passphrase: Option<&str>) -> anyhow::Result<Backup> {
+    if !crypto::is_encrypted(zip_path) {
+        return archive::unpack(zip_path, backup_dir);
+    }
+    let passphrase = passphrase.ok_or_else(|| anyhow!(String::from(msg("remote.locked"))))?;
+    let plain = zip_path.with_extension("plain");
+    let result = crypto::decrypt_file(zip_path, &plain, passphrase).and_then(|_| archive::unpack(&plain, backup_dir));
+    let _ = fs::remove_file(&plain);
+    result
+}
+
+/// 推送远端没有的备份，并拉取本地没有的备份
+fn sync_files(
+    backend: &dyn StorageBackend,
+    local: &[Backup],
+    data_root: &Path,
+    passphrase: Option<&str>,
+    report: &mut SyncReport,
+) -> anyhow::Result<Vec<Backup>> {
+    let tmp_dir = std::env::temp_dir().join("svld_sync");
+    fs::create_dir_all(&tmp_dir)?;
+
+    let remote: Vec<String> = backend
+        .list()?
+        .into_iter()
+        .filter_map(|o| archive::digest_of(&o.key).map(|d| d.to_string()))
+        .collect();
+    let remote_digests: HashSet<&str> = remote.iter().map(|d| d.as_str()).collect();
+    let local_digests: HashSet<&str> = local.iter().map(|b| b.digest.as_str()).collect();
+
+    for backup in local.iter().filter(|b| !remote_digests.contains(b.digest.as_str())) {
+        let key = archive::archive_key(&backup.digest);
+        let zip_path = tmp_dir.join(&key);
+        let result = pack_for_upload(backup, data_root, &zip_path, passphrase)
+            .and_then(|_| backend.upload(&key, &zip_path));
+        let _ = fs::remove_file(&zip_path);
+        match result {
+            Ok(()) => report.pushed.push(display_name(backup)),
+            Err(e) => {
+                error!("上传备份 {} 失败: {}", backup.digest, e);
+                report.failed.push(format!("{}: {}", display_name(backup), i18n::translate(&e.to_string())));
+            }
+        }
+    }
+
+    let mut pulled = Vec::new();
+    for digest in remote.iter().filter(|d| !local_digests.contains(d.as_str())) {
+        let key = archive::archive_key(digest);
+        let zip_path = tmp_dir.join(&key);
+        let dir_name = format!("backup_{}", &digest[..12]);
+        let result = backend
+            .download(&key, &zip_path)
+            .and_then(|_| unpack_download(&zip_path, &data_root.join(&dir_name), digest, passphrase));
+        let _ = fs::remove_file(&zip_path);
+        match result {
+            Ok(record) => {
+                report.pulled.push(display_name(&record));
+                pulled.push(Backup { path: dir_name, ..record });
+            }
+            Err(e) => {
+                error!("下载备份 {} 失败: {}", digest, e);
+                report.failed.push(format!("{}: {}", key, i18n::translate(&e.to_string())));
+            }
+        }
+    }
+    Ok(pulled)
+}
+
+#[tauri::command]
+pub async fn sync_backups() -> Reply<SyncBackups> {
+    info!("[sync_backups] 开始同步");
+    let _busy = busy::begin()?;
+    // 开启备份加密后，上传到远端的包也要加密
+    let passphrase = crypto::resolve_passphrase(None).filter(|_| EncryptionConfig::load().enabled);
+    if EncryptionConfig::load().enabled && passphrase.is_none() {
+        return Err(msg("backup.locked").into());
+    }
+    let backend = connect_backend().await?;
+
+    let db_path = db_path::get_db_path().map_err(|e| {
+        error!("获取数据库路径失败: {}", e);
+        e.to_string()
+    })?;
+    let mut conn = Db::new(db_path).await.map_err(|e| {
+        error!("建立数据库连接出错: {}", e);
+        e.to_string()
+    })?;
+    let local = Db::get_all_backup(&mut conn).await.map_err(|e| {
+        error!("获取已有存档失败: {}", e);
+        e.to_string()
+    })?;
+    let data_root = PathBuf::from(path::get_data_path()?);
+
+    let (mut report, pulled) = tokio::task::spawn_blocking(move || {
+        let mut report = SyncReport::default();
+        let pulled = sync_files(backend.as_ref(), &local, &data_root, passphrase.as_deref(), &mut report);
+        pulled.map(|pulled| (report, pulled))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| {
+        error!("同步失败: {}", e);
+        e.to_string()
+    })?;
+
+    // 父节点 id 只在原来的机器上有意义，拉下来的备份各自成为根节点
+    for record in pulled {
+        let backup = Backup {
+            id: 0,
+            parent_id: None,
+            ..record
+        };
+        let stored = match Db::store_backup(&backup, &mut conn).await {
+            Ok(id) => Db::set_tags(&mut conn, id, &backup.tags).await.map(|_| id),
+            Err(e) => Err(e),
+        };
+        match stored {
+            Ok(id) => events::emit(BackupCreated { backup: Backup { id, ..backup } }),
+            Err(e) => {
+                error!("保存拉取的备份记录失败: {}", e);
+                report.failed.push(format!("{}: {}", display_name(&backup), e));
+            }
+        }
+    }
+
+    info!(
+        "同步完成: 上传 {} 个, 下载 {} 个, 失败 {} 个",
+        report.pushed.len(),
+        report.pulled.len(),
+        report.failed.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::local::LocalBackend;
+    use time::OffsetDateTime;
+
+    #[test]
+    fn test_sync_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let backend = LocalBackend::new(root.join("remote").to_str().unwrap()).unwrap();
+
+        // 第一台机器上的备份
+        let machine_a = root.join("a");
+        let backup = Backup {
+            id: 1,
+            name: Some("a".to_string()),
+            digest: "0123456789ab".repeat(5) + "cdef",
+            size: 0,
+            path: "backup_0123456789ab".to_string(),
+            save_time: OffsetDateTime::UNIX_EPOCH,
+            more_info: None,
+            notes: None,
+            pinned: false,
+            parent_id: None,
+            run_id: None,
+            tags: Vec::new(),
+        };
+        fs::create_dir_all(machine_a.join(&backup.path)).unwrap();
+        fs::write(machine_a.join(&backup.path).join("player.xml"), "<Entity/>").unwrap();
+
+        let mut report = SyncReport::default();
+        let pulled = sync_files(&backend, std::slice::from_ref(&backup), &machine_a, None, &mut report).unwrap();
+        assert_eq!(report.pushed, vec!["a"]);
+        assert!(pulled.is_empty());
+
+        // 第二台机器拉取
+        let machine_b = root.join("b");
+        let mut report = SyncReport::default();
+        let pulled = sync_files(&backend, &[], &machine_b, None, &mut report).unwrap();
+        assert_eq!(report.pulled, vec!["a"]);
+        assert_eq!(pulled[0].digest, backup.digest);
+        assert_eq!(pulled[0].path, "backup_0123456789ab");
+        assert!(machine_b.join("backup_0123456789ab").join("player.xml").exists());
+
+        // 包里的记录和文件名对不上时不接收
+        let forged = "f".repeat(64);
+        fs::rename(
+            root.join("remote").join(archive::archive_key(&backup.digest)),
+            root.join("remote").join(archive::archive_key(&forged)),
+        )
+        .unwrap();
+        let machine_c = root.join("c");
+        let mut report = SyncReport::default();
+        let pulled = sync_files(&backend, &[], &machine_c, None, &mut report).unwrap();
+        assert!(pulled.is_empty());
+        assert_eq!(report.failed.len(), 1);
+        assert!(!machine_c.join("backup_ffffffffffff").exists());
+        assert!(!machine_c.join("backup_ffffffffffff.part").exists());
+        fs::rename(
+            root.join("remote").join(archive::archive_key(&forged)),
+            root.join("remote").join(archive::archive_key(&backup.digest)),
+        )
+        .unwrap();
+
+        // 已经同步过的不会重复传输
+        let mut report = SyncReport::default();
+        sync_files(&backend, &[backup], &machine_a, None, &mut report).unwrap();
+        assert!(report.pushed.is_empty() && report.pulled.is_empty());
+    }
+}
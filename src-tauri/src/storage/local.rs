@@ -0,0 +1,81 @@
+/// 本地目录（另一块硬盘、U 盘或挂载的网络共享）
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::Context;
+use crate::storage::{RemoteObject, StorageBackend};
+
+pub struct LocalBackend {
+    root: PathBuf,
+}
+
+impl LocalBackend {
+    pub fn new(root: &str) -> anyhow::Result<LocalBackend> {
+        let root = PathBuf::from(root);
+        fs::create_dir_all(&root).with_context(|| format!("无法创建目录: {}", root.display()))?;
+        Ok(LocalBackend { root })
+    }
+}
+
+impl StorageBackend for LocalBackend {
+    fn upload(&self, key: &str, local: &Path) -> anyhow::Result<()> {
+        // 先写临时文件再改名，中途失败不会留下不完整的 zip
+        let tmp = self.root.join(format!("{}.part", key));
+        fs::copy(local, &tmp).with_context(|| format!("复制到 {} 失败", tmp.display()))?;
+        fs::rename(&tmp, self.root.join(key))?;
+        Ok(())
+    }
+
+    fn download(&self, key: &str, local: &Path) -> anyhow::Result<()> {
+        fs::copy(self.root.join(key), local).with_context(|| format!("读取 {} 失败", key))?;
+        Ok(())
+    }
+
+    fn list(&self) -> anyhow::Result<Vec<RemoteObject>> {
+        let mut objects = Vec::new();
+        for entry in fs::read_dir(&self.root)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                objects.push(RemoteObject {
+                    key: entry.file_name().to_string_lossy().to_string(),
+                    size: metadata.len(),
+                });
+            }
+        }
+        Ok(objects)
+    }
+
+    fn delete(&self, key: &str) -> anyhow::Result<()> {
+        fs::remove_file(self.root.join(key)).with_context(|| format!("删除 {} 失败", key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_backend() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let backend = LocalBackend::new(root.join("remote").to_str().unwrap()).unwrap();
+
+        let src = root.join("a.zip");
+        fs::write(&src, b"zip data").unwrap();
+        backend.upload("a.zip", &src).unwrap();
+        assert_eq!(backend.list().unwrap(), vec![RemoteObject { key: "a.zip".to_string(), size: 8 }]);
+
+        let dst = root.join("b.zip");
+        backend.download("a.zip", &dst).unwrap();
+        assert_eq!(fs::read(&dst).unwrap(), b"zip data");
+
+        backend.delete("a.zip").unwrap();
+        assert!(backend.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_local_roundtrip() {
+        let root = tempfile::tempdir().unwrap();
+        crate::storage::roundtrip(&LocalBackend::new(root.path().to_str().unwrap()).unwrap());
+    }
+}
@@ -0,0 +1,154 @@
+/// 远程存储：把备份打包成 zip 复制到其他机器上，磁盘坏了也能找回
+/// 每个备份在远端是一个 {digest}.zip，里面带着数据库记录，拉取时按 digest 判断本地是否已有
+pub mod archive;
+pub mod commands;
+pub mod local;
+pub mod s3;
+pub mod sftp;
+pub mod webdav;
+
+use std::path::Path;
+use anyhow::Context;
+
+pub use svld_shared::storage::RemoteConfig;
+
+/// 远端的一个文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteObject {
+    pub key: String,
+    pub size: u64,
+}
+
+/// 各种远程存储的统一接口，key 是不含路径的文件名
+/// 都是阻塞调用，在 spawn_blocking 中使用
+pub trait StorageBackend: Send {
+    fn upload(&self, key: &str, local: &Path) -> anyhow::Result<()>;
+    fn download(&self, key: &str, local: &Path) -> anyhow::Result<()>;
+    fn list(&self) -> anyhow::Result<Vec<RemoteObject>>;
+    fn delete(&self, key: &str) -> anyhow::Result<()>;
+}
+
+pub const CONFIG_SECTION: &str = "remote";
+const KEYRING_SERVICE: &str = "svld";
+const KEYRING_USER: &str = "remote-storage";
+
+/// 读取保存在系统凭据管理器中的密码或密钥
+pub fn load_secret() -> anyhow::Result<Option<String>> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)?;
+    match entry.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e).context("读取凭据失败"),
+    }
+}
+
+/// 保存密码或密钥，None 表示删除
+pub fn save_secret(secret: Option<&str>) -> anyhow::Result<()> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)?;
+    match secret {
+        Some(secret) => entry.set_password(secret).context("保存凭据失败"),
+        None => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e).context("删除凭据失败"),
+        },
+    }
+}
+
+/// 按配置创建存储后端
+pub fn open_backend(config: &RemoteConfig, secret: Option<String>) -> anyhow::Result<Box<dyn StorageBackend>> {
+    let backend: Box<dyn StorageBackend> = match config {
+        RemoteConfig::Local { path } => Box::new(local::LocalBackend::new(path)?),
+        RemoteConfig::Sftp { host, port, username, root } => Box::new(sftp::SftpBackend::connect(
+            host,
+            port.unwrap_or(22),
+            username,
+            secret.as_deref(),
+            root,
+        )?),
+        RemoteConfig::Webdav { url, username } => {
+            Box::new(webdav::WebdavBackend::new(url, username.clone(), secret)?)
+        }
+        RemoteConfig::S3 { endpoint, region, bucket, access_key, prefix, path_style } => {
+            let secret_key = secret.context("S3 需要填写 Secret Key")?;
+            Box::new(s3::S3Backend::new(s3::S3Options {
+                endpoint: endpoint.clone(),
+                region: region.clone(),
+                bucket: bucket.clone(),
+                access_key: access_key.clone(),
+                secret_key,
+                prefix: prefix.clone().unwrap_or_default(),
+                path_style: *path_style,
+            })?)
+        }
+    };
+    Ok(backend)
+}
+
+/// 取 <tag>...</tag> 或 <ns:tag>...</ns:tag> 的所有内容，用于解析 WebDAV 和 S3 返回的 xml
+pub(crate) fn xml_values<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let name = &rest[..end];
+        let local_name = name.rsplit(':').next().unwrap_or(name);
+        if local_name != tag {
+            continue;
+        }
+        let body = &rest[end + 1..];
+        let close = format!("</{}>", name);
+        if let Some(close_pos) = body.find(&close) {
+            values.push(&body[..close_pos]);
+            rest = &body[close_pos + close.len()..];
+        }
+    }
+    values
+}
+
+/// 上传、列出、下载、删除各走一遍，用于需要真实服务的后端测试
+#[cfg(test)]
+pub(crate) fn roundtrip(backend: &dyn StorageBackend) {
+    let scratch = tempfile::tempdir().unwrap();
+    let key = "svld_roundtrip.zip";
+    let src = scratch.path().join("up.zip");
+    std::fs::write(&src, b"zip data").unwrap();
+    backend.upload(key, &src).unwrap();
+    // 再传一次覆盖已有的文件
+    backend.upload(key, &src).unwrap();
+
+    let objects = backend.list().unwrap();
+    assert!(objects.contains(&RemoteObject { key: key.to_string(), size: 8 }), "{:?}", objects);
+    assert!(!objects.iter().any(|o| o.key.ends_with(".part")), "{:?}", objects);
+
+    let dst = scratch.path().join("down.zip");
+    backend.download(key, &dst).unwrap();
+    assert_eq!(std::fs::read(&dst).unwrap(), b"zip data");
+
+    backend.delete(key).unwrap();
+    assert!(!backend.list().unwrap().iter().any(|o| o.key == key));
+}
+
+/// 读取测试用的环境变量，没有设置时说明需要哪个变量
+#[cfg(test)]
+pub(crate) fn test_env(name: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| panic!("需要设置环境变量 {}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xml_values() {
+        let xml = r#"<D:multistatus xmlns:D="DAV:"><D:response><D:href>/dav/a.zip</D:href></D:response>
+            <D:response><D:href>/dav/b.zip</D:href><D:getcontentlength>12</D:getcontentlength></D:response></D:multistatus>"#;
+        assert_eq!(xml_values(xml, "href"), vec!["/dav/a.zip", "/dav/b.zip"]);
+        assert_eq!(xml_values(xml, "getcontentlength"), vec!["12"]);
+
+        let xml = "<ListBucketResult><Contents><Key>svld/a.zip</Key><Size>3</Size></Contents></ListBucketResult>";
+        assert_eq!(xml_values(xml, "Key"), vec!["svld/a.zip"]);
+    }
+}
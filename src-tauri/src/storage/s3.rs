@@ -0,0 +1,266 @@
+/// S3 兼容的对象存储（AWS S3、MinIO、Cloudflare R2 等），请求使用 AWS Signature V4 签名
+use std::fs::File;
+use std::path::Path;
+use anyhow::{bail, Context};
+use reqwest::blocking::{Client, Response};
+use reqwest::{Method, Url};
+use sha2::{Digest, Sha256};
+use time::macros::format_description;
+use time::OffsetDateTime;
+use crate::storage::{xml_values, RemoteObject, StorageBackend};
+
+pub struct S3Options {
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// 对象名前缀，例如 noita/
+    pub prefix: String,
+    pub path_style: bool,
+}
+
+pub struct S3Backend {
+    client: Client,
+    options: S3Options,
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    const BLOCK_SIZE: usize = 64;
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(Sha256::digest(key).as_slice());
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(data);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().to_vec()
+}
+
+fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let k_date = hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date.as_bytes());
+    let k_region = hmac_sha256(&k_date, region.as_bytes());
+    let k_service = hmac_sha256(&k_region, service.as_bytes());
+    hmac_sha256(&k_service, b"aws4_request")
+}
+
+fn check(response: Response, action: &str) -> anyhow::Result<Response> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().unwrap_or_default();
+        let message = xml_values(&body, "Message").first().map(|m| m.to_string()).unwrap_or_default();
+        bail!("{}失败: HTTP {} {}", action, status, message);
+    }
+    Ok(response)
+}
+
+impl S3Backend {
+    pub fn new(options: S3Options) -> anyhow::Result<S3Backend> {
+        Url::parse(&options.endpoint).with_context(|| format!("无效的 S3 地址: {}", options.endpoint))?;
+        Ok(S3Backend { client: Client::new(), options })
+    }
+
+    /// 对象 key 对应的地址，key 为空时是桶本身
+    fn url(&self, key: &str) -> anyhow::Result<Url> {
+        let endpoint = self.options.endpoint.trim_end_matches('/');
+        let object = key
+            .split('/')
+            .map(|segment| urlencoding::encode(segment).to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        let url = if self.options.path_style {
+            if object.is_empty() {
+                format!("{}/{}", endpoint, self.options.bucket)
+            } else {
+                format!("{}/{}/{}", endpoint, self.options.bucket, object)
+            }
+        } else {
+            let mut url = Url::parse(endpoint)?;
+            let host = format!("{}.{}", self.options.bucket, url.host_str().unwrap_or_default());
+            url.set_host(Some(&host))?;
+            format!("{}/{}", url.as_str().trim_end_matches('/'), object)
+        };
+        Ok(Url::parse(&url)?)
+    }
+
+    /// 发送签名后的请求，query 中的参数不需要预先编码
+    fn send(&self, method: Method, key: &str, query: &[(&str, &str)], body: Option<File>) -> anyhow::Result<Response> {
+        let mut url = self.url(key)?;
+        let mut query: Vec<(String, String)> = query
+            .iter()
+            .map(|(k, v)| (urlencoding::encode(k).to_string(), urlencoding::encode(v).to_string()))
+            .collect();
+        query.sort();
+        let canonical_query = query.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("&");
+        url.set_query(if canonical_query.is_empty() { None } else { Some(&canonical_query) });
+
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let now = OffsetDateTime::now_utc();
+        let amz_date = now.format(format_description!("[year][month][day]T[hour][minute][second]Z"))?;
+        let date = &amz_date[..8];
+        // 不对请求体签名，上传大文件时不用先读一遍
+        let payload_hash = "UNSIGNED-PAYLOAD";
+
+        let canonical_request = format!(
+            "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+            method.as_str(),
+            url.path(),
+            canonical_query,
+            host,
+            payload_hash,
+            amz_date,
+            payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.options.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let key = signing_key(&self.options.secret_key, date, &self.options.region, "s3");
+        let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+            self.options.access_key, scope, signature
+        );
+
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", amz_date)
+            .header("Authorization", authorization);
+        if let Some(file) = body {
+            request = request.body(file);
+        }
+        Ok(request.send()?)
+    }
+
+    fn object_key(&self, key: &str) -> String {
+        format!("{}{}", self.options.prefix, key)
+    }
+}
+
+impl StorageBackend for S3Backend {
+    fn upload(&self, key: &str, local: &Path) -> anyhow::Result<()> {
+        let file = File::open(local)?;
+        let response = self.send(Method::PUT, &self.object_key(key), &[], Some(file))?;
+        check(response, &format!("上传 {} ", key))?;
+        Ok(())
+    }
+
+    fn download(&self, key: &str, local: &Path) -> anyhow::Result<()> {
+        let response = self.send(Method::GET, &self.object_key(key), &[], None)?;
+        let mut response = check(response, &format!("下载 {} ", key))?;
+        let mut file = File::create(local)?;
+        response.copy_to(&mut file).with_context(|| format!("下载 {} 失败", key))?;
+        Ok(())
+    }
+
+    fn list(&self) -> anyhow::Result<Vec<RemoteObject>> {
+        let mut objects = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", self.options.prefix.as_str())];
+            if let Some(token) = &token {
+                query.push(("continuation-token", token.as_str()));
+            }
+            let response = self.send(Method::GET, "", &query, None)?;
+            let xml = check(response, "列出文件")?.text()?;
+
+            for contents in xml_values(&xml, "Contents") {
+                let Some(key) = xml_values(contents, "Key").first().copied() else {
+                    continue;
+                };
+                let key = key.strip_prefix(self.options.prefix.as_str()).unwrap_or(key);
+                // 前缀下的子目录不属于备份
+                if key.is_empty() || key.contains('/') {
+                    continue;
+                }
+                let size = xml_values(contents, "Size").first().and_then(|s| s.parse().ok()).unwrap_or(0);
+                objects.push(RemoteObject { key: key.to_string(), size });
+            }
+
+            token = xml_values(&xml, "NextContinuationToken").first().map(|t| t.to_string());
+            if token.is_none() {
+                break;
+            }
+        }
+        Ok(objects)
+    }
+
+    fn delete(&self, key: &str) -> anyhow::Result<()> {
+        let response = self.send(Method::DELETE, &self.object_key(key), &[], None)?;
+        check(response, &format!("删除 {} ", key))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{roundtrip, test_env};
+
+    #[test]
+    fn test_signing() {
+        // RFC 4231 测试用例 2
+        assert_eq!(
+            hex::encode(hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // AWS 文档中派生签名密钥的示例
+        assert_eq!(
+            hex::encode(signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam")),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_url() {
+        let options = |path_style| S3Options {
+            endpoint: "http://127.0.0.1:9000/".to_string(),
+            region: "us-east-1".to_string(),
+            bucket: "saves".to_string(),
+            access_key: String::new(),
+            secret_key: String::new(),
+            prefix: "noita/".to_string(),
+            path_style,
+        };
+        let backend = S3Backend::new(options(true)).unwrap();
+        assert_eq!(backend.url("noita/a b.zip").unwrap().as_str(), "http://127.0.0.1:9000/saves/noita/a%20b.zip");
+        assert_eq!(backend.url("").unwrap().as_str(), "http://127.0.0.1:9000/saves");
+
+        let backend = S3Backend::new(S3Options { endpoint: "https://s3.amazonaws.com".to_string(), ..options(false) }).unwrap();
+        assert_eq!(backend.url("a.zip").unwrap().as_str(), "https://saves.s3.amazonaws.com/a.zip");
+    }
+
+    /// 用本地的 MinIO 测试，例如
+    /// minio server /tmp/minio 然后建好桶，设置 SVLD_TEST_S3_ENDPOINT=http://127.0.0.1:9000、
+    /// SVLD_TEST_S3_BUCKET、SVLD_TEST_S3_ACCESS_KEY、SVLD_TEST_S3_SECRET_KEY 后
+    /// cargo test -- --ignored s3_roundtrip
+    #[test]
+    #[ignore = "需要本地的 S3 服务"]
+    fn test_s3_roundtrip() {
+        let backend = S3Backend::new(S3Options {
+            endpoint: test_env("SVLD_TEST_S3_ENDPOINT"),
+            region: std::env::var("SVLD_TEST_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            bucket: test_env("SVLD_TEST_S3_BUCKET"),
+            access_key: test_env("SVLD_TEST_S3_ACCESS_KEY"),
+            secret_key: test_env("SVLD_TEST_S3_SECRET_KEY"),
+            prefix: "svld-test/".to_string(),
+            path_style: true,
+        })
+        .unwrap();
+        roundtrip(&backend);
+    }
+}
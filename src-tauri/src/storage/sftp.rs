@@ -0,0 +1,104 @@
+/// SFTP，没有填写密码时使用 ssh-agent 登录
+use std::fs::File;
+use std::io;
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use anyhow::{bail, Context};
+use ssh2::{Session, Sftp};
+use crate::storage::{RemoteObject, StorageBackend};
+
+pub struct SftpBackend {
+    // Sftp 依赖 Session 保持连接
+    _session: Session,
+    sftp: Sftp,
+    root: PathBuf,
+}
+
+impl SftpBackend {
+    pub fn connect(host: &str, port: u16, username: &str, password: Option<&str>, root: &str) -> anyhow::Result<SftpBackend> {
+        let tcp = TcpStream::connect((host, port)).with_context(|| format!("无法连接 {}:{}", host, port))?;
+        let mut session = Session::new()?;
+        session.set_tcp_stream(tcp);
+        session.handshake().context("SSH 握手失败")?;
+        match password {
+            Some(password) => session.userauth_password(username, password),
+            None => session.userauth_agent(username),
+        }
+        .context("SFTP 登录失败")?;
+        if !session.authenticated() {
+            bail!("SFTP 登录失败");
+        }
+
+        let sftp = session.sftp()?;
+        let root = PathBuf::from(root);
+        if sftp.stat(&root).is_err() {
+            sftp.mkdir(&root, 0o755).with_context(|| format!("无法创建远端目录 {}", root.display()))?;
+        }
+        Ok(SftpBackend { _session: session, sftp, root })
+    }
+}
+
+impl StorageBackend for SftpBackend {
+    fn upload(&self, key: &str, local: &Path) -> anyhow::Result<()> {
+        let tmp = self.root.join(format!("{}.part", key));
+        let mut src = File::open(local)?;
+        let mut dst = self.sftp.create(&tmp)?;
+        io::copy(&mut src, &mut dst).with_context(|| format!("上传 {} 失败", key))?;
+        drop(dst);
+        let target = self.root.join(key);
+        // 目标已存在时 rename 会失败
+        let _ = self.sftp.unlink(&target);
+        self.sftp.rename(&tmp, &target, None)?;
+        Ok(())
+    }
+
+    fn download(&self, key: &str, local: &Path) -> anyhow::Result<()> {
+        let mut src = self.sftp.open(self.root.join(key))?;
+        let mut dst = File::create(local)?;
+        io::copy(&mut src, &mut dst).with_context(|| format!("下载 {} 失败", key))?;
+        Ok(())
+    }
+
+    fn list(&self) -> anyhow::Result<Vec<RemoteObject>> {
+        let entries = self.sftp.readdir(&self.root)?;
+        Ok(entries
+            .into_iter()
+            .filter(|(_, stat)| stat.is_file())
+            .filter_map(|(path, stat)| {
+                Some(RemoteObject {
+                    key: path.file_name()?.to_string_lossy().to_string(),
+                    size: stat.size.unwrap_or(0),
+                })
+            })
+            .collect())
+    }
+
+    fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.sftp.unlink(&self.root.join(key)).with_context(|| format!("删除 {} 失败", key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{roundtrip, test_env};
+
+    /// 用本地的 SSH 服务测试，例如
+    /// docker run -p 2222:22 atmoz/sftp svld:svld:::upload 然后设置
+    /// SVLD_TEST_SFTP_HOST=127.0.0.1、SVLD_TEST_SFTP_PORT=2222、SVLD_TEST_SFTP_USER=svld、
+    /// SVLD_TEST_SFTP_PASSWORD=svld、SVLD_TEST_SFTP_ROOT=/upload/svld 后 cargo test -- --ignored sftp_roundtrip
+    #[test]
+    #[ignore = "需要本地的 SSH 服务"]
+    fn test_sftp_roundtrip() {
+        let port = std::env::var("SVLD_TEST_SFTP_PORT").ok().and_then(|p| p.parse().ok()).unwrap_or(22);
+        let backend = SftpBackend::connect(
+            &test_env("SVLD_TEST_SFTP_HOST"),
+            port,
+            &test_env("SVLD_TEST_SFTP_USER"),
+            std::env::var("SVLD_TEST_SFTP_PASSWORD").ok().as_deref(),
+            &test_env("SVLD_TEST_SFTP_ROOT"),
+        )
+        .unwrap();
+        roundtrip(&backend);
+    }
+}
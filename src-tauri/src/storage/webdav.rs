@@ -0,0 +1,135 @@
+/// WebDAV（坚果云、Nextcloud 等）
+use std::fs::File;
+use std::path::Path;
+use anyhow::{bail, Context};
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::Method;
+use crate::storage::{xml_values, RemoteObject, StorageBackend};
+
+pub struct WebdavBackend {
+    client: Client,
+    /// 以 / 结尾的目录地址
+    base_url: String,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+fn check(response: Response, action: &str) -> anyhow::Result<Response> {
+    if !response.status().is_success() {
+        bail!("{}失败: HTTP {}", action, response.status());
+    }
+    Ok(response)
+}
+
+impl WebdavBackend {
+    pub fn new(url: &str, username: Option<String>, password: Option<String>) -> anyhow::Result<WebdavBackend> {
+        let base_url = format!("{}/", url.trim_end_matches('/'));
+        let backend = WebdavBackend { client: Client::new(), base_url, username, password };
+
+        // 目录不存在时创建，已存在会返回 405
+        let response = backend.request(Method::from_bytes(b"MKCOL")?, "").send()?;
+        if !response.status().is_success() && response.status().as_u16() != 405 {
+            bail!("无法创建 WebDAV 目录: HTTP {}", response.status());
+        }
+        Ok(backend)
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}{}", self.base_url, urlencoding::encode(key))
+    }
+
+    fn request(&self, method: Method, key: &str) -> RequestBuilder {
+        let request = self.client.request(method, self.url(key));
+        match &self.username {
+            Some(username) => request.basic_auth(username, self.password.as_deref()),
+            None => request,
+        }
+    }
+}
+
+impl StorageBackend for WebdavBackend {
+    fn upload(&self, key: &str, local: &Path) -> anyhow::Result<()> {
+        // 先传到临时文件再移动过去，中途断开不会留下不完整的 zip
+        let tmp = format!("{}.part", key);
+        let file = File::open(local)?;
+        let response = self.request(Method::PUT, &tmp).body(file).send()?;
+        check(response, &format!("上传 {} ", key))?;
+
+        let response = self
+            .request(Method::from_bytes(b"MOVE")?, &tmp)
+            .header("Destination", self.url(key))
+            .header("Overwrite", "T")
+            .send()?;
+        if let Err(e) = check(response, &format!("移动 {} ", key)) {
+            let _ = self.request(Method::DELETE, &tmp).send();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn download(&self, key: &str, local: &Path) -> anyhow::Result<()> {
+        let response = self.request(Method::GET, key).send()?;
+        let mut response = check(response, &format!("下载 {} ", key))?;
+        let mut file = File::create(local)?;
+        response.copy_to(&mut file).with_context(|| format!("下载 {} 失败", key))?;
+        Ok(())
+    }
+
+    fn list(&self) -> anyhow::Result<Vec<RemoteObject>> {
+        let body = r#"<?xml version="1.0" encoding="utf-8"?><D:propfind xmlns:D="DAV:"><D:prop><D:getcontentlength/></D:prop></D:propfind>"#;
+        let response = self
+            .request(Method::from_bytes(b"PROPFIND")?, "")
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml")
+            .body(body)
+            .send()?;
+        let xml = check(response, "列出文件")?.text()?;
+
+        let mut objects = Vec::new();
+        for response in xml_values(&xml, "response") {
+            let Some(href) = xml_values(response, "href").first().copied() else {
+                continue;
+            };
+            // 目录本身和子目录以 / 结尾
+            let name = href.trim_end_matches('/').rsplit('/').next().unwrap_or_default();
+            if href.ends_with('/') || name.is_empty() {
+                continue;
+            }
+            let size = xml_values(response, "getcontentlength")
+                .first()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(0);
+            let key = urlencoding::decode(name).map(|k| k.to_string()).unwrap_or_else(|_| name.to_string());
+            objects.push(RemoteObject { key, size });
+        }
+        Ok(objects)
+    }
+
+    fn delete(&self, key: &str) -> anyhow::Result<()> {
+        let response = self.request(Method::DELETE, key).send()?;
+        check(response, &format!("删除 {} ", key))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{roundtrip, test_env};
+
+    /// 用本地的 WebDAV 服务测试，例如
+    /// rclone serve webdav /tmp/dav --addr 127.0.0.1:8080 然后设置
+    /// SVLD_TEST_WEBDAV_URL=http://127.0.0.1:8080/svld 后 cargo test -- --ignored webdav_roundtrip，
+    /// 需要登录时再设置 SVLD_TEST_WEBDAV_USER 和 SVLD_TEST_WEBDAV_PASSWORD
+    #[test]
+    #[ignore = "需要本地的 WebDAV 服务"]
+    fn test_webdav_roundtrip() {
+        let backend = WebdavBackend::new(
+            &test_env("SVLD_TEST_WEBDAV_URL"),
+            std::env::var("SVLD_TEST_WEBDAV_USER").ok(),
+            std::env::var("SVLD_TEST_WEBDAV_PASSWORD").ok(),
+        )
+        .unwrap();
+        roundtrip(&backend);
+    }
+}
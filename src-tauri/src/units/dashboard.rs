@@ -1,19 +1,17 @@
+use svld_shared::commands::GetDashboardStats;
+use svld_shared::Reply;
 use crate::db;
-use serde::Serialize;
 use log::{debug, error};
 use crate::db::Db;
 use crate::units::path;
 use crate::units::db_path;
+use crate::units::i18n::msg;
+use crate::backup::space;
 
-#[derive(Serialize, Debug)]
-pub struct DashboardStats {
-    backup_count: usize,
-    total_size: i64,    // 单位字节
-    is_ready: bool,     // 后端健康
-}
+pub use svld_shared::backup::DashboardStats;
 
 #[tauri::command]
-pub async fn get_dashboard_stats() -> Result<DashboardStats, String> {
+pub async fn get_dashboard_stats() -> Reply<GetDashboardStats> {
     let mut total_size : i64 = 0;
     let is_ready : bool = true;
 
@@ -33,7 +31,7 @@ pub async fn get_dashboard_stats() -> Result<DashboardStats, String> {
 
     let backups = db::Db::get_all_backup(&mut conn).await.map_err(|_e| {
         error!("查询存档错误");
-        "查询数据库失败".to_string()
+        String::from(msg("dashboard.query_failed"))
     })?;
     // 计算文件夹数量
     let count = backups.len();
@@ -45,11 +43,15 @@ pub async fn get_dashboard_stats() -> Result<DashboardStats, String> {
 
     debug!("存档数:{} ，总大小：{}", total_size, count);
 
+    let disk = space::data_disk_space()?;
+
     Ok(
         DashboardStats {
             backup_count : count,
-            total_size,
+            total_size: total_size.max(0) as u64,
             is_ready,
+            free_space: disk.available,
+            low_space: disk.low,
         }
     )
 }
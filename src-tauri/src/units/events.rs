@@ -0,0 +1,19 @@
+/// 推送给前端的事件，事件的类型和名字定义在 svld_shared::events 中
+use std::sync::OnceLock;
+use svld_shared::Event;
+use tauri::{AppHandle, Emitter};
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// 在 setup 中调用一次
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+/// 发给所有窗口。还没初始化（例如测试中）或发送失败时忽略，
+/// 这里不写日志，因为日志跟踪本身也通过事件发送
+pub fn emit<E: Event + Clone>(event: E) {
+    if let Some(app) = APP.get() {
+        let _ = app.emit(E::NAME, event);
+    }
+}
@@ -1,39 +1,26 @@
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::{env, fs};
+use std::fs;
 use log::{debug, error, info};
+use tauri::{AppHandle, Manager};
+use svld_shared::commands::OpenBackup;
+use svld_shared::Reply;
 use crate::db::Db;
 use crate::units::db_path;
+use crate::units::i18n::msg;
 
-#[tauri::command]
-pub async fn open_log() -> Result<(), String> {
-    // 获取 LOCALAPPDATA 环境变量 (通常是 C:\Users\用户名\AppData\Local)
-    let local_app_data = env::var("LOCALAPPDATA")
-        .map_err(|_| "无法获取 LOCALAPPDATA 环境变量".to_string())?;
-
-    // 拼接日志完整路径
-    let log_path = Path::new(&local_app_data)
-        .join("com.auceptin.noita-svld")
-        .join("logs")
-        .join("app.log");
-
-    if !log_path.exists() {
-        return Err(format!("日志文件不存在: {:?}", log_path));
-    }
-
-    // 在 Windows 资源管理器中打开并选中该文件
-    // /select, 后面紧跟路径可以实现“在文件夹中显示”并高亮该文件
-    Command::new("explorer")
-        .arg("/select,")
-        .arg(log_path)
-        .spawn()
-        .map_err(|e| format!("无法打开资源管理器: {}", e))?;
-
-    Ok(())
+/// tauri_plugin_log 写入的日志文件，和 lib.rs 中 LogDir 的文件名对应
+pub fn log_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_log_dir().map_err(|e| {
+        error!("获取日志目录失败: {}", e);
+        e.to_string()
+    })?;
+    Ok(dir.join("app.log"))
 }
 
 #[tauri::command]
-pub async fn open_backup(id: i32) -> Result<(), String> {
+pub async fn open_backup(args: OpenBackup) -> Reply<OpenBackup> {
+    let OpenBackup { id } = args;
     debug!("[open_backup] id = {}", id);
 
     let db_path = db_path::get_db_path().map_err(|e| {
@@ -58,11 +45,13 @@ pub async fn open_backup(id: i32) -> Result<(), String> {
 
     let backup = match backup {
         Some(b) => b,
-        None => return Err(format!("未找到 ID 为 {} 的备份", id)),
+        None => return Err(msg("backup.not_found").with("id", id).into()),
     };
 
     // 获取备份文件所在的父目录，或者是备份路径本身
-    let path = Path::new(&backup.path);
+    let data_path = crate::units::path::get_data_path()?;
+    let path = backup.resolve_dir(Path::new(&data_path));
+    let path = path.as_path();
 
     // 如果 backup.path 指向的是文件，我们打开它的父目录并选中它
     // 如果是目录，直接打开该目录
@@ -71,15 +60,27 @@ pub async fn open_backup(id: i32) -> Result<(), String> {
             .arg("/select,")
             .arg(path)
             .spawn()
-            .map_err(|e| format!("打开文件夹失败: {}", e))?;
+            .map_err(|e| String::from(msg("file.open_failed").with("error", e)))?;
     } else if path.is_dir() {
         Command::new("explorer")
             .arg(path)
             .spawn()
-            .map_err(|e| format!("打开文件夹失败: {}", e))?;
+            .map_err(|e| String::from(msg("file.open_failed").with("error", e)))?;
     } else {
-        return Err("备份路径不存在或无效".to_string());
+        return Err(msg("file.backup_path_invalid").into());
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+/// 在资源管理器中打开备份目录
+pub fn open_data_dir() -> Result<(), String> {
+    let data_path = crate::units::path::get_data_path()?;
+    if !Path::new(&data_path).is_dir() {
+        return Err(msg("file.data_dir_missing").with("path", data_path).into());
+    }
+    Command::new("explorer")
+        .arg(&data_path)
+        .spawn()
+        .map_err(|e| String::from(msg("file.open_failed").with("error", e)))?;
+    Ok(())
+}
@@ -0,0 +1,43 @@
+/// 检测 Noita 是否在运行
+use std::time::Duration;
+use log::info;
+use svld_shared::commands::IsGameRunning;
+use svld_shared::Reply;
+use svld_shared::events::GameStatusChanged;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+use crate::units::events;
+
+const PROCESS_NAMES: &[&str] = &["noita.exe", "noita_dev.exe", "noita"];
+/// 检查游戏进程的间隔
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 游戏运行时覆盖存档，退出游戏时会被内存里的数据写回
+pub fn is_noita_running() -> bool {
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing());
+    system.processes().values().any(|process| {
+        let name = process.name().to_string_lossy().to_lowercase();
+        PROCESS_NAMES.contains(&name.as_str())
+    })
+}
+
+/// 启动后台循环，游戏启动或退出时发出 GameStatusChanged，在 setup 中调用一次
+pub fn watch() {
+    tauri::async_runtime::spawn(async {
+        let mut running = is_noita_running();
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+            let now = is_noita_running();
+            if now != running {
+                running = now;
+                info!("Noita {}", if running { "已启动" } else { "已退出" });
+                events::emit(GameStatusChanged { running });
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn is_game_running() -> Reply<IsGameRunning> {
+    Ok(is_noita_running())
+}
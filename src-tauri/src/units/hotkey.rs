@@ -0,0 +1,168 @@
+/// 全局快捷键：游戏中不用切出去就能快速备份和读档
+use std::sync::Mutex;
+use log::{debug, error, info, warn};
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+use svld_shared::commands::{GetHotkeys, LoadBackup, SaveBackup, SaveHotkeys};
+use svld_shared::Reply;
+use crate::backup::commands::{load_backup, save_backup, LoadOutcome};
+use crate::backup::history;
+use crate::db::Db;
+use crate::units::settings::{ConfigManager, Section};
+use crate::units::i18n::{self, msg};
+use crate::units::notify::{self, Category, NotifyAction, Severity};
+use crate::units::{db_path, game};
+
+pub use svld_shared::settings::HotkeyConfig;
+
+pub const CONFIG_SECTION: &str = "hotkeys";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HotkeyAction {
+    QuickSave,
+    /// 还原最新的备份
+    RestoreLatest,
+    /// 还原当前存档的上一个备份，连续按可以一直往前退
+    RestorePrevious,
+}
+
+impl Section for HotkeyConfig {
+    const NAME: &'static str = CONFIG_SECTION;
+}
+
+/// 设置了的快捷键和对应的操作
+fn shortcuts(config: &HotkeyConfig) -> Vec<(HotkeyAction, &str)> {
+    [
+        (HotkeyAction::QuickSave, &config.quick_save),
+        (HotkeyAction::RestoreLatest, &config.restore_latest),
+        (HotkeyAction::RestorePrevious, &config.restore_previous),
+    ]
+    .into_iter()
+    .filter_map(|(action, accelerator)| {
+        let accelerator = accelerator.as_deref()?.trim();
+        (!accelerator.is_empty()).then_some((action, accelerator))
+    })
+    .collect()
+}
+
+/// 已注册的快捷键和对应的操作
+static BINDINGS: Mutex<Vec<(Shortcut, HotkeyAction)>> = Mutex::new(Vec::new());
+
+/// 按配置重新注册全部快捷键，返回注册失败的说明
+pub fn register_all(app: &AppHandle, config: &HotkeyConfig) -> Vec<String> {
+    let manager = app.global_shortcut();
+    if let Err(e) = manager.unregister_all() {
+        warn!("注销快捷键失败: {}", e);
+    }
+    let mut bindings = BINDINGS.lock().unwrap();
+    bindings.clear();
+
+    let mut errors = Vec::new();
+    for (action, accelerator) in shortcuts(config) {
+        let result = accelerator
+            .parse::<Shortcut>()
+            .map_err(|e| e.to_string())
+            .and_then(|shortcut| manager.register(shortcut).map(|_| shortcut).map_err(|e| e.to_string()));
+        match result {
+            Ok(shortcut) => {
+                debug!("已注册快捷键 {} -> {:?}", accelerator, action);
+                bindings.push((shortcut, action));
+            }
+            Err(e) => {
+                error!("注册快捷键 {} 失败: {}", accelerator, e);
+                errors.push(format!("{}: {}", accelerator, e));
+            }
+        }
+    }
+    errors
+}
+
+/// 全局快捷键插件的回调
+pub fn handle(_app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let action = BINDINGS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(s, _)| s == shortcut)
+        .map(|(_, action)| *action);
+    let Some(action) = action else {
+        return;
+    };
+
+    tauri::async_runtime::spawn(async move {
+        match run(action).await {
+            Ok(message) => info!("[hotkey] {:?}: {}", action, i18n::translate(&message)),
+            Err(e) => warn!("[hotkey] {:?} 未完成: {}", action, i18n::translate(&e)),
+        }
+    });
+}
+
+/// 执行一个快捷操作，托盘菜单也会调用
+pub async fn run(action: HotkeyAction) -> Result<String, String> {
+    if action == HotkeyAction::QuickSave {
+        return save_backup(SaveBackup::default()).await.map(|outcome| outcome.message().to_string());
+    }
+
+    if !HotkeyConfig::load().allow_load_while_running && game::is_noita_running() {
+        let message = msg("hotkey.blocked_running");
+        notify::send(Category::Failure, Severity::Warning, message.clone(), None);
+        return Err(message.into());
+    }
+    // 读档成功时 load_backup 会发通知，这里只需要通知失败
+    let result = restore(action).await;
+    if let Err(e) = &result {
+        notify::send(Category::Failure, Severity::Error, msg("hotkey.restore_failed").with("error", e), Some(NotifyAction::ViewLog));
+    }
+    result
+}
+
+async fn restore(action: HotkeyAction) -> Result<String, String> {
+    let id = restore_target(action).await?;
+    match load_backup(LoadBackup { backup_id: id, ..Default::default() }).await? {
+        LoadOutcome::Loaded { message, .. } => Ok(message),
+        LoadOutcome::ModMismatch { .. } => Err(msg("hotkey.mod_mismatch").into()),
+        LoadOutcome::PassphraseRequired => Err(msg("encryption.passphrase_required").into()),
+    }
+}
+
+/// 要还原的备份 id
+async fn restore_target(action: HotkeyAction) -> Result<i32, String> {
+    let db_path = db_path::get_db_path().map_err(|e| e.to_string())?;
+    let mut conn = Db::new(db_path).await.map_err(|e| {
+        error!("建立数据库连接出错: {}", e);
+        e.to_string()
+    })?;
+    let mut backups = Db::get_all_backup(&mut conn).await.map_err(|e| e.to_string())?;
+    // 置顶的排在前面，这里只看时间
+    backups.sort_by_key(|b| std::cmp::Reverse(b.save_time));
+
+    if action == HotkeyAction::RestorePrevious {
+        let head = history::get_head(&mut conn).await.map_err(|e| e.to_string())?;
+        if let Some(head) = head.and_then(|id| backups.iter().find(|b| b.id == id)) {
+            return head.parent_id.ok_or_else(|| msg("hotkey.no_parent").into());
+        }
+        return backups.get(1).map(|b| b.id).ok_or_else(|| msg("hotkey.no_earlier").into());
+    }
+    backups.first().map(|b| b.id).ok_or_else(|| msg("hotkey.no_backups").into())
+}
+
+#[tauri::command]
+pub fn get_hotkeys() -> Reply<GetHotkeys> {
+    Ok(HotkeyConfig::load())
+}
+
+/// 保存并立即重新注册，有快捷键注册失败时配置仍会保存
+#[tauri::command]
+pub fn save_hotkeys(app: AppHandle, args: SaveHotkeys) -> Reply<SaveHotkeys> {
+    let SaveHotkeys { config } = args;
+    debug!("[save_hotkeys] {:?}", config);
+    ConfigManager::save_section(CONFIG_SECTION, Some(&config))?;
+    let errors = register_all(&app, &config);
+    if !errors.is_empty() {
+        return Err(msg("hotkey.register_failed").with("keys", errors.join("; ")).into());
+    }
+    Ok(())
+}
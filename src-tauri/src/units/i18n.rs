@@ -0,0 +1,85 @@
+/// 多语言支持。文案和消息格式在 svld_shared::i18n 中和前端共用，
+/// 返回给前端的错误只带错误码和参数，由前端按界面语言翻译
+use std::sync::RwLock;
+use log::debug;
+use tauri::AppHandle;
+use svld_shared::commands::{GetLanguage, SetLanguage};
+use svld_shared::Reply;
+use crate::units::settings::ConfigManager;
+use crate::units::{notify, tray};
+
+pub const CONFIG_SECTION: &str = "language";
+
+pub use svld_shared::i18n::{msg, Locale, Message};
+
+static CURRENT: RwLock<Option<Locale>> = RwLock::new(None);
+
+/// 当前语言，第一次调用时从配置读取
+pub fn current() -> Locale {
+    if let Some(locale) = *CURRENT.read().unwrap() {
+        return locale;
+    }
+    let locale = ConfigManager::load_section::<String>(CONFIG_SECTION)
+        .map(|code| Locale::from_code(&code))
+        .unwrap_or(Locale::ZhCn);
+    *CURRENT.write().unwrap() = Some(locale);
+    locale
+}
+
+/// 取一条文案，当前语言缺失时退回中文
+pub fn t(key: &str) -> String {
+    current().t(key)
+}
+
+/// 按后端当前语言翻译消息，用于托盘、通知这些不经过前端的文字
+pub fn text(message: &Message) -> String {
+    message.text(current())
+}
+
+/// 翻译命令返回的字符串，不是消息格式时原样返回
+pub fn translate(text: &str) -> String {
+    current().translate(text)
+}
+
+#[tauri::command]
+pub fn get_language() -> Reply<GetLanguage> {
+    Ok(current().code().to_string())
+}
+
+#[tauri::command]
+pub fn set_language(app: AppHandle, args: SetLanguage) -> Reply<SetLanguage> {
+    let SetLanguage { language } = args;
+    debug!("[set_language] {}", language);
+    let locale = Locale::from_code(&language);
+    ConfigManager::save_section(CONFIG_SECTION, Some(&locale.code().to_string()))?;
+    *CURRENT.write().unwrap() = Some(locale);
+    relabel(&app);
+    Ok(())
+}
+
+/// 设置被导入或重置后重新读取语言
+pub fn reload(app: &AppHandle) {
+    *CURRENT.write().unwrap() = None;
+    relabel(app);
+}
+
+/// 托盘菜单和通知按钮是启动时建的，换成新语言
+fn relabel(app: &AppHandle) {
+    tray::relabel(app);
+    notify::register_actions(app);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_roundtrip() {
+        let text: String = msg("backup.not_found").with("id", 3).into();
+        let message: Message = serde_json::from_str(&text).unwrap();
+        assert_eq!(message.code, "backup.not_found");
+        assert_eq!(message.params["id"], "3");
+        assert!(translate(&text).contains('3'));
+        assert_eq!(translate("普通文字"), "普通文字");
+    }
+}
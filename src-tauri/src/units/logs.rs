@@ -0,0 +1,212 @@
+/// 应用内的日志查看：解析 tauri_plugin_log 写出的 app.log，支持筛选、分页和实时跟踪
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use chrono::{Local, NaiveDateTime};
+use log::{debug, error, Level};
+use svld_shared::commands::{ReadLogs, StartLogTail, StopLogTail};
+use svld_shared::Reply;
+use svld_shared::events::LogTail;
+use tauri::AppHandle;
+use crate::units::{events, file};
+use crate::units::i18n::msg;
+
+pub use svld_shared::logs::{LogEntry, LogFilter, LogPage};
+
+const TAIL_INTERVAL: Duration = Duration::from_secs(1);
+const DEFAULT_PAGE_SIZE: usize = 200;
+
+/// 解析一行日志，格式是插件默认的 [日期][时间][target][级别] 内容，时间为 UTC
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let rest = line.strip_prefix('[')?;
+    let (date, rest) = rest.split_once("][")?;
+    let (time, rest) = rest.split_once("][")?;
+    let (target, rest) = rest.split_once("][")?;
+    let (level, message) = rest.split_once(']')?;
+    let severity = Level::from_str(level).ok()?;
+    let timestamp = NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M:%S")
+        .ok()?
+        .and_utc();
+    Some(LogEntry {
+        time: timestamp.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string(),
+        timestamp: timestamp.timestamp(),
+        level: severity.to_string(),
+        target: target.to_string(),
+        message: message.strip_prefix(' ').unwrap_or(message).to_string(),
+    })
+}
+
+/// 解析一段日志文本，不以日志头开始的行接到上一条后面
+pub fn parse_entries(text: &str) -> Vec<LogEntry> {
+    let mut entries: Vec<LogEntry> = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end_matches('\r');
+        match parse_line(line) {
+            Some(entry) => entries.push(entry),
+            None => {
+                if let Some(last) = entries.last_mut() {
+                    last.message.push('\n');
+                    last.message.push_str(line);
+                }
+            }
+        }
+    }
+    entries
+}
+
+/// 日志是否符合筛选条件
+pub fn matches(filter: &LogFilter, entry: &LogEntry) -> bool {
+    if let Some(level) = filter.level.as_deref().and_then(|l| Level::from_str(l).ok()) {
+        // 解析日志时已经检查过级别
+        if Level::from_str(&entry.level).is_ok_and(|severity| severity > level) {
+            return false;
+        }
+    }
+    if filter.from.is_some_and(|from| entry.timestamp < from.unix_timestamp()) {
+        return false;
+    }
+    if filter.to.is_some_and(|to| entry.timestamp > to.unix_timestamp()) {
+        return false;
+    }
+    match filter.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(search) => {
+            let search = search.to_lowercase();
+            entry.message.to_lowercase().contains(&search) || entry.target.to_lowercase().contains(&search)
+        }
+        None => true,
+    }
+}
+
+/// 从最新的一条往前数，跳过 offset 条后取 limit 条
+pub fn page(entries: Vec<LogEntry>, filter: &LogFilter, offset: usize, limit: usize) -> LogPage {
+    let matched: Vec<LogEntry> = entries.into_iter().filter(|e| matches(filter, e)).collect();
+    let total = matched.len();
+    let end = total.saturating_sub(offset);
+    let start = end.saturating_sub(limit);
+    LogPage { entries: matched[start..end].to_vec(), total }
+}
+
+fn read_entries(path: &Path) -> Result<Vec<LogEntry>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read(path).map_err(|e| {
+        error!("读取日志失败: {}", e);
+        String::from(msg("log.read_failed").with("error", e))
+    })?;
+    Ok(parse_entries(&String::from_utf8_lossy(&content)))
+}
+
+#[tauri::command]
+pub async fn read_logs(app: AppHandle, args: ReadLogs) -> Reply<ReadLogs> {
+    let ReadLogs { filter, offset, limit } = args;
+    let filter = filter.unwrap_or_default();
+    debug!("[read_logs] {:?} offset = {:?}", filter, offset);
+    let entries = read_entries(&file::log_path(&app)?)?;
+    Ok(page(entries, &filter, offset.unwrap_or(0), limit.unwrap_or(DEFAULT_PAGE_SIZE)))
+}
+
+/// 每次开始跟踪时加一，旧的跟踪任务发现不一致就退出
+static TAIL_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 读取 offset 之后新写入的完整行，文件被轮换后从头开始读
+fn read_appended(path: &Path, offset: &mut u64) -> std::io::Result<Vec<LogEntry>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+    if len < *offset {
+        *offset = 0;
+    }
+    if len == *offset {
+        return Ok(Vec::new());
+    }
+    file.seek(SeekFrom::Start(*offset))?;
+    let mut buf = Vec::new();
+    file.take(len - *offset).read_to_end(&mut buf)?;
+    // 最后一行可能还没写完，留到下次
+    let Some(end) = buf.iter().rposition(|b| *b == b'\n') else {
+        return Ok(Vec::new());
+    };
+    *offset += end as u64 + 1;
+    Ok(parse_entries(&String::from_utf8_lossy(&buf[..end])))
+}
+
+/// 开始跟踪日志，新的日志通过 LogTail 事件发给前端。再次调用会替换之前的跟踪
+/// 返回这次跟踪的编号，停止时传回来
+#[tauri::command]
+pub fn start_log_tail(app: AppHandle, args: StartLogTail) -> Reply<StartLogTail> {
+    let StartLogTail { filter } = args;
+    let filter = filter.unwrap_or_default();
+    debug!("[start_log_tail] {:?}", filter);
+    let path = file::log_path(&app)?;
+    let generation = TAIL_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let mut offset = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    tauri::async_runtime::spawn(async move {
+        // 循环里不能写日志，否则每次读到的都是自己刚写的
+        loop {
+            tokio::time::sleep(TAIL_INTERVAL).await;
+            if TAIL_GENERATION.load(Ordering::SeqCst) != generation {
+                break;
+            }
+            let Ok(entries) = read_appended(&path, &mut offset) else {
+                continue;
+            };
+            let entries: Vec<LogEntry> = entries.into_iter().filter(|e| matches(&filter, e)).collect();
+            if !entries.is_empty() {
+                events::emit(LogTail(entries));
+            }
+        }
+    });
+    Ok(generation)
+}
+
+/// 停止跟踪。已经被新的跟踪替换时什么也不做，避免停掉新的
+#[tauri::command]
+pub fn stop_log_tail(args: StopLogTail) -> Reply<StopLogTail> {
+    let StopLogTail { generation } = args;
+    debug!("[stop_log_tail] {}", generation);
+    let _ = TAIL_GENERATION.compare_exchange(generation, generation + 1, Ordering::SeqCst, Ordering::SeqCst);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::OffsetDateTime;
+
+    const SAMPLE: &str = "[2024-05-01][10:00:00][svld_lib::db][INFO] 数据库连接成功\n\
+                          [2024-05-01][10:00:05][svld_lib::backup][ERROR] 备份失败: 拒绝访问\n\
+                          第二行\n\
+                          [2024-05-01][10:01:00][svld_lib::backup][DEBUG] [save_backup] 完成\n";
+
+    #[test]
+    fn parses_and_joins_continuation_lines() {
+        let entries = parse_entries(SAMPLE);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1].level, "ERROR");
+        assert_eq!(entries[1].target, "svld_lib::backup");
+        assert_eq!(entries[1].message, "备份失败: 拒绝访问\n第二行");
+        assert_eq!(entries[2].message, "[save_backup] 完成");
+    }
+
+    #[test]
+    fn filters_and_pages_from_newest() {
+        let warn = LogFilter { level: Some("warn".to_string()), ..Default::default() };
+        let result = page(parse_entries(SAMPLE), &warn, 0, 10);
+        assert_eq!(result.total, 1);
+        assert_eq!(result.entries[0].level, "ERROR");
+
+        let search = LogFilter { search: Some("BACKUP".to_string()), ..Default::default() };
+        assert_eq!(page(parse_entries(SAMPLE), &search, 0, 10).total, 2);
+
+        let from = OffsetDateTime::from_unix_timestamp(1714557601).ok();
+        let recent = LogFilter { from, ..Default::default() };
+        assert_eq!(page(parse_entries(SAMPLE), &recent, 0, 10).total, 2);
+
+        let older = page(parse_entries(SAMPLE), &LogFilter::default(), 1, 1);
+        assert_eq!(older.total, 3);
+        assert_eq!(older.entries[0].level, "ERROR");
+    }
+}
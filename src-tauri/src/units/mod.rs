@@ -3,3 +3,13 @@ pub mod dashboard;
 pub mod db_path;
 pub mod update;
 pub mod file;
+pub mod game;
+pub mod hotkey;
+pub mod tray;
+pub mod notify;
+pub mod events;
+pub mod i18n;
+pub mod privacy;
+pub mod logs;
+pub mod settings;
+pub mod settings_io;
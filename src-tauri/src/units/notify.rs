@@ -0,0 +1,162 @@
+/// 系统通知：后台备份、快捷键和托盘操作的结果只能靠通知告诉用户
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+use log::{debug, error, warn};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::{Action, ActionPerformed, ActionType, NotificationExt};
+use svld_shared::commands::{GetNotifications, SaveNotifications};
+use svld_shared::Reply;
+use svld_shared::events::ShowLog;
+use crate::units::settings::{ConfigManager, Section};
+use crate::units::i18n::{self, Message};
+use crate::units::{events, tray};
+
+pub use svld_shared::settings::NotificationConfig;
+
+pub const CONFIG_SECTION: &str = "notifications";
+
+/// 通知按钮的类型 id，对应 register_action_types 注册的按钮
+const ACTION_OPEN: &str = "open";
+const ACTION_VIEW_LOG: &str = "view_log";
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// 可以在设置里分别关闭的通知种类
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Category {
+    BackupCreated,
+    DuplicateSkipped,
+    RestoreFinished,
+    Failure,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn title(self) -> String {
+        match self {
+            Severity::Info => "svld".to_string(),
+            Severity::Warning => i18n::t("notify.title_warning"),
+            Severity::Error => i18n::t("notify.title_error"),
+        }
+    }
+}
+
+/// 通知上的按钮
+#[derive(Debug, Clone)]
+pub enum NotifyAction {
+    /// 在资源管理器中打开目录
+    Open(PathBuf),
+    ViewLog,
+}
+
+impl Section for NotificationConfig {
+    const NAME: &'static str = CONFIG_SECTION;
+}
+
+impl Category {
+    fn enabled(self, config: &NotificationConfig) -> bool {
+        match self {
+            Category::BackupCreated => config.backup_created,
+            Category::DuplicateSkipped => config.duplicate_skipped,
+            Category::RestoreFinished => config.restore_finished,
+            Category::Failure => config.failure,
+        }
+    }
+}
+
+/// 注册通知按钮和点击回调，在 setup 中调用一次
+pub fn init(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+    register_actions(app);
+    let handle = app.clone();
+    let _ = app.notification().on_action(move |performed| on_action(&handle, performed));
+}
+
+/// 注册通知上的按钮，切换语言后需要重新注册
+pub fn register_actions(app: &AppHandle) {
+    let types = vec![
+        ActionType::builder(ACTION_OPEN)
+            .actions(vec![Action::builder(ACTION_OPEN, i18n::t("notify.action_open")).build()])
+            .build(),
+        ActionType::builder(ACTION_VIEW_LOG)
+            .actions(vec![Action::builder(ACTION_VIEW_LOG, i18n::t("notify.action_view_log")).build()])
+            .build(),
+    ];
+    if let Err(e) = app.notification().register_action_types(types) {
+        warn!("注册通知按钮失败: {}", e);
+    }
+}
+
+/// 发送一条通知。窗口在前台时界面上已经有弹窗提示，不再重复通知
+pub fn send(category: Category, severity: Severity, body: Message, action: Option<NotifyAction>) {
+    let body = i18n::text(&body);
+    let Some(app) = APP.get() else {
+        return;
+    };
+    if !category.enabled(&NotificationConfig::load()) {
+        return;
+    }
+    let focused = app
+        .get_webview_window("main")
+        .is_some_and(|w| w.is_visible().unwrap_or(false) && w.is_focused().unwrap_or(false));
+    if focused {
+        return;
+    }
+
+    debug!("[notify] {:?} {:?}: {}", category, severity, body);
+    let mut builder = app.notification().builder().title(severity.title()).body(body);
+    match action {
+        Some(NotifyAction::Open(path)) => {
+            builder = builder.action_type_id(ACTION_OPEN).extra("path", path.to_string_lossy());
+        }
+        Some(NotifyAction::ViewLog) => builder = builder.action_type_id(ACTION_VIEW_LOG),
+        None => {}
+    }
+    if let Err(e) = builder.show() {
+        error!("发送通知失败: {}", e);
+    }
+}
+
+fn on_action(app: &AppHandle, performed: &ActionPerformed) {
+    debug!("[notify] action = {}", performed.action_id());
+    match performed.action_id() {
+        ACTION_OPEN => {
+            let path = performed
+                .notification()
+                .and_then(|n| n.extra().get("path"))
+                .and_then(|p| p.as_str())
+                .map(PathBuf::from);
+            if let Some(path) = path {
+                if let Err(e) = Command::new("explorer").arg(&path).spawn() {
+                    error!("打开文件夹失败: {}", e);
+                }
+            }
+        }
+        // 在窗口里打开日志面板
+        ACTION_VIEW_LOG => {
+            tray::show_main_window(app);
+            events::emit(ShowLog);
+        }
+        // 点击通知本身时显示窗口
+        _ => tray::show_main_window(app),
+    }
+}
+
+#[tauri::command]
+pub fn get_notifications() -> Reply<GetNotifications> {
+    Ok(NotificationConfig::load())
+}
+
+#[tauri::command]
+pub fn save_notifications(args: SaveNotifications) -> Reply<SaveNotifications> {
+    let SaveNotifications { config } = args;
+    debug!("[save_notifications] {:?}", config);
+    ConfigManager::save_section(CONFIG_SECTION, Some(&config))
+}
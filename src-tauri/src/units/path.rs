@@ -1,88 +1,50 @@
 /// 路径相关写了四个前端可调用的函数，读、写、验证、选择文件路径。
 use anyhow::Result;
-use std::path::{Path, PathBuf};
-use std::{env, fs};
+use std::path::Path;
+use std::fs;
 use chrono::Local;
 use tauri::AppHandle;
 use tauri_plugin_dialog::DialogExt;
+use svld_shared::commands::{
+    GetDataPath, GetSavePath, SavePathToEnv, SelectDataPath, SelectSavePath, VerifyDataValidation,
+    VerifyValidation,
+};
+use svld_shared::Reply;
+use svld_shared::events::SaveDirChanged;
 use log::{info, debug, error};
-use serde::{Deserialize, Serialize};
+use crate::units::events;
+use crate::units::i18n::{self, msg, Message};
+use crate::units::settings::ConfigManager;
 
 
-// 定义配置结构体，自动支持序列化
-#[derive(Debug, Serialize, Deserialize, Default)]
-struct AppConfig {
-    // 使用 Option，如果是 None 代表用户还没设置
-    save_path: Option<String>,
-    data_path: Option<String>,
-}
-
-pub struct ConfigManager;
-
-impl ConfigManager {
-    fn get_config_file_path() -> PathBuf {
-        let exe_path = env::current_exe().unwrap_or_else(|_| PathBuf::from("."));
-        let exe_dir = exe_path.parent().unwrap_or_else(|| Path::new("."));
-        exe_dir.join("config.json")
-    }
-
-    /// 读取配置
-    fn load() -> AppConfig {
-        let path = Self::get_config_file_path();
-        if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-                Err(e) => {
-                    error!("读取配置文件失败: {}", e);
-                    AppConfig::default()
-                }
-            }
-        } else {
-            AppConfig::default()
-        }
-    }
-
-    /// 保存配置
-    fn save(config: &AppConfig) -> Result<(), String> {
-        let path = Self::get_config_file_path();
-        let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
-
-        fs::write(path, content).map_err(|e| e.to_string())?;
-        Ok(())
-    }
-}
-
-#[tauri::command]
-pub fn save_path_to_env(path: &str) -> Result<(), String> {
+/// 保存存档路径，其他设置保持不变
+pub fn set_save_path(path: &str) -> Result<(), String> {
     debug!("[save_config] 保存路径: {}", path);
 
-    // 1. 读取现有配置（保留其他可能存在的字段）
-    let mut config = ConfigManager::load();
-
-    // 2. 修改路径
-    config.save_path = Some(path.to_string());
-
-    // 3. 写入文件（serde 自动处理 json 格式）
-    ConfigManager::save(&config)?;
+    // 只修改路径，其他设置保持不变
+    ConfigManager::update(|settings| settings.save_path = Some(path.to_string()))?;
 
     info!("配置已更新");
+    events::emit(SaveDirChanged { path: path.to_string() });
     Ok(())
 }
-#[tauri::command]
-pub fn save_data_path(path : &str) -> Result<(), String> {
+/// 保存备份目录路径，不会移动已有的备份
+pub fn set_data_path(path : &str) -> Result<(), String> {
     debug!("[save_config] 保存路径: {}", path);
 
-    let mut config = ConfigManager::load();
-
-    config.data_path = Some(path.to_string());
-
-    ConfigManager::save(&config)?;
+    ConfigManager::update(|settings| settings.data_path = Some(path.to_string()))?;
 
     info!("配置已更新");
     Ok(())
 }
+
+#[tauri::command]
+pub fn save_path_to_env(args: SavePathToEnv) -> Reply<SavePathToEnv> {
+    set_save_path(&args.path)
+}
+
 #[tauri::command]
-pub fn get_save_path() -> Result<String, String> {
+pub fn get_save_path() -> Reply<GetSavePath> {
     let config = ConfigManager::load();
 
     // 如果配置文件里有，直接返回
@@ -95,20 +57,20 @@ pub fn get_save_path() -> Result<String, String> {
     let default_path = if let Some(home) = dirs::home_dir() {
         home.join("AppData").join("LocalLow").join("Nolla_Games_Noita").join("save00")
     } else {
-        return Err("无法获取系统用户目录".to_string());
+        return Err(msg("path.no_home").into());
     };
 
     let path_str = default_path.to_string_lossy().to_string();
 
     // 保存默认值到配置文件
-    save_path_to_env(&path_str)?;
+    set_save_path(&path_str)?;
 
     info!("[get_save_path] 使用默认路径: {}", path_str);
     Ok(path_str)
 }
 
 #[tauri::command]
-pub fn get_data_path() -> Result<String, String> {
+pub fn get_data_path() -> Reply<GetDataPath> {
     let config = ConfigManager::load();
 
     if let Some(path) = config.data_path {
@@ -119,87 +81,77 @@ pub fn get_data_path() -> Result<String, String> {
     let default_path = if let Some(home) = dirs::home_dir() {
         home.join("Documents").join("Noita-svld")
     }else {
-        return Err("无法获取系统用户目录".to_string());
+        return Err(msg("path.no_home").into());
     };
 
     if !default_path.exists() {
         std::fs::create_dir_all(&default_path)
-            .map_err(|e| format!("无法创建文件夹: {}", e))?;
+            .map_err(|e| String::from(msg("path.create_failed").with("error", e)))?;
     }
 
     let path_str = default_path.to_string_lossy().to_string();
 
     // 保存默认值到配置文件
-    save_data_path(&path_str)?;
+    set_data_path(&path_str)?;
 
     info!("[get_data_path] 使用默认路径: {}", path_str);
     Ok(path_str)
 }
 
 #[tauri::command]
-pub async fn select_save_path(app: AppHandle) -> Option<String> {
+pub async fn select_save_path(app: AppHandle) -> Reply<SelectSavePath> {
     debug!("[select_save_path] {}", Local::now());
     // 使用同步方式获取文件夹路径
     if let Some(path) = app
         .dialog()
         .file()
-        .set_title("选择存档目录")
+        .set_title(i18n::t("path.select_save_title"))
         .blocking_pick_folder()
     {
-        Some(path.to_string())
+        Ok(Some(path.to_string()))
     } else {
-        None
+        Ok(None)
     }
 }
 
 #[tauri::command]
-pub async fn select_data_path(app: AppHandle) -> Option<String> {
+pub async fn select_data_path(app: AppHandle) -> Reply<SelectDataPath> {
     debug!("[select_data_path] {}", Local::now());
     // 使用同步方式获取文件夹路径
     if let Some(path) = app
         .dialog()
         .file()
-        .set_title("选择保存目录")
+        .set_title(i18n::t("path.select_data_title"))
         .blocking_pick_folder()
     {
-        Some(path.to_string())
+        Ok(Some(path.to_string()))
     } else {
-        None
+        Ok(None)
     }
 }
 
-#[tauri::command]
-pub async fn verify_validation() -> Result<(), String> {
-    debug!("[verify_validation] {}", Local::now());
-    let current_path = get_save_path().map_err(|e| e.to_string())?;
-    let path = Path::new(&current_path);
+/// 检查存档目录：必须是存在的目录，并且包含存档的三个子目录
+pub fn check_save_dir(current_path: &str) -> Result<(), Message> {
+    let path = Path::new(current_path);
 
     // 检查路径是否存在
     if !path.exists() {
-        let e = format!("路径不存在: {}", current_path);
-        error!("{}",e);
-        return Err(e);
+        return Err(msg("path.not_exist").with("path", current_path));
     }
 
     // 检查是否为目录
     if !path.is_dir() {
-        let e = format!("路径不是目录: {}", current_path);
-        error!("{}",e);
-        return Err(e);
+        return Err(msg("path.not_dir").with("path", current_path));
     }
 
     // 读取目录内容
-    let entries =
-        fs::read_dir(path).map_err(|e| {
-            let msg = format!("无法读取目录 {}: {}", current_path, e);
-            error!("{}",msg);
-            msg
-        })?;
+    let entries = fs::read_dir(path)
+        .map_err(|e| msg("path.read_failed").with("path", current_path).with("error", e))?;
 
     // 收集所有子目录名称
     let mut subdirs = Vec::new();
     for entry in entries {
-        let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+        let entry = entry.map_err(|e| msg("path.read_entry_failed").with("error", e))?;
         let path = entry.path();
         if path.is_dir() {
             if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
@@ -212,45 +164,56 @@ pub async fn verify_validation() -> Result<(), String> {
     let required_dirs = ["persistent", "stats", "world"];
     for required_dir in &required_dirs {
         if !subdirs.contains(&required_dir.to_string()) {
-            error!("无法找到存档文件");
-            return Err("无法找到存档文件".parse().unwrap());
+            return Err(msg("path.no_save_files"));
         }
     }
-
-    let success_msg = "路径验证成功".to_string();
-    info!("时间：{},{}",Local::now() ,success_msg);
     Ok(())
 }
 
-#[tauri::command]
-pub async fn verify_data_validation() -> Result<(), String> {
-    debug!("[verify_validation] {}", Local::now());
-    let current_path = get_data_path().map_err(|e| e.to_string())?;
-    let path = Path::new(&current_path);
+/// 检查备份目录：必须是存在的目录，并且可以写入
+pub fn check_data_dir(current_path: &str) -> Result<(), Message> {
+    let path = Path::new(current_path);
 
     // 检查路径是否存在
     if !path.exists() {
-        let e = format!("路径不存在: {}", current_path);
-        error!("{}",e);
-        return Err(e);
+        return Err(msg("path.not_exist").with("path", current_path));
     }
 
     // 检查是否为目录
     if !path.is_dir() {
-        let e = format!("路径不是目录: {}", current_path);
-        error!("{}",e);
-        return Err(e);
+        return Err(msg("path.not_dir").with("path", current_path));
     }
 
     let probe_file = path.join(".write_probe");
     if let Err(err) = fs::File::create(&probe_file).and_then(|_| fs::remove_file(&probe_file)) {
-        let e = format!("目录不可写: {} (错误: {})", current_path, err);
-        error!("{}", e);
-        return Err(e);
+        return Err(msg("path.not_writable").with("path", current_path).with("error", err));
     }
+    Ok(())
+}
 
+#[tauri::command]
+pub async fn verify_validation() -> Reply<VerifyValidation> {
+    debug!("[verify_validation] {}", Local::now());
+    let current_path = get_save_path().map_err(|e| e.to_string())?;
+    check_save_dir(&current_path).map_err(|e| {
+        error!("{}", i18n::text(&e));
+        String::from(e)
+    })?;
+
+    let success_msg = "路径验证成功".to_string();
+    info!("时间：{},{}",Local::now() ,success_msg);
     Ok(())
 }
+
+#[tauri::command]
+pub async fn verify_data_validation() -> Reply<VerifyDataValidation> {
+    debug!("[verify_validation] {}", Local::now());
+    let current_path = get_data_path().map_err(|e| e.to_string())?;
+    check_data_dir(&current_path).map_err(|e| {
+        error!("{}", i18n::text(&e));
+        String::from(e)
+    })
+}
 #[cfg(test)]
 mod tests {
     use crate::units::path::verify_validation;
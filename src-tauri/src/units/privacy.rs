@@ -0,0 +1,190 @@
+/// 日志脱敏：把主目录、用户名和计算机名换成占位符，日志可以直接附在问题反馈里
+use std::borrow::Cow;
+use std::env;
+use std::fmt::Arguments;
+use std::sync::OnceLock;
+use chrono::{Local, Utc};
+use log::{debug, error, Record};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_log::fern::FormatCallback;
+use svld_shared::commands::ExportSanitizedLog;
+use svld_shared::Reply;
+use crate::units::file;
+use crate::units::i18n::{self, msg};
+
+pub const HOME: &str = "<HOME>";
+pub const USER: &str = "<USER>";
+pub const HOST: &str = "<HOST>";
+
+/// 一条替换规则
+struct Rule {
+    needle: String,
+    placeholder: &'static str,
+}
+
+pub struct Scrubber {
+    rules: Vec<Rule>,
+}
+
+impl Scrubber {
+    pub fn new(home: Option<String>, user: Option<String>, host: Option<String>) -> Scrubber {
+        let mut rules = Vec::new();
+        if let Some(home) = home.filter(|h| h.len() > 3) {
+            // 日志里的路径有直接打印的，也有 {:?} 打印的（反斜杠会变成两个）
+            let variants = [home.clone(), home.replace('\\', "/"), home.replace('\\', "\\\\")];
+            for needle in variants {
+                if !rules.iter().any(|r: &Rule| r.needle == needle) {
+                    rules.push(Rule { needle, placeholder: HOME });
+                }
+            }
+        }
+        for (value, placeholder) in [(user, USER), (host, HOST)] {
+            // 太短的名字逐字替换会误伤正常文字
+            if let Some(needle) = value.filter(|v| v.chars().count() >= 2) {
+                rules.push(Rule { needle, placeholder });
+            }
+        }
+        // 长的先替换，主目录里通常就含有用户名
+        rules.sort_by_key(|r| std::cmp::Reverse(r.needle.len()));
+        Scrubber { rules }
+    }
+
+    /// 从当前环境读取要隐藏的信息
+    pub fn from_env() -> Scrubber {
+        let var = |names: &[&str]| names.iter().find_map(|n| env::var(n).ok()).filter(|v| !v.is_empty());
+        Scrubber::new(
+            dirs::home_dir().map(|p| p.to_string_lossy().into_owned()),
+            var(&["USERNAME", "USER"]),
+            var(&["COMPUTERNAME", "HOSTNAME"]),
+        )
+    }
+
+    pub fn scrub<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for rule in &self.rules {
+            if let Some(replaced) = replace_word(&text, &rule.needle, rule.placeholder) {
+                text = Cow::Owned(replaced);
+            }
+        }
+        text
+    }
+}
+
+fn is_word_char(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// 不区分大小写地替换完整的词，Windows 路径的大小写并不固定。没有替换时返回 None
+fn replace_word(text: &str, needle: &str, placeholder: &str) -> Option<String> {
+    // 只转换 ASCII，字节位置和原文一一对应
+    let haystack = text.to_ascii_lowercase();
+    let needle_lower = needle.to_ascii_lowercase();
+    let step = needle.chars().next()?.len_utf8();
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut start = 0;
+    while let Some(pos) = haystack[start..].find(&needle_lower) {
+        let begin = start + pos;
+        let end = begin + needle.len();
+        if !is_word_char(text[..begin].chars().next_back()) && !is_word_char(text[end..].chars().next()) {
+            out.push_str(&text[last..begin]);
+            out.push_str(placeholder);
+            last = end;
+            start = end;
+        } else {
+            start = begin + step;
+        }
+    }
+    if last == 0 {
+        return None;
+    }
+    out.push_str(&text[last..]);
+    Some(out)
+}
+
+pub fn scrubber() -> &'static Scrubber {
+    static SCRUBBER: OnceLock<Scrubber> = OnceLock::new();
+    SCRUBBER.get_or_init(Scrubber::from_env)
+}
+
+pub fn scrub(text: &str) -> Cow<'_, str> {
+    scrubber().scrub(text)
+}
+
+/// tauri_plugin_log 的格式化函数，和插件默认的格式一样，只是写出前先脱敏
+pub fn format_record(out: FormatCallback, message: &Arguments, record: &Record) {
+    let message = message.to_string();
+    out.finish(format_args!(
+        "{}[{}][{}] {}",
+        Utc::now().format("[%Y-%m-%d][%H:%M:%S]"),
+        record.target(),
+        record.level(),
+        scrub(&message)
+    ))
+}
+
+/// 导出脱敏后的日志，用于反馈问题。返回导出的文件路径，取消选择时返回 None
+#[tauri::command]
+pub async fn export_sanitized_log(app: AppHandle) -> Reply<ExportSanitizedLog> {
+    debug!("[export_sanitized_log]");
+    let log_path = file::log_path(&app)?;
+    if !log_path.exists() {
+        return Err(msg("file.log_missing").with("path", log_path.display()).into());
+    }
+    let default_name = format!("svld-log-{}.txt", Local::now().format("%Y%m%d-%H%M%S"));
+    let Some(target) = app
+        .dialog()
+        .file()
+        .set_title(i18n::t("log.export_title"))
+        .set_file_name(&default_name)
+        .add_filter(i18n::t("log.filter"), &["txt", "log"])
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let target = target.into_path().map_err(|e| e.to_string())?;
+
+    // 早期版本写入的日志没有脱敏，导出时再过滤一遍
+    let content = std::fs::read(&log_path).map_err(|e| {
+        error!("读取日志失败: {}", e);
+        String::from(msg("log.read_failed").with("error", e))
+    })?;
+    let content = String::from_utf8_lossy(&content);
+    std::fs::write(&target, scrub(&content).as_bytes()).map_err(|e| {
+        error!("写入日志失败: {}", e);
+        String::from(msg("log.write_failed").with("error", e))
+    })?;
+    Ok(Some(target.to_string_lossy().into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scrubber() -> Scrubber {
+        Scrubber::new(
+            Some(r"C:\Users\Alice".to_string()),
+            Some("Alice".to_string()),
+            Some("ALICE-PC".to_string()),
+        )
+    }
+
+    #[test]
+    fn replaces_home_user_and_host() {
+        let s = scrubber();
+        assert_eq!(
+            s.scrub(r"数据库文件路径: C:\Users\Alice\AppData\Local\svld\data.db"),
+            r"数据库文件路径: <HOME>\AppData\Local\svld\data.db"
+        );
+        assert_eq!(s.scrub(r#"path = "c:\\users\\alice\\Saves""#), r#"path = "<HOME>\\Saves""#);
+        assert_eq!(s.scrub("D:/Games/alice/noita on ALICE-PC"), "D:/Games/<USER>/noita on <HOST>");
+    }
+
+    #[test]
+    fn keeps_partial_words() {
+        let s = scrubber();
+        assert_eq!(s.scrub("Alicetown and malice"), "Alicetown and malice");
+        assert!(matches!(s.scrub("没有隐私信息"), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,389 @@
+/// 设置的存储。保存在系统的配置目录下的 settings.json，带版本号，
+/// 写入时先写临时文件再替换，避免写到一半时崩溃留下损坏的文件。
+/// 旧版本放在 exe 目录的 config.json 第一次启动时会迁移过来
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::env;
+use chrono::Local;
+use log::{error, info, warn};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use svld_shared::commands::{DismissSettingsProblems, GetSettingsInfo};
+use svld_shared::Reply;
+use crate::units::i18n::{self, msg, Message};
+
+pub use svld_shared::settings::SettingsInfo;
+
+pub const SAVE_PATH: &str = "save_path";
+pub const DATA_PATH: &str = "data_path";
+/// 当前的设置格式版本，格式变化时加一并在 MIGRATIONS 里补上迁移
+pub const SCHEMA_VERSION: u64 = 1;
+/// 和 tauri.conf.json 中的 identifier 一致，与 app_config_dir 是同一个目录
+const IDENTIFIER: &str = "com.auceptin.noita-svld";
+const FILE_NAME: &str = "settings.json";
+const LEGACY_FILE_NAME: &str = "config.json";
+/// exe 目录下有这个文件时使用便携模式，设置保存在 exe 目录
+const PORTABLE_MARKER: &str = "portable";
+
+/// 第 i 项把版本 i 的设置迁移到版本 i + 1
+const MIGRATIONS: [fn(&mut Map<String, Value>); SCHEMA_VERSION as usize] = [
+    // 0 是旧版本 exe 目录下的 config.json，字段没有变化，只是补上版本号
+    |_| {},
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    pub version: u64,
+    // 使用 Option，如果是 None 代表用户还没设置
+    pub save_path: Option<String>,
+    pub data_path: Option<String>,
+    /// 其他模块各自的配置段，例如 remote
+    #[serde(flatten)]
+    pub sections: Map<String, Value>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings { version: SCHEMA_VERSION, save_path: None, data_path: None, sections: Map::new() }
+    }
+}
+
+impl Settings {
+    /// 按名称取一项设置，两个路径和其他配置段一样对待
+    pub fn get(&self, key: &str) -> Option<Value> {
+        match key {
+            SAVE_PATH => self.save_path.clone().map(Value::from),
+            DATA_PATH => self.data_path.clone().map(Value::from),
+            _ => self.sections.get(key).cloned(),
+        }
+    }
+
+    /// 按名称设置一项，None 表示恢复默认
+    pub fn set(&mut self, key: &str, value: Option<Value>) {
+        let text = || value.as_ref().and_then(Value::as_str).map(str::to_string);
+        match key {
+            SAVE_PATH => self.save_path = text(),
+            DATA_PATH => self.data_path = text(),
+            _ => match value {
+                Some(value) => {
+                    self.sections.insert(key.to_string(), value);
+                }
+                None => {
+                    self.sections.remove(key);
+                }
+            },
+        }
+    }
+}
+
+fn exe_dir() -> PathBuf {
+    let exe_path = env::current_exe().unwrap_or_else(|_| PathBuf::from("."));
+    exe_path.parent().unwrap_or_else(|| Path::new(".")).to_path_buf()
+}
+
+/// 是否是便携模式，需要用户自己在 exe 旁边放一个 portable 文件
+pub fn is_portable() -> bool {
+    exe_dir().join(PORTABLE_MARKER).exists()
+}
+
+/// 设置文件的位置
+pub fn settings_path() -> PathBuf {
+    let dir = match dirs::config_dir() {
+        Some(dir) if !is_portable() => dir.join(IDENTIFIER),
+        _ => exe_dir(),
+    };
+    dir.join(FILE_NAME)
+}
+
+/// 把旧的设置升级到当前版本。比当前版本新的设置（用过新版本后又装回旧版本）保留原来的版本号
+fn migrate(mut value: Value) -> Result<Value, Message> {
+    let object = value.as_object_mut().ok_or_else(|| msg("settings.not_object"))?;
+    let version = match object.get("version") {
+        None => 0,
+        Some(v) => v.as_u64().ok_or_else(|| msg("settings.invalid_field").with("field", "version"))?,
+    };
+    for (from, step) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        info!("设置从版本 {} 迁移到 {}", from, from + 1);
+        step(object);
+    }
+    object.insert("version".to_string(), Value::from(version.max(SCHEMA_VERSION)));
+    Ok(value)
+}
+
+/// 检查各字段，不合法的字段记录下来并清空，其他设置照常使用
+fn validate(settings: &mut Settings) -> Vec<Message> {
+    let mut problems = Vec::new();
+    for (field, value) in [(SAVE_PATH, &mut settings.save_path), (DATA_PATH, &mut settings.data_path)] {
+        if value.as_deref().is_some_and(|v| v.trim().is_empty()) {
+            problems.push(msg("settings.invalid_field").with("field", field));
+            *value = None;
+        }
+    }
+    problems
+}
+
+/// 读取并解析设置文件，返回设置和发现的问题
+fn read_file(path: &Path) -> Result<(Settings, Vec<Message>), Message> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        msg("settings.read_failed").with("path", path.display()).with("error", e)
+    })?;
+    let value = serde_json::from_str(&content)
+        .map_err(|e| msg("settings.invalid").with("path", path.display()).with("error", e))?;
+    parse(value, path)
+}
+
+/// 迁移并检查设置，source 是设置的来源，用于错误提示
+pub fn parse(value: Value, source: &Path) -> Result<(Settings, Vec<Message>), Message> {
+    let value = migrate(value)?;
+    let mut settings: Settings = serde_json::from_value(value)
+        .map_err(|e| msg("settings.invalid").with("path", source.display()).with("error", e))?;
+    let mut problems = validate(&mut settings);
+    if settings.version > SCHEMA_VERSION {
+        problems.push(msg("settings.newer_version").with("version", settings.version));
+    }
+    Ok((settings, problems))
+}
+
+/// 先写到临时文件再改名，改名在同一目录下是原子的
+fn write_file(path: &Path, settings: &Settings) -> Result<(), Message> {
+    let failed = |e: &dyn std::fmt::Display| {
+        error!("写入设置失败: {}", e);
+        msg("settings.write_failed").with("path", path.display()).with("error", e)
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| failed(&e))?;
+    }
+    let content = serde_json::to_string_pretty(settings).map_err(|e| failed(&e))?;
+    let tmp = path.with_extension("json.tmp");
+    let mut file = File::create(&tmp).map_err(|e| failed(&e))?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| failed(&e))?;
+    drop(file);
+    fs::rename(&tmp, path).map_err(|e| failed(&e))
+}
+
+/// 损坏的设置文件改名保留下来，方便用户找回里面的内容
+fn quarantine(path: &Path) -> Option<PathBuf> {
+    let target = path.with_extension(format!("broken-{}.json", Local::now().format("%Y%m%d-%H%M%S")));
+    match fs::rename(path, &target) {
+        Ok(_) => Some(target),
+        Err(e) => {
+            warn!("无法保留损坏的设置文件 {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// 从 legacy 迁移到 path。旧文件改名为 config.json.migrated，改不了名（例如在 Program Files 下）也不影响
+fn migrate_legacy(path: &Path, legacy: &Path, problems: &mut Vec<Message>) -> Option<Settings> {
+    if !legacy.exists() || legacy == path {
+        return None;
+    }
+    info!("迁移旧的设置文件 {} -> {}", legacy.display(), path.display());
+    let (settings, found) = match read_file(legacy) {
+        Ok(result) => result,
+        Err(e) => {
+            // 旧文件不动，用户修好后删掉新文件就能重新迁移
+            problems.push(msg("settings.migrate_failed").with("path", legacy.display()).with("error", String::from(e)));
+            return None;
+        }
+    };
+    problems.extend(found);
+    if let Err(e) = write_file(path, &settings) {
+        problems.push(e);
+        return Some(settings);
+    }
+    if let Err(e) = fs::rename(legacy, legacy.with_extension("json.migrated")) {
+        warn!("旧的设置文件无法改名: {}", e);
+    }
+    Some(settings)
+}
+
+/// 从磁盘加载设置。出错时使用默认设置，但问题会记录下来在设置页显示，不会悄悄丢掉
+fn load_from(path: &Path, legacy: &Path) -> (Settings, Vec<Message>) {
+    let mut problems = Vec::new();
+    if !path.exists() {
+        let settings = migrate_legacy(path, legacy, &mut problems).unwrap_or_default();
+        return (settings, problems);
+    }
+    match read_file(path) {
+        Ok((settings, found)) => {
+            problems.extend(found);
+            (settings, problems)
+        }
+        Err(e) => {
+            let problem = match quarantine(path) {
+                Some(backup) => msg("settings.reset").with("path", backup.display()).with("error", String::from(e)),
+                None => e,
+            };
+            problems.push(problem);
+            (Settings::default(), problems)
+        }
+    }
+}
+
+struct Store {
+    path: PathBuf,
+    settings: Settings,
+    problems: Vec<Message>,
+}
+
+static STORE: Mutex<Option<Store>> = Mutex::new(None);
+
+/// 在锁内访问设置，第一次访问时从磁盘加载
+fn with_store<R>(f: impl FnOnce(&mut Store) -> R) -> R {
+    let mut guard = STORE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut loaded = Vec::new();
+    if guard.is_none() {
+        let path = settings_path();
+        let (settings, problems) = load_from(&path, &exe_dir().join(LEGACY_FILE_NAME));
+        loaded = problems.clone();
+        *guard = Some(Store { path, settings, problems });
+    }
+    let result = guard.as_mut().map(f).unwrap();
+    drop(guard);
+    // 翻译时会读取语言设置，要在释放锁之后
+    for problem in loaded {
+        error!("{}", i18n::text(&problem));
+    }
+    result
+}
+
+/// 记录一个设置问题，同样的问题只记一次
+fn report(problem: Message) {
+    let added = with_store(|store| {
+        let added = !store.problems.contains(&problem);
+        if added {
+            store.problems.push(problem.clone());
+        }
+        added
+    });
+    if added {
+        error!("{}", i18n::text(&problem));
+    }
+}
+
+pub struct ConfigManager;
+
+impl ConfigManager {
+    /// 当前的全部设置
+    pub fn load() -> Settings {
+        with_store(|store| store.settings.clone())
+    }
+
+    /// 修改设置并写入磁盘，写入失败时内存中的设置也不变
+    pub fn update<R>(f: impl FnOnce(&mut Settings) -> R) -> Result<R, String> {
+        with_store(|store| {
+            let mut settings = store.settings.clone();
+            let result = f(&mut settings);
+            write_file(&store.path, &settings)?;
+            store.settings = settings;
+            Ok(result)
+        })
+    }
+
+    /// 读取某个配置段，不存在或格式不对时返回 None，格式不对会记录到设置问题里
+    pub fn load_section<T: DeserializeOwned>(name: &str) -> Option<T> {
+        let value = with_store(|store| store.settings.sections.get(name).cloned())?;
+        serde_json::from_value(value)
+            .map_err(|e| report(msg("settings.invalid_section").with("section", name).with("error", e)))
+            .ok()
+    }
+
+    /// 写入某个配置段，value 为 None 时删除
+    pub fn save_section<T: Serialize>(name: &str, value: Option<&T>) -> Result<(), String> {
+        let value = value
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        Self::update(|settings| settings.set(name, value))?;
+        // 重新保存后之前的格式问题就不存在了
+        with_store(|store| {
+            store.problems.retain(|p| p.code != "settings.invalid_section" || p.params.get("section").map(String::as_str) != Some(name))
+        });
+        Ok(())
+    }
+}
+
+/// 各模块保存在设置中的配置段，不存在或格式不对时使用默认值
+pub trait Section: DeserializeOwned + Default {
+    /// 配置段在设置文件中的名字
+    const NAME: &'static str;
+
+    fn load() -> Self {
+        ConfigManager::load_section(Self::NAME).unwrap_or_default()
+    }
+}
+
+#[tauri::command]
+pub fn get_settings_info() -> Reply<GetSettingsInfo> {
+    Ok(with_store(|store| SettingsInfo {
+        path: store.path.to_string_lossy().into_owned(),
+        portable: is_portable(),
+        version: store.settings.version,
+        problems: store.problems.iter().cloned().map(String::from).collect(),
+    }))
+}
+
+/// 用户看过之后清除问题提示
+#[tauri::command]
+pub fn dismiss_settings_problems() -> Reply<DismissSettingsProblems> {
+    with_store(|store| store.problems.clear());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_legacy_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let legacy = dir.join("exe").join(LEGACY_FILE_NAME);
+        fs::create_dir_all(legacy.parent().unwrap()).unwrap();
+        fs::write(&legacy, r#"{"save_path":"D:\\save00","data_path":"","remote":{"kind":"local"}}"#).unwrap();
+        let path = dir.join("config").join(FILE_NAME);
+
+        let (settings, problems) = load_from(&path, &legacy);
+        assert_eq!(settings.version, SCHEMA_VERSION);
+        assert_eq!(settings.save_path.as_deref(), Some("D:\\save00"));
+        assert_eq!(settings.data_path, None);
+        assert!(settings.sections.contains_key("remote"));
+        assert_eq!(problems, vec![msg("settings.invalid_field").with("field", "data_path")]);
+        assert!(path.exists());
+        assert!(!legacy.exists());
+
+        // 已经迁移过的不会再读旧文件
+        let (again, problems) = load_from(&path, &legacy);
+        assert_eq!(again.save_path, settings.save_path);
+        assert!(problems.is_empty());
+    }
+
+    #[test]
+    fn keeps_broken_file_and_reports() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let path = dir.join(FILE_NAME);
+        fs::write(&path, "{ \"save_path\": ").unwrap();
+
+        let (settings, problems) = load_from(&path, &dir.join(LEGACY_FILE_NAME));
+        assert_eq!(settings.save_path, None);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].code, "settings.reset");
+        assert!(!path.exists());
+        let kept = fs::read_dir(dir).unwrap().filter_map(|e| e.ok()).count();
+        assert_eq!(kept, 1);
+
+        // 写入后可以正常读回，不会留下临时文件
+        write_file(&path, &Settings { save_path: Some("E:\\save00".to_string()), ..Default::default() }).unwrap();
+        let (settings, problems) = load_from(&path, &dir.join(LEGACY_FILE_NAME));
+        assert_eq!(settings.save_path.as_deref(), Some("E:\\save00"));
+        assert!(problems.is_empty());
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+}
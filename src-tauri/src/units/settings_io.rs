@@ -0,0 +1,329 @@
+/// 设置的导入、导出和恢复默认，用于换电脑时搬走全部设置。
+/// 远程存储的密码保存在系统凭据管理器里，不会导出；备份加密的配置里有口令的哈希，也不导出
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::Local;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+use svld_shared::commands::{ExportSettings, ImportSettings, PreviewSettingsImport, ResetSettingsSection};
+use svld_shared::Reply;
+use crate::backup::auto::{self, AutoBackupConfig};
+use crate::backup::crypto;
+use crate::backup::space::{self, DiskSpaceConfig};
+use crate::storage::{self, RemoteConfig};
+use crate::units::hotkey::{self, HotkeyConfig};
+use crate::units::i18n::{self, msg, Message};
+use crate::units::notify::{self, NotificationConfig};
+use crate::units::update::{self, UpdateConfig};
+use crate::units::settings::{self, ConfigManager, Section, Settings, DATA_PATH, SAVE_PATH};
+use crate::units::{path, tray};
+
+pub use svld_shared::settings::{Change, ImportItem, ImportPreview};
+
+/// 导出文件里的格式标识
+const FORMAT: &str = "svld-settings";
+
+/// 可以导入和恢复默认的设置项，也是界面上的顺序
+pub const KEYS: [&str; 9] = [
+    SAVE_PATH,
+    DATA_PATH,
+    auto::CONFIG_SECTION,
+    space::CONFIG_SECTION,
+    hotkey::CONFIG_SECTION,
+    notify::CONFIG_SECTION,
+    update::CONFIG_SECTION,
+    i18n::CONFIG_SECTION,
+    storage::CONFIG_SECTION,
+];
+
+/// 不能直接导入或恢复默认的设置项：数据路径要通过移动备份来修改
+const MANUAL: [&str; 1] = [DATA_PATH];
+
+/// 不导出的配置段。口令的哈希可以用来离线猜口令，不能跟着设置文件分享出去
+const PRIVATE: [&str; 1] = [crypto::CONFIG_SECTION];
+
+/// 手动修改 MANUAL 中设置项的说明
+fn manual_hint(key: &str) -> Option<Message> {
+    match key {
+        DATA_PATH => Some(msg("settings_io.manual_data_path")),
+        _ => None,
+    }
+}
+
+/// 导出的文件
+#[derive(Debug, Serialize, Deserialize)]
+struct SettingsExport {
+    format: String,
+    exported_at: String,
+    settings: Value,
+}
+
+fn show(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+/// 比较两个值，配置段按字段比较
+fn diff(current: Option<&Value>, imported: Option<&Value>) -> Vec<Change> {
+    if let (Some(Value::Object(current)), Some(Value::Object(imported))) = (current, imported) {
+        let fields: BTreeSet<&String> = current.keys().chain(imported.keys()).collect();
+        return fields
+            .into_iter()
+            .filter(|field| current.get(*field) != imported.get(*field))
+            .map(|field| Change {
+                field: field.clone(),
+                current: show(current.get(field)),
+                imported: show(imported.get(field)),
+            })
+            .collect();
+    }
+    if current == imported {
+        return Vec::new();
+    }
+    vec![Change { field: String::new(), current: show(current), imported: show(imported) }]
+}
+
+/// 检查导入的配置段能否被对应的模块读取
+fn check(key: &str, value: &Value) -> Result<(), Message> {
+    let value = value.clone();
+    let result = match key {
+        SAVE_PATH | DATA_PATH | i18n::CONFIG_SECTION => serde_json::from_value::<String>(value).map(drop),
+        auto::CONFIG_SECTION => serde_json::from_value::<AutoBackupConfig>(value).map(drop),
+        hotkey::CONFIG_SECTION => serde_json::from_value::<HotkeyConfig>(value).map(drop),
+        notify::CONFIG_SECTION => serde_json::from_value::<NotificationConfig>(value).map(drop),
+        space::CONFIG_SECTION => serde_json::from_value::<DiskSpaceConfig>(value).map(drop),
+        update::CONFIG_SECTION => serde_json::from_value::<UpdateConfig>(value).map(drop),
+        storage::CONFIG_SECTION => serde_json::from_value::<RemoteConfig>(value).map(drop),
+        _ => Ok(()),
+    };
+    result.map_err(|e| msg("settings.invalid_section").with("section", key).with("error", e))
+}
+
+/// 在这台电脑上检查导入的路径，有问题只提示，仍然可以导入
+fn check_path(key: &str, value: &Value) -> Option<Message> {
+    let path = value.as_str()?;
+    let result = match key {
+        SAVE_PATH => path::check_save_dir(path),
+        DATA_PATH => path::check_data_dir(path),
+        _ => Ok(()),
+    };
+    result.err()
+}
+
+/// 读取导出的设置文件
+fn read_export(file: &Path) -> Result<(Settings, Vec<Message>), Message> {
+    let content = fs::read_to_string(file)
+        .map_err(|e| msg("settings.read_failed").with("path", file.display()).with("error", e))?;
+    let export: SettingsExport = serde_json::from_str(&content)
+        .ok()
+        .filter(|export: &SettingsExport| export.format == FORMAT)
+        .ok_or_else(|| msg("settings_io.not_export").with("path", file.display()))?;
+    settings::parse(export.settings, file)
+}
+
+/// 要写进导出文件的设置
+fn export_value(mut settings: Settings) -> serde_json::Result<Value> {
+    for key in PRIVATE {
+        settings.set(key, None);
+    }
+    serde_json::to_value(settings)
+}
+
+fn preview(current: &Settings, imported: &Settings) -> Vec<ImportItem> {
+    KEYS.iter()
+        .filter_map(|key| {
+            let value = imported.get(key)?;
+            let (error, warning) = match check(key, &value) {
+                Err(e) => (Some(e.into()), None),
+                Ok(_) => (None, check_path(key, &value).map(String::from)),
+            };
+            Some(ImportItem {
+                key: key.to_string(),
+                changes: diff(current.get(key).as_ref(), Some(&value)),
+                error,
+                warning,
+                manual: manual_hint(key).map(String::from),
+            })
+        })
+        .collect()
+}
+
+/// 设置改变后让对应的模块生效
+fn apply(app: &AppHandle, key: &str) {
+    match key {
+        hotkey::CONFIG_SECTION => {
+            let errors = hotkey::register_all(app, &HotkeyConfig::load());
+            if !errors.is_empty() {
+                warn!("部分快捷键注册失败: {}", errors.join("; "));
+            }
+        }
+        i18n::CONFIG_SECTION => i18n::reload(app),
+        auto::CONFIG_SECTION => tray::refresh(app),
+        _ => {}
+    }
+}
+
+/// 导出全部设置，返回导出的文件路径，取消选择时返回 None
+#[tauri::command]
+pub async fn export_settings(app: AppHandle) -> Reply<ExportSettings> {
+    debug!("[export_settings]");
+    let default_name = format!("svld-settings-{}.json", Local::now().format("%Y%m%d"));
+    let Some(target) = app
+        .dialog()
+        .file()
+        .set_title(i18n::t("settings_io.export_title"))
+        .set_file_name(&default_name)
+        .add_filter(i18n::t("settings_io.filter"), &["json"])
+        .blocking_save_file()
+    else {
+        return Ok(None);
+    };
+    let target = target.into_path().map_err(|e| e.to_string())?;
+
+    let export = SettingsExport {
+        format: FORMAT.to_string(),
+        exported_at: Local::now().to_rfc3339(),
+        settings: export_value(ConfigManager::load()).map_err(|e| e.to_string())?,
+    };
+    let content = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+    fs::write(&target, content).map_err(|e| {
+        error!("导出设置失败: {}", e);
+        String::from(msg("settings.write_failed").with("path", target.display()).with("error", e))
+    })?;
+    info!("设置已导出到 {}", target.display());
+    Ok(Some(target.to_string_lossy().into_owned()))
+}
+
+/// 选择要导入的文件，返回和当前设置的差异，此时还没有修改任何设置
+#[tauri::command]
+pub async fn preview_settings_import(app: AppHandle) -> Reply<PreviewSettingsImport> {
+    debug!("[preview_settings_import]");
+    let Some(file) = app
+        .dialog()
+        .file()
+        .set_title(i18n::t("settings_io.import_title"))
+        .add_filter(i18n::t("settings_io.filter"), &["json"])
+        .blocking_pick_file()
+    else {
+        return Ok(None);
+    };
+    let file = file.into_path().map_err(|e| e.to_string())?;
+    let (imported, problems) = read_export(&file)?;
+    Ok(Some(ImportPreview {
+        file: file.to_string_lossy().into_owned(),
+        items: preview(&ConfigManager::load(), &imported),
+        problems: problems.into_iter().map(String::from).collect(),
+    }))
+}
+
+/// 导入选中的设置项。重新读取文件，格式不对的项会跳过
+#[tauri::command]
+pub fn import_settings(app: AppHandle, args: ImportSettings) -> Reply<ImportSettings> {
+    let ImportSettings { file, keys } = args;
+    debug!("[import_settings] {} {:?}", file, keys);
+    let (imported, _) = read_export(&PathBuf::from(&file))?;
+    let keys: Vec<&str> = KEYS
+        .into_iter()
+        .filter(|key| keys.iter().any(|k| k == key) && !MANUAL.contains(key))
+        .filter(|key| imported.get(key).is_some_and(|value| check(key, &value).is_ok()))
+        .collect();
+    ConfigManager::update(|settings| {
+        for key in &keys {
+            settings.set(key, imported.get(key));
+        }
+    })?;
+    for key in &keys {
+        apply(&app, key);
+    }
+    info!("已导入设置: {:?}", keys);
+    Ok(keys.into_iter().map(str::to_string).collect())
+}
+
+/// 把一项设置恢复为默认值
+#[tauri::command]
+pub fn reset_settings_section(app: AppHandle, args: ResetSettingsSection) -> Reply<ResetSettingsSection> {
+    let ResetSettingsSection { key } = args;
+    debug!("[reset_settings_section] {}", key);
+    if !KEYS.contains(&key.as_str()) || MANUAL.contains(&key.as_str()) {
+        return Err(msg("settings_io.cannot_reset").with("section", &key).into());
+    }
+    ConfigManager::update(|settings| settings.set(&key, None))?;
+    apply(&app, &key);
+    info!("设置 {} 已恢复默认", key);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn previews_changed_fields() {
+        let current: Settings = serde_json::from_value(json!({
+            "version": 1,
+            "save_path": "C:\\old",
+            "auto_backup": { "enabled": false, "interval_minutes": 10, "only_while_running": true },
+            "language": "zh-CN",
+        }))
+        .unwrap();
+        let imported: Settings = serde_json::from_value(json!({
+            "version": 1,
+            "save_path": "C:\\old",
+            "auto_backup": { "enabled": true, "interval_minutes": 30, "only_while_running": true },
+            "hotkeys": "broken",
+        }))
+        .unwrap();
+
+        let items = preview(&current, &imported);
+        let keys: Vec<&str> = items.iter().map(|i| i.key.as_str()).collect();
+        assert_eq!(keys, [SAVE_PATH, auto::CONFIG_SECTION, hotkey::CONFIG_SECTION]);
+        // 路径相同，但在测试环境里不存在
+        assert!(items[0].changes.is_empty());
+        assert!(items[0].warning.is_some());
+        assert_eq!(
+            items[1].changes,
+            vec![
+                Change { field: "enabled".into(), current: Some("false".into()), imported: Some("true".into()) },
+                Change { field: "interval_minutes".into(), current: Some("10".into()), imported: Some("30".into()) },
+            ]
+        );
+        assert!(items[2].error.is_some());
+    }
+
+    #[test]
+    fn data_path_needs_manual_apply() {
+        let current: Settings = serde_json::from_value(json!({ "version": 1 })).unwrap();
+        let imported: Settings = serde_json::from_value(json!({
+            "version": 1,
+            "data_path": "D:\\svld",
+            "encryption": { "enabled": true, "verifier": "x" },
+        }))
+        .unwrap();
+
+        // 旧版本导出的文件里可能有加密配置，不会出现在预览中
+        let items = preview(&current, &imported);
+        assert_eq!(items.len(), 1);
+        assert!(items[0].manual.is_some() && !items[0].importable());
+    }
+
+    #[test]
+    fn export_leaves_out_encryption() {
+        let settings: Settings = serde_json::from_value(json!({
+            "version": 1,
+            "language": "en",
+            "encryption": { "enabled": true, "verifier": "$argon2id$x" },
+        }))
+        .unwrap();
+        let value = export_value(settings).unwrap();
+        assert!(value.get(crypto::CONFIG_SECTION).is_none());
+        assert_eq!(value.get(i18n::CONFIG_SECTION), Some(&json!("en")));
+    }
+}
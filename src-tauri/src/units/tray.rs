@@ -0,0 +1,148 @@
+/// 托盘图标：关闭窗口后程序留在托盘里，自动备份继续运行
+use log::{debug, error, info, warn};
+use tauri::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use svld_shared::commands::SaveBackup;
+use crate::backup::auto;
+use crate::backup::commands::save_backup;
+use crate::units::{file, i18n};
+use crate::units::hotkey::{self, HotkeyAction};
+
+const TRAY_ID: &str = "main";
+
+/// 菜单项 id，文案的 key 是 tray.<id>
+const ACTIONS: [&str; 3] = ["backup_now", "restore_latest", "open_folder"];
+const WINDOW_ACTIONS: [&str; 2] = ["show", "quit"];
+
+/// 需要随状态或语言更新的菜单项
+struct TrayMenu {
+    items: Vec<MenuItem<Wry>>,
+    status: MenuItem<Wry>,
+    pause: CheckMenuItem<Wry>,
+}
+
+fn label(id: &str) -> String {
+    i18n::t(&format!("tray.{}", id))
+}
+
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let item = |id: &str| MenuItem::with_id(app, id, label(id), true, None::<&str>);
+    let actions = ACTIONS.iter().map(|id| item(id)).collect::<tauri::Result<Vec<_>>>()?;
+    let window_actions = WINDOW_ACTIONS.iter().map(|id| item(id)).collect::<tauri::Result<Vec<_>>>()?;
+    let status = MenuItem::with_id(app, "status", auto::status_line(), false, None::<&str>)?;
+    let pause = CheckMenuItem::with_id(app, "pause", label("pause"), true, auto::is_paused(), None::<&str>)?;
+
+    let menu = Menu::new(app)?;
+    for action in &actions {
+        menu.append(action)?;
+    }
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&status)?;
+    menu.append(&pause)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    for action in &window_actions {
+        menu.append(action)?;
+    }
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("svld")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(on_menu_event)
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    app.manage(TrayMenu { items: actions.into_iter().chain(window_actions).collect(), status, pause });
+    Ok(())
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// 切换语言后更新所有菜单文字
+pub fn relabel(app: &AppHandle) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    for item in &menu.items {
+        let _ = item.set_text(label(item.id().as_ref()));
+    }
+    let _ = menu.pause.set_text(label("pause"));
+    refresh(app);
+}
+
+/// 自动备份状态变化后刷新菜单
+pub fn refresh(app: &AppHandle) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    if let Err(e) = menu.status.set_text(auto::status_line()) {
+        warn!("更新托盘状态失败: {}", e);
+    }
+    let _ = menu.pause.set_checked(auto::is_paused());
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    debug!("[tray] {:?}", event.id());
+    match event.id().as_ref() {
+        "backup_now" => {
+            // 结果由 save_backup 发通知
+            tauri::async_runtime::spawn(async move {
+                match save_backup(SaveBackup::default()).await {
+                    Ok(outcome) => info!("[tray] {}", i18n::translate(outcome.message())),
+                    Err(e) => warn!("[tray] 备份未完成: {}", i18n::translate(&e)),
+                }
+            });
+        }
+        "restore_latest" => {
+            app.dialog()
+                .message(i18n::t("tray.restore_confirm"))
+                .title(i18n::t("tray.restore_title"))
+                .kind(MessageDialogKind::Warning)
+                .buttons(MessageDialogButtons::OkCancelCustom(i18n::t("tray.restore_ok"), i18n::t("common.cancel")))
+                .show(move |confirmed| {
+                    if !confirmed {
+                        return;
+                    }
+                    tauri::async_runtime::spawn(async move {
+                        match hotkey::run(HotkeyAction::RestoreLatest).await {
+                            Ok(message) => info!("[tray] {}", i18n::translate(&message)),
+                            Err(e) => warn!("[tray] 读档未完成: {}", i18n::translate(&e)),
+                        }
+                    });
+                });
+        }
+        "open_folder" => {
+            if let Err(e) = file::open_data_dir() {
+                show_error(app, e);
+            }
+        }
+        "pause" => {
+            auto::set_paused(!auto::is_paused());
+            refresh(app);
+        }
+        "show" => show_main_window(app),
+        "quit" => app.exit(0),
+        _ => {}
+    }
+}
+
+fn show_error(app: &AppHandle, message: String) {
+    let message = i18n::translate(&message);
+    error!("[tray] {}", message);
+    app.dialog().message(message).title("svld").kind(MessageDialogKind::Error).show(|_| {});
+}
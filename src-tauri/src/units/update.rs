@@ -1,41 +1,226 @@
-use log::info;
-use tauri::AppHandle;
-use tauri_plugin_updater::UpdaterExt;
-use tauri_plugin_process::*;
+/// 检查和安装更新。检查只返回新版本的信息，用户确认后才下载安装；
+/// 开始安装后不再接受新的备份或还原，等进行中的结束再安装，安装前先把数据库和设置复制一份
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use chrono::Local;
+use log::{debug, error, info, warn};
+use tauri::{AppHandle, Manager, Url};
+use tauri_plugin_updater::{Update, UpdaterExt};
+use svld_shared::commands::{CheckForUpdate, GetUpdateConfig, GetVersion, InstallUpdate, SaveUpdateConfig};
+use svld_shared::Reply;
+use crate::backup::busy;
+use crate::units::{db_path, events};
+use crate::units::i18n::msg;
+use crate::units::settings::{self, ConfigManager, Section};
 
+pub use svld_shared::settings::{Channel, UpdateConfig, UpdateInfo, UpdateProgress, UpdateStage};
+
+pub const CONFIG_SECTION: &str = "update";
+
+const STABLE_ENDPOINT: &str = "https://github.com/AuceptinFang/noita-svld/releases/latest/download/latest.json";
+/// 测试版都发布在 beta 这个标签下
+const BETA_ENDPOINT: &str = "https://github.com/AuceptinFang/noita-svld/releases/download/beta/latest.json";
+/// 保留最近几次更新前的快照
+const SNAPSHOTS_KEPT: usize = 3;
+/// 等待备份结束时的检查间隔
+const WAIT_INTERVAL: Duration = Duration::from_secs(2);
+
+fn channel_endpoint(channel: Channel) -> &'static str {
+    match channel {
+        Channel::Stable => STABLE_ENDPOINT,
+        Channel::Beta => BETA_ENDPOINT,
+    }
+}
+
+impl Section for UpdateConfig {
+    const NAME: &'static str = CONFIG_SECTION;
+}
+
+/// 最近一次检查到的更新，安装时使用
+static PENDING: Mutex<Option<Update>> = Mutex::new(None);
+/// 已经开始安装或在等待安装，防止重复点击
+static INSTALLING: AtomicBool = AtomicBool::new(false);
+
+fn emit_progress(stage: UpdateStage, downloaded: u64, total: Option<u64>) {
+    events::emit(UpdateProgress { stage, downloaded, total, error: None });
+}
+
+/// 把 files 中存在的文件复制到 dir
+fn snapshot_into(dir: &Path, files: &[PathBuf]) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for file in files.iter().filter(|f| f.is_file()) {
+        if let Some(name) = file.file_name() {
+            fs::copy(file, dir.join(name))?;
+        }
+    }
+    Ok(())
+}
+
+/// 只保留最新的 keep 个快照，快照目录名以时间开头，按名称排序就是按时间排序
+fn prune_snapshots(root: &Path, keep: usize) {
+    let Ok(entries) = fs::read_dir(root) else {
+        return;
+    };
+    let mut dirs: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()).collect();
+    dirs.sort();
+    let excess = dirs.len().saturating_sub(keep);
+    for dir in dirs.into_iter().take(excess) {
+        if let Err(e) = fs::remove_dir_all(&dir) {
+            warn!("删除旧的更新快照失败 {}: {}", dir.display(), e);
+        }
+    }
+}
+
+/// 更新前复制数据库和设置，新版本出问题时可以手动恢复
+fn snapshot(app: &AppHandle, from_version: &str) -> Result<PathBuf, String> {
+    let root = app.path().app_data_dir().map_err(|e| e.to_string())?.join("pre-update");
+    let dir = root.join(format!("{}-v{}", Local::now().format("%Y%m%d-%H%M%S"), from_version));
+    let db = PathBuf::from(db_path::get_db_path().map_err(|e| e.to_string())?);
+    let mut files = vec![settings::settings_path()];
+    // WAL 模式下未合并的数据在旁边的 -wal 文件里
+    for suffix in ["", "-wal", "-shm"] {
+        files.push(PathBuf::from(format!("{}{}", db.display(), suffix)));
+    }
+    snapshot_into(&dir, &files).map_err(|e| {
+        error!("更新前备份数据失败: {}", e);
+        String::from(msg("update.snapshot_failed").with("error", e))
+    })?;
+    prune_snapshots(&root, SNAPSHOTS_KEPT);
+    info!("更新前的数据已复制到 {}", dir.display());
+    Ok(dir)
+}
+
+/// 下载并安装，成功后重启。调用前已经关闭了 busy，这里等进行中的操作结束
+async fn install(app: AppHandle, update: Update) -> Result<(), String> {
+    while busy::is_busy() {
+        tokio::time::sleep(WAIT_INTERVAL).await;
+    }
+    snapshot(&app, &update.current_version)?;
+
+    let mut downloaded = 0u64;
+    let mut last_percent = None;
+    update
+        .download_and_install(
+            move |chunk_length, content_length| {
+                downloaded += chunk_length as u64;
+                // 按百分比节流，没有总大小时每块都发
+                let percent = content_length.map(|total| downloaded * 100 / total.max(1));
+                if percent.is_none() || percent != last_percent {
+                    last_percent = percent;
+                    emit_progress(UpdateStage::Downloading, downloaded, content_length);
+                }
+            },
+            move || {
+                info!("下载完成，正在安装...");
+                emit_progress(UpdateStage::Installing, 0, None);
+            },
+        )
+        .await
+        .map_err(|e| {
+            error!("安装更新失败: {}", e);
+            String::from(msg("update.install_failed").with("error", e))
+        })?;
+
+    info!("更新成功，应用即将重启");
+    app.restart();
+}
+
+/// 按设置的渠道检查更新，有新版本时返回版本和更新说明，不会下载
 #[tauri::command]
-pub async fn check_update(app: tauri::AppHandle) -> Result<String, String> {
-    let updater = app.updater().map_err(|e| e.to_string())?;
-
-    // 检查是否有新版本
-    // 根据 tauri.conf.json 里的 endpoints 去请求更新源
-    let update_result = updater.check().await.map_err(|e| e.to_string())?;
-
-    if let Some(update) = update_result {
-        update
-            .download_and_install(
-                |chunk_length, content_length| {
-                    if let Some(total) = content_length {
-                        info!("下载进度: {}/{}", chunk_length, total);
-                    }
-                },
-                || {
-                    info!("下载完成，正在安装...");
-                },
-            )
-            .await
-            .map_err(|e| e.to_string())?;
-
-        info!("更新成功，应用即将重启");
-        app.restart();
-
-        Ok("Update installed successfully, restarting...".to_string())
-    } else {
-        Ok("Already up to date".to_string())
+pub async fn check_for_update(app: AppHandle) -> Reply<CheckForUpdate> {
+    let channel = UpdateConfig::load().channel;
+    debug!("[check_for_update] {:?}", channel);
+    let endpoint = Url::parse(channel_endpoint(channel)).map_err(|e| e.to_string())?;
+    let updater = app
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .and_then(|builder| builder.build())
+        .map_err(|e| e.to_string())?;
+    let update = updater.check().await.map_err(|e| {
+        error!("检查更新失败: {}", e);
+        String::from(msg("update.check_failed").with("error", e))
+    })?;
+
+    let info = update.as_ref().map(|update| UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        notes: update.body.clone().filter(|notes| !notes.trim().is_empty()),
+        date: update.date.map(|date| date.date().to_string()),
+        channel,
+    });
+    *PENDING.lock().unwrap() = update;
+    Ok(info)
+}
+
+/// 安装检查到的更新。有备份或还原在进行时等待它们结束，返回是否需要等待
+/// 进度通过 UpdateProgress 事件发出，安装完成后程序会重启
+#[tauri::command]
+pub async fn install_update(app: AppHandle) -> Reply<InstallUpdate> {
+    debug!("[install_update]");
+    let update = PENDING.lock().unwrap().clone().ok_or_else(|| String::from(msg("update.not_checked")))?;
+    if INSTALLING.swap(true, Ordering::SeqCst) {
+        return Err(msg("update.in_progress").into());
+    }
+    let deferred = busy::close();
+    if deferred {
+        info!("有备份或还原正在进行，结束后再安装更新");
+        emit_progress(UpdateStage::Waiting, 0, None);
     }
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = install(app, update).await {
+            busy::reopen();
+            INSTALLING.store(false, Ordering::SeqCst);
+            let failed = UpdateProgress { stage: UpdateStage::Failed, downloaded: 0, total: None, error: Some(e) };
+            events::emit(failed);
+        }
+    });
+    Ok(deferred)
+}
+
+#[tauri::command]
+pub fn get_update_config() -> Reply<GetUpdateConfig> {
+    Ok(UpdateConfig::load())
 }
+
+/// 切换渠道后之前检查到的更新不再有效
+#[tauri::command]
+pub fn save_update_config(args: SaveUpdateConfig) -> Reply<SaveUpdateConfig> {
+    let SaveUpdateConfig { config } = args;
+    debug!("[save_update_config] {:?}", config);
+    ConfigManager::save_section(CONFIG_SECTION, Some(&config))?;
+    *PENDING.lock().unwrap() = None;
+    Ok(())
+}
+
 #[tauri::command]
-pub async fn get_version(app: AppHandle) -> Result<String, String> {
+pub async fn get_version(app: AppHandle) -> Reply<GetVersion> {
     let version = app.package_info().version.to_string();
     Ok(format!("v{}", version))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_existing_files_and_prunes_old() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        let db = root.join("backups.db");
+        fs::write(&db, b"sqlite").unwrap();
+
+        let snapshots = root.join("pre-update");
+        for name in ["20240101-000000-v0.1.0", "20240201-000000-v0.1.1", "20240301-000000-v0.1.2"] {
+            snapshot_into(&snapshots.join(name), &[db.clone(), root.join("backups.db-wal")]).unwrap();
+        }
+        assert!(snapshots.join("20240301-000000-v0.1.2/backups.db").is_file());
+        assert!(!snapshots.join("20240301-000000-v0.1.2/backups.db-wal").exists());
+
+        prune_snapshots(&snapshots, 2);
+        assert!(!snapshots.join("20240101-000000-v0.1.0").exists());
+        assert!(snapshots.join("20240201-000000-v0.1.1").exists());
+    }
+}
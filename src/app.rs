@@ -1,5 +1,4 @@
 use serde::{Deserialize, Serialize};
-use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 use yew_router::prelude::*;
@@ -7,12 +6,7 @@ use crate::components::*;
 use crate::pages::{index::Index, backup::Backup_page,setting::Setting,info::Info};
 use crate::router::Route;
 use crate::components::SideBar;
-
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
-    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
-}
+use crate::i18n::t;
 
 fn switch(routes: Route) -> Html {
     match routes {
@@ -26,7 +20,7 @@ fn switch(routes: Route) -> Html {
 
         Route::Settings => html! { <Setting /> },
 
-        Route::NotFound => html! { <h1>{ "404 页面不存在" }</h1> },
+        Route::NotFound => html! { <h1>{ t("app.not_found") }</h1> },
     }
 }
 
@@ -0,0 +1,135 @@
+use svld_shared::backup::{AutoBackupConfig, AutoBackupStatus};
+use svld_shared::commands::{GetAutoBackup, SaveAutoBackup, SetAutoBackupPaused};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use crate::i18n::{self, t, tf};
+use crate::invoke::invoke;
+
+#[function_component(AutoBackup)]
+pub fn auto_backup() -> Html {
+    let status = use_state(AutoBackupStatus::default);
+    let message = use_state(|| None::<(bool, String)>);
+
+    let refresh = {
+        let status = status.clone();
+        move || {
+            let status = status.clone();
+            spawn_local(async move {
+                if let Ok(value) = invoke(&GetAutoBackup).await {
+                    status.set(value);
+                }
+            });
+        }
+    };
+
+    {
+        let refresh = refresh.clone();
+        use_effect_with((), move |_| {
+            refresh();
+            || {}
+        });
+    }
+
+    let update = |apply: fn(&mut AutoBackupConfig, &HtmlInputElement)| {
+        let status = status.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut next = (*status).clone();
+            apply(&mut next.config, &input);
+            status.set(next);
+        })
+    };
+
+    let on_save = {
+        let status = status.clone();
+        let message = message.clone();
+        let refresh = refresh.clone();
+        Callback::from(move |_: MouseEvent| {
+            let config = status.config.clone();
+            let message = message.clone();
+            let refresh = refresh.clone();
+            spawn_local(async move {
+                match invoke(&SaveAutoBackup { config }).await {
+                    Ok(_) => message.set(Some((true, t("common.saved")))),
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+                refresh();
+            });
+        })
+    };
+
+    let on_pause = {
+        let status = status.clone();
+        let refresh = refresh.clone();
+        Callback::from(move |_: MouseEvent| {
+            let paused = !status.paused;
+            let refresh = refresh.clone();
+            spawn_local(async move {
+                let _ = invoke(&SetAutoBackupPaused { paused }).await;
+                refresh();
+            });
+        })
+    };
+
+    let badge = match (status.config.enabled, status.paused) {
+        (false, _) => html! { <span class="badge badge-error">{ t("common.off") }</span> },
+        (true, true) => html! { <span class="badge badge-error">{ t("auto.badge_paused") }</span> },
+        (true, false) => html! { <span class="badge badge-success">{ t("auto.badge_running") }</span> },
+    };
+
+    html! {
+        <div class="path-card auto-backup-card">
+            <div class="path-header">
+                <span class="path-label">{ t("auto.title") }</span>
+                { badge }
+            </div>
+            <p class="auto-backup-help">
+                { t("auto.help") }
+            </p>
+            <label class="auto-backup-check">
+                <input
+                    type="checkbox"
+                    checked={status.config.enabled}
+                    onchange={update(|c, input| c.enabled = input.checked())}
+                />
+                <span>{ t("auto.enable") }</span>
+            </label>
+            <label class="auto-backup-row">
+                <span>{ t("auto.every") }</span>
+                <input
+                    type="number"
+                    min="1"
+                    value={status.config.interval_minutes.to_string()}
+                    onchange={update(|c, input| c.interval_minutes = input.value().parse().unwrap_or(c.interval_minutes))}
+                />
+                <span>{ t("auto.minutes") }</span>
+            </label>
+            <label class="auto-backup-check">
+                <input
+                    type="checkbox"
+                    checked={status.config.only_while_running}
+                    onchange={update(|c, input| c.only_while_running = input.checked())}
+                />
+                <span>{ t("auto.only_while_running") }</span>
+            </label>
+            if let Some(last_run) = &status.last_run {
+                <div class="auto-backup-last">
+                    { tf("auto.last_run", &[
+                        ("time", last_run.clone()),
+                        ("result", status.last_result.as_deref().map(i18n::message).unwrap_or_default()),
+                    ]) }
+                </div>
+            }
+            <div class="auto-backup-actions">
+                <button class="btn btn-secondary" onclick={on_save}>{ t("common.save") }</button>
+                <button class="btn btn-secondary" onclick={on_pause} disabled={!status.config.enabled}>
+                    { if status.paused { t("auto.resume") } else { t("auto.pause") } }
+                </button>
+                if let Some((ok, text)) = &*message {
+                    <span class={if *ok { "auto-backup-message" } else { "auto-backup-message auto-backup-error" }}>{ text }</span>
+                }
+            </div>
+        </div>
+    }
+}
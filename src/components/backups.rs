@@ -1,32 +1,191 @@
-use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use svld_shared::events::{BackupCreated, BackupDeleted, BackupsChanged, RestoreFinished, RestoreStarted};
+use svld_shared::backup::{
+    biome_at, Backup, BackupQuery, BackupSort, BatchResult, ImportOutcome, LoadOutcome, MetaFilter, ModDiff, SaveOutcome,
+    ScopeConflict,
+};
+use svld_shared::commands::{
+    BatchDeleteBackups, BatchExportBackups, BatchPinBackups, BatchTagBackups, DeleteBackup, ExportBackup, GetAllTags,
+    GetBackupThumbnail, ImportBackup, ListBackups, LoadBackup, OpenBackup, PinBackup, PreviewRestore, SaveBackup,
+    SetBackupNotes, SetBackupTags, UnpinBackup,
+};
+use wasm_bindgen::JsValue;
 use time::OffsetDateTime;
-use wasm_bindgen::prelude::*;
-use serde_json::json;
+use time::format_description::well_known::Rfc3339;
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 use web_sys::console;
 use time::macros::format_description;
+use crate::i18n::{self, t, tf, Locale};
+use crate::invoke::invoke;
+use crate::event::use_event;
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct BackupsProps {
     pub is_valid: bool,
 }
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], catch)]
-    async fn invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+/// 列出 mod 的变化
+pub fn render_mod_diff(diff: &ModDiff) -> Html {
+    let row = |key: &str, mods: &Vec<String>| {
+            if mods.is_empty() {
+                html! {}
+            } else {
+                html! { <li>{ format!("{}: {}", t(key), mods.join(", ")) }</li> }
+            }
+        };
+    html! {
+        <ul class="mod-diff">
+            { row("backups.mods_added", &diff.added) }
+            { row("backups.mods_removed", &diff.removed) }
+            { row("backups.mods_reordered", &diff.reordered) }
+        </ul>
+    }
+}
+
+// 还原范围选项，显示名的 key 是 restore.scope_<值>
+const RESTORE_SCOPES: [&str; 4] = ["full", "world+player", "persistent", "stats"];
+
+/// 勾选的范围加上手动输入的路径
+fn merge_scope(selected: &[String], extra: &str) -> Vec<String> {
+    if selected.iter().any(|s| s == "full") {
+        return vec!["full".to_string()];
+    }
+    let mut scope = selected.to_vec();
+    scope.extend(
+        extra
+            .split(|c| c == ',' || c == '，')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty()),
+    );
+    scope
+}
+
+/// 每次向后端请求的条数
+const PAGE_SIZE: u32 = 50;
+/// 每一行的固定高度（像素），和 .backup-row 的 height 一致
+const ROW_HEIGHT: f64 = 150.0;
+/// 可见区域上下额外渲染的行数
+const OVERSCAN: usize = 4;
+const SORTS: [(BackupSort, &str); 4] = [
+    (BackupSort::Time, "backups.sort_time"),
+    (BackupSort::Size, "backups.sort_size"),
+    (BackupSort::Name, "backups.sort_name"),
+    (BackupSort::Pinned, "backups.sort_pinned"),
+];
+
+/// 已加载的备份，总是排序后最前面的连续一段
+#[derive(Debug, Default, PartialEq)]
+struct BackupList {
+    items: Vec<Backup>,
+}
+
+enum ListAction {
+    Reset(Vec<Backup>),
+    Append(Vec<Backup>),
+}
+
+impl Reducible for BackupList {
+    type Action = ListAction;
+
+    fn reduce(self: Rc<Self>, action: ListAction) -> Rc<Self> {
+        match action {
+            ListAction::Reset(items) => Rc::new(BackupList { items }),
+            ListAction::Append(more) => {
+                let mut items = self.items.clone();
+                items.extend(more);
+                Rc::new(BackupList { items })
+            }
+        }
+    }
 }
 
-// 对应后端的数据结构
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct Backup {
-    pub id: i32, // 唯一标识
-    pub name: Option<String>, // 备注
-    pub size: i64,
-    pub digest: String,
-    #[serde(with = "time::serde::rfc3339")]
-    pub save_time: OffsetDateTime,
+/// 翻页状态，异步回调需要读到最新的值所以不放在 use_state 里
+#[derive(Debug, Default)]
+struct Paging {
+    sort: BackupSort,
+    ascending: bool,
+    /// 下一页的游标，None 表示已经到底
+    cursor: Option<String>,
+    /// 每次重新查询加一，用来丢弃旧查询晚到的结果
+    generation: u32,
+    loading: bool,
+}
+
+/// 解析 "orbs>=5, gold>100" 这样的元信息筛选条件
+fn parse_meta_filters(input: &str) -> Vec<MetaFilter> {
+    const OPS: [&str; 8] = [">=", "<=", "!=", "==", "~", "=", ">", "<"];
+    input
+        .split(',')
+        .filter_map(|expr| {
+            let (pos, op) = OPS
+                .iter()
+                .filter_map(|op| expr.find(op).map(|pos| (pos, *op)))
+                .min_by_key(|(pos, _)| *pos)?;
+            let field = expr[..pos].trim();
+            let value = expr[pos + op.len()..].trim();
+            if field.is_empty() || value.is_empty() {
+                return None;
+            }
+            Some(MetaFilter { field: field.to_string(), op: op.to_string(), value: value.to_string() })
+        })
+        .collect()
+}
+
+/// 把 <input type="date"> 的值转换成带本地时区的时间
+pub fn parse_local_date(date: &str, time: &str) -> Option<OffsetDateTime> {
+    if date.is_empty() {
+        return None;
+    }
+    // getTimezoneOffset 返回 UTC - 本地时间 (分钟)
+    let offset = -(js_sys::Date::new_0().get_timezone_offset() as i32);
+    let sign = if offset >= 0 { '+' } else { '-' };
+    let text = format!("{}T{}{}{:02}:{:02}", date, time, sign, offset.abs() / 60, offset.abs() % 60);
+    OffsetDateTime::parse(&text, &Rfc3339).ok()
+}
+
+/// 逗号分隔的标签，中文逗号也可以
+fn parse_tags(input: &str) -> Vec<String> {
+    input
+        .split(|c| c == ',' || c == '，')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+fn input_value(node: &NodeRef) -> String {
+    node.cast::<web_sys::HtmlInputElement>()
+        .map(|input| input.value().trim().to_string())
+        .unwrap_or_default()
+}
+
+#[derive(Properties, PartialEq)]
+struct ThumbnailProps {
+    id: i32,
+}
+
+/// 备份的地图缩略图，没有地形数据时不显示
+#[function_component(Thumbnail)]
+fn thumbnail(props: &ThumbnailProps) -> Html {
+    let src = use_state(|| None::<String>);
+    {
+        let src = src.clone();
+        use_effect_with(props.id, move |id| {
+            let id = *id;
+            spawn_local(async move {
+                match invoke(&GetBackupThumbnail { id }).await {
+                    Ok(url) => src.set(url),
+                    Err(e) => console::log_1(&format!("获取缩略图失败：{:?}", e).into()),
+                }
+            });
+            || {}
+        });
+    }
+
+    match &*src {
+        Some(url) => html! { <img class="card-thumb" src={url.clone()} alt={t("backups.thumb_alt")} /> },
+        None => html! {},
+    }
 }
 
 // 用于控制弹窗状态的枚举
@@ -34,41 +193,217 @@ pub struct Backup {
 enum ModalAction {
     None,
     ConfirmRestore(i32, String), // id, name
-    ConfirmDelete(i32, String),  // id, name
+    ConfirmDelete(i32, String, bool), // id, name, pinned
+    EditInfo(i32, String, String), // id, notes, tags
+    ModMismatch(i32, String, ModDiff, Vec<String>), // id, name, mod 变化, 还原范围
+    LoadPassphrase(i32, String, bool, Vec<String>), // id, name, force, 还原范围
+    ImportPassphrase(String),    // 导入的文件路径
+    Export(i32, String),         // id, name
+    BatchDelete(Vec<i32>, usize), // ids, 其中置顶的个数
+    BatchTag(Vec<i32>),
+    BatchExport(Vec<i32>),
+    BatchSummary(BatchResult),   // 批量操作的结果
     ShowError(String),           // 错误信息
 }
 
 #[function_component(Backups)]
 pub fn backups() -> Html {
-    let backups_list = use_state(|| Vec::<Backup>::new());
+    let list = use_reducer(BackupList::default);
+    let paging = use_mut_ref(Paging::default);
+    let list_ref = use_node_ref();
+    // 列表的滚动位置和可见高度
+    let viewport = use_state(|| (0.0_f64, 0.0_f64));
     let note_input_ref = use_node_ref();
     let modal_state = use_state(|| ModalAction::None);
     let finished = use_state(|| true);
+    let all_tags = use_state(|| Vec::<String>::new());
+    let query = use_mut_ref(BackupQuery::default);
+
+    // 筛选栏
+    let filter_text_ref = use_node_ref();
+    let filter_tag_ref = use_node_ref();
+    let filter_from_ref = use_node_ref();
+    let filter_to_ref = use_node_ref();
+    let filter_min_ref = use_node_ref();
+    let filter_max_ref = use_node_ref();
+    let filter_meta_ref = use_node_ref();
+
+    // 编辑备注和标签
+    let notes_input_ref = use_node_ref();
+    let tags_input_ref = use_node_ref();
+    // 批量打标签时要去掉的标签
+    let remove_tags_input_ref = use_node_ref();
 
-    // 获取备份列表
+    // 多选的备份，按选择的顺序
+    let selected = use_state(Vec::<i32>::new);
+
+    // 还原范围和预览
+    let restore_scope = use_state(|| vec!["full".to_string()]);
+    let restore_preview = use_state(|| None::<Vec<ScopeConflict>>);
+    let scope_extra_ref = use_node_ref();
+
+    // 加密备份的口令
+    let passphrase_input_ref = use_node_ref();
+
+    // 从第一页重新获取备份列表
     let fetch_backups = {
-        let backups_list = backups_list.clone();
+        let list = list.clone();
+        let paging = paging.clone();
+        let list_ref = list_ref.clone();
+        let all_tags = all_tags.clone();
+        let query = query.clone();
         move || {
-            let backups_list = backups_list.clone();
+            let list = list.clone();
+            let paging = paging.clone();
+            let all_tags = all_tags.clone();
+            let args = {
+                let mut paging = paging.borrow_mut();
+                paging.generation += 1;
+                paging.cursor = None;
+                paging.loading = true;
+                ListBackups {
+                    query: query.borrow().clone(),
+                    sort: paging.sort,
+                    descending: !paging.ascending,
+                    cursor: None,
+                    limit: PAGE_SIZE,
+                }
+            };
+            let generation = paging.borrow().generation;
+            if let Some(element) = list_ref.cast::<web_sys::Element>() {
+                element.set_scroll_top(0);
+            }
             spawn_local(async move {
-                match invoke("get_all_backups", JsValue::NULL).await {
-                    Ok(response) => {
-                        match serde_wasm_bindgen::from_value::<Vec<Backup>>(response) {
-                            Ok(mut data) => {
-                                console::log_1(&"获取存档正常".into());
-                                // 按时间倒序排序（最新的在最上面）
-                                data.sort_by(|a, b| b.save_time.cmp(&a.save_time));
-                                backups_list.set(data);
-                            }
-                            Err(e) => console::log_1(&format!("解析失败: {:?}", e).into()),
+                if let Ok(tags) = invoke(&GetAllTags).await {
+                    all_tags.set(tags);
+                }
+
+                let result = invoke(&args).await;
+                let items = {
+                    let mut paging = paging.borrow_mut();
+                    if paging.generation != generation {
+                        return;
+                    }
+                    paging.loading = false;
+                    match result {
+                        Ok(page) => {
+                            paging.cursor = page.next_cursor;
+                            page.items
+                        }
+                        Err(e) => {
+                            console::log_1(&format!("获取失败: {:?}", e).into());
+                            return;
                         }
                     }
-                    Err(e) => console::log_1(&format!("获取失败: {:?}", e).into()),
+                };
+                list.dispatch(ListAction::Reset(items));
+            });
+        }
+    };
+
+    // 接着上一页往后取
+    let load_more = {
+        let list = list.clone();
+        let paging = paging.clone();
+        let query = query.clone();
+        move || {
+            let args = {
+                let mut paging = paging.borrow_mut();
+                let Some(cursor) = paging.cursor.clone() else {
+                    return;
+                };
+                if paging.loading {
+                    return;
+                }
+                paging.loading = true;
+                ListBackups {
+                    query: query.borrow().clone(),
+                    sort: paging.sort,
+                    descending: !paging.ascending,
+                    cursor: Some(cursor),
+                    limit: PAGE_SIZE,
                 }
+            };
+            let generation = paging.borrow().generation;
+            let list = list.clone();
+            let paging = paging.clone();
+            spawn_local(async move {
+                let result = invoke(&args).await;
+                let items = {
+                    let mut paging = paging.borrow_mut();
+                    if paging.generation != generation {
+                        return;
+                    }
+                    paging.loading = false;
+                    match result {
+                        Ok(page) => {
+                            paging.cursor = page.next_cursor;
+                            page.items
+                        }
+                        Err(e) => {
+                            console::log_1(&format!("获取下一页失败: {:?}", e).into());
+                            return;
+                        }
+                    }
+                };
+                list.dispatch(ListAction::Append(items));
             });
         }
     };
 
+    let on_scroll = {
+        let list_ref = list_ref.clone();
+        let viewport = viewport.clone();
+        Callback::from(move |_: Event| {
+            if let Some(element) = list_ref.cast::<web_sys::Element>() {
+                viewport.set((element.scroll_top() as f64, element.client_height() as f64));
+            }
+        })
+    };
+
+    // 第一次渲染后量出可见高度；快滚到已加载部分的底部时取下一页
+    {
+        let list_ref = list_ref.clone();
+        let viewport = viewport.clone();
+        let load_more = load_more.clone();
+        use_effect_with((list.items.len(), *viewport), move |(len, (top, height))| {
+            if *height <= 0.0 {
+                if let Some(element) = list_ref.cast::<web_sys::Element>() {
+                    if element.client_height() > 0 {
+                        viewport.set((*top, element.client_height() as f64));
+                    }
+                }
+            } else if ((top + height) / ROW_HEIGHT).ceil() as usize + OVERSCAN >= *len {
+                load_more();
+            }
+            || {}
+        });
+    }
+
+    let on_sort_change = {
+        let paging = paging.clone();
+        let fetch = fetch_backups.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            if let Some((sort, _)) = usize::try_from(select.selected_index()).ok().and_then(|i| SORTS.get(i)) {
+                paging.borrow_mut().sort = *sort;
+                fetch();
+            }
+        })
+    };
+
+    let on_toggle_order = {
+        let paging = paging.clone();
+        let fetch = fetch_backups.clone();
+        Callback::from(move |_: MouseEvent| {
+            {
+                let mut paging = paging.borrow_mut();
+                paging.ascending = !paging.ascending;
+            }
+            fetch();
+        })
+    };
+
     // 初始化加载
     {
         let fetch = fetch_backups.clone();
@@ -78,6 +413,32 @@ pub fn backups() -> Html {
         });
     }
 
+    // 快捷键、托盘、自动备份和远程同步也会增删备份，收到事件后刷新列表
+    {
+        let fetch = fetch_backups.clone();
+        use_event::<BackupCreated, _>(move |_| fetch());
+    }
+    {
+        let fetch = fetch_backups.clone();
+        use_event::<BackupDeleted, _>(move |_| fetch());
+    }
+    {
+        let fetch = fetch_backups.clone();
+        use_event::<BackupsChanged, _>(move |_| fetch());
+    }
+    {
+        let finished = finished.clone();
+        use_event::<RestoreStarted, _>(move |_| finished.set(false));
+    }
+    {
+        let fetch = fetch_backups.clone();
+        let finished = finished.clone();
+        use_event::<RestoreFinished, _>(move |_| {
+            finished.set(true);
+            fetch();
+        });
+    }
+
     // 创建备份 (Create)
     let on_create_click = {
         let note_input_ref = note_input_ref.clone();
@@ -96,11 +457,15 @@ pub fn backups() -> Html {
 
             spawn_local(async move {
                 // 调用 Tauri: save_backup
-                let args = serde_wasm_bindgen::to_value(&json!({ "name": note })).unwrap();
                 console::log_1(&format!("name: {}", note).into());
                 finished.set(false);
-                match invoke("save_backup", args).await {
-                    Ok(_) => {
+                match invoke(&SaveBackup { name: Some(note) }).await {
+                    // 存档没有变化，没有新建备份，提示一下相同的是哪个
+                    Ok(SaveOutcome::Duplicate { message, .. }) => {
+                        finished.set(true);
+                        modal_state.set(ModalAction::ShowError(i18n::message(&message)));
+                    }
+                    Ok(SaveOutcome::Saved { .. }) => {
                         console::log_1(&"保存成功".into());
                         finished.set(true);
                         // 清空输入框并刷新列表
@@ -109,7 +474,7 @@ pub fn backups() -> Html {
                     }
                     Err(err) => {
                         // 从 JsValue 中提取错误信息
-                        let err_msg = err.as_string().unwrap_or_else(|| "保存失败".to_string());
+                        let err_msg = i18n::error(&err);
                         finished.set(true);
                         console::log_1(&format!("保存失败: {}", err_msg).into());
                         // 显示错误弹窗
@@ -120,18 +485,265 @@ pub fn backups() -> Html {
         })
     };
 
+    // 导入备份文件，path 为空时由后端弹出文件选择框
+    let run_import = {
+        let fetch = fetch_backups.clone();
+        let modal_state = modal_state.clone();
+        let finished = finished.clone();
+        Callback::from(move |(path, passphrase): (Option<String>, Option<String>)| {
+            let fetch = fetch.clone();
+            let modal_state = modal_state.clone();
+            let finished = finished.clone();
+            spawn_local(async move {
+                finished.set(false);
+                match invoke(&ImportBackup { path, passphrase }).await {
+                    Ok(ImportOutcome::Imported { message }) => {
+                        console::log_1(&i18n::message(&message).into());
+                        fetch();
+                    }
+                    Ok(ImportOutcome::PassphraseRequired { path }) => {
+                        modal_state.set(ModalAction::ImportPassphrase(path));
+                    }
+                    Ok(ImportOutcome::Cancelled) => {}
+                    Err(err) => {
+                        let err_msg = i18n::error(&err);
+                        modal_state.set(ModalAction::ShowError(err_msg));
+                    }
+                }
+                finished.set(true);
+            });
+        })
+    };
+
+    let on_import_click = {
+        let run_import = run_import.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            run_import.emit((None, None));
+        })
+    };
+
+    // 预览还原会改动的内容
+    let refresh_preview = {
+        let restore_preview = restore_preview.clone();
+        Callback::from(move |(id, scope): (i32, Vec<String>)| {
+            let restore_preview = restore_preview.clone();
+            restore_preview.set(None);
+            spawn_local(async move {
+                match invoke(&PreviewRestore { backup_id: id, scope: Some(scope) }).await {
+                    Ok(summary) => restore_preview.set(Some(summary)),
+                    Err(e) => console::log_1(&format!("预览还原失败：{:?}", e).into()),
+                }
+            });
+        })
+    };
+
     // 触发弹窗逻辑
     let trigger_restore = {
         let modal_state = modal_state.clone();
+        let restore_scope = restore_scope.clone();
+        let refresh_preview = refresh_preview.clone();
         Callback::from(move |(id, name): (i32, String)| {
+            let scope = vec!["full".to_string()];
+            restore_scope.set(scope.clone());
+            refresh_preview.emit((id, scope));
             modal_state.set(ModalAction::ConfirmRestore(id, name));
         })
     };
 
+    // 勾选或取消某个还原范围
+    let on_scope_change = {
+        let modal_state = modal_state.clone();
+        let restore_scope = restore_scope.clone();
+        let scope_extra_ref = scope_extra_ref.clone();
+        let refresh_preview = refresh_preview.clone();
+        Callback::from(move |toggled: Option<String>| {
+            let mut selected = (*restore_scope).clone();
+            if let Some(value) = toggled {
+                if selected.contains(&value) {
+                    selected.retain(|s| *s != value);
+                } else {
+                    selected.push(value);
+                }
+                restore_scope.set(selected.clone());
+            }
+            if let ModalAction::ConfirmRestore(id, _) = &*modal_state {
+                refresh_preview.emit((*id, merge_scope(&selected, &input_value(&scope_extra_ref))));
+            }
+        })
+    };
+
     let trigger_delete = {
+        let modal_state = modal_state.clone();
+        Callback::from(move |(id, name, pinned): (i32, String, bool)| {
+            modal_state.set(ModalAction::ConfirmDelete(id, name, pinned));
+        })
+    };
+
+    // 置顶 / 取消置顶
+    let trigger_pin = {
+        let fetch = fetch_backups.clone();
+        Callback::from(move |(id, pinned): (i32, bool)| {
+            let fetch = fetch.clone();
+            spawn_local(async move {
+                let result = if pinned {
+                    invoke(&UnpinBackup { id }).await
+                } else {
+                    invoke(&PinBackup { id }).await
+                };
+                if let Err(e) = result {
+                    console::log_1(&format!("切换置顶失败：{:?}", e).into());
+                }
+                fetch();
+            });
+        })
+    };
+
+    let trigger_export = {
         let modal_state = modal_state.clone();
         Callback::from(move |(id, name): (i32, String)| {
-            modal_state.set(ModalAction::ConfirmDelete(id, name));
+            modal_state.set(ModalAction::Export(id, name));
+        })
+    };
+
+    let toggle_select = {
+        let selected = selected.clone();
+        Callback::from(move |id: i32| {
+            let mut ids = (*selected).clone();
+            match ids.iter().position(|selected| *selected == id) {
+                Some(index) => {
+                    ids.remove(index);
+                }
+                None => ids.push(id),
+            }
+            selected.set(ids);
+        })
+    };
+
+    // 只能选中已经加载出来的部分
+    let on_select_all = {
+        let selected = selected.clone();
+        let list = list.clone();
+        Callback::from(move |_: MouseEvent| selected.set(list.items.iter().map(|b| b.id).collect()))
+    };
+
+    let on_clear_selection = {
+        let selected = selected.clone();
+        Callback::from(move |_: MouseEvent| selected.set(Vec::new()))
+    };
+
+    // 批量操作完成后显示汇总，失败的项保持选中方便重试
+    let show_batch_result = {
+        let selected = selected.clone();
+        let modal_state = modal_state.clone();
+        Callback::from(move |result: Result<BatchResult, JsValue>| match result {
+            Ok(result) => {
+                selected.set(result.failed.iter().map(|f| f.id).collect());
+                modal_state.set(ModalAction::BatchSummary(result));
+            }
+            Err(e) => modal_state.set(ModalAction::ShowError(i18n::error(&e))),
+        })
+    };
+
+    let on_batch_pin = {
+        let selected = selected.clone();
+        let show_batch_result = show_batch_result.clone();
+        let finished = finished.clone();
+        Callback::from(move |pinned: bool| {
+            let ids = (*selected).clone();
+            let show_batch_result = show_batch_result.clone();
+            let finished = finished.clone();
+            finished.set(false);
+            spawn_local(async move {
+                show_batch_result.emit(invoke(&BatchPinBackups { ids, pinned }).await);
+                finished.set(true);
+            });
+        })
+    };
+
+    let on_batch_tag = {
+        let selected = selected.clone();
+        let modal_state = modal_state.clone();
+        Callback::from(move |_: MouseEvent| modal_state.set(ModalAction::BatchTag((*selected).clone())))
+    };
+
+    let on_batch_export = {
+        let selected = selected.clone();
+        let modal_state = modal_state.clone();
+        Callback::from(move |_: MouseEvent| modal_state.set(ModalAction::BatchExport((*selected).clone())))
+    };
+
+    let on_batch_delete = {
+        let selected = selected.clone();
+        let modal_state = modal_state.clone();
+        let list = list.clone();
+        Callback::from(move |_: MouseEvent| {
+            let ids = (*selected).clone();
+            let pinned = list.items.iter().filter(|b| b.pinned && ids.contains(&b.id)).count();
+            modal_state.set(ModalAction::BatchDelete(ids, pinned));
+        })
+    };
+
+    let trigger_edit = {
+        let modal_state = modal_state.clone();
+        Callback::from(move |(id, notes, tags): (i32, String, String)| {
+            modal_state.set(ModalAction::EditInfo(id, notes, tags));
+        })
+    };
+
+    // 应用筛选条件
+    let on_search = {
+        let query = query.clone();
+        let fetch = fetch_backups.clone();
+        let refs = (
+            filter_text_ref.clone(),
+            filter_tag_ref.clone(),
+            filter_from_ref.clone(),
+            filter_to_ref.clone(),
+            filter_min_ref.clone(),
+            filter_max_ref.clone(),
+            filter_meta_ref.clone(),
+        );
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            let (text, tag, from, to, min, max, meta) = &refs;
+            let non_empty = |v: String| if v.is_empty() { None } else { Some(v) };
+            let mb = |v: String| v.parse::<f64>().ok().map(|mb| (mb * 1024.0 * 1024.0) as i64);
+
+            *query.borrow_mut() = BackupQuery {
+                text: non_empty(input_value(text)),
+                tag: non_empty(input_value(tag)),
+                from: parse_local_date(&input_value(from), "00:00:00"),
+                to: parse_local_date(&input_value(to), "23:59:59"),
+                min_size: mb(input_value(min)),
+                max_size: mb(input_value(max)),
+                meta: parse_meta_filters(&input_value(meta)),
+            };
+            fetch();
+        })
+    };
+
+    let on_clear_search = {
+        let query = query.clone();
+        let fetch = fetch_backups.clone();
+        let refs = [
+            filter_text_ref.clone(),
+            filter_tag_ref.clone(),
+            filter_from_ref.clone(),
+            filter_to_ref.clone(),
+            filter_min_ref.clone(),
+            filter_max_ref.clone(),
+            filter_meta_ref.clone(),
+        ];
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            for node in refs.iter() {
+                if let Some(input) = node.cast::<web_sys::HtmlInputElement>() {
+                    input.set_value("");
+                }
+            }
+            *query.borrow_mut() = BackupQuery::default();
+            fetch();
         })
     };
 
@@ -139,8 +751,7 @@ pub fn backups() -> Html {
         Callback::from(move |(id): (i32)| {
             spawn_local(async move {
                 let id = id.clone();
-                let args = serde_wasm_bindgen::to_value(&json!({ "id": id })).unwrap();
-                match invoke("open_backup",args).await{
+                match invoke(&OpenBackup { id }).await{
                     Ok(r) => {
                         console::log_1(&format!("打开存档成功" ).into());
                     },
@@ -157,37 +768,129 @@ pub fn backups() -> Html {
         let modal_state = modal_state.clone();
         let fetch = fetch_backups.clone();
         let finished = finished.clone();
+        let notes_input_ref = notes_input_ref.clone();
+        let tags_input_ref = tags_input_ref.clone();
+        let restore_scope = restore_scope.clone();
+        let scope_extra_ref = scope_extra_ref.clone();
+        let passphrase_input_ref = passphrase_input_ref.clone();
+        let remove_tags_input_ref = remove_tags_input_ref.clone();
+        let run_import = run_import.clone();
+        let show_batch_result = show_batch_result.clone();
 
         Callback::from(move |_| {
             let fetch = fetch.clone();
             let current_action = (*modal_state).clone();
             let finished = finished.clone();
+            let notes = notes_input_ref
+                .cast::<web_sys::HtmlTextAreaElement>()
+                .map(|input| input.value())
+                .unwrap_or_default();
+            let tags = parse_tags(&input_value(&tags_input_ref));
+            let remove_tags = parse_tags(&input_value(&remove_tags_input_ref));
+            let scope = merge_scope(&restore_scope, &input_value(&scope_extra_ref));
+            let passphrase = Some(input_value(&passphrase_input_ref)).filter(|p| !p.is_empty());
 
+            let modal = modal_state.clone();
+            let run_import = run_import.clone();
+            let show_batch_result = show_batch_result.clone();
             spawn_local(async move {
+                // mod 不一致的提示已经确认过，强制加载
+                let force = match &current_action {
+                    ModalAction::ModMismatch(..) => true,
+                    ModalAction::LoadPassphrase(_, _, force, _) => *force,
+                    _ => false,
+                };
+                // 后续的弹窗里没有范围输入框，沿用之前选择的范围
+                let scope = match &current_action {
+                    ModalAction::ModMismatch(_, _, _, scope) | ModalAction::LoadPassphrase(_, _, _, scope) => scope.clone(),
+                    _ => scope,
+                };
                 match current_action {
-                    ModalAction::ConfirmRestore(id, _) => {
-                        let args = serde_wasm_bindgen::to_value(&json!({ "backupId": id })).unwrap();
+                    ModalAction::ConfirmRestore(id, name)
+                    | ModalAction::ModMismatch(id, name, _, _)
+                    | ModalAction::LoadPassphrase(id, name, _, _) => {
+                        let args = LoadBackup {
+                            backup_id: id,
+                            force: Some(force),
+                            scope: Some(scope.clone()),
+                            passphrase,
+                        };
                         console::log_1(&format!("准备调用 load_backup，参数: backupId={}", id).into());
                         finished.set(false);
-                        match invoke("load_backup", args).await {
-                            Ok(result) => {
-                                console::log_1(&format!("加载存档成功：{:?}", result).into());
+                        match invoke(&args).await {
+                            Ok(LoadOutcome::Loaded { message, summary }) => {
+                                console::log_1(&format!("加载存档成功：{}，改动：{:?}", message, summary).into());
                                 fetch();
-                            },
+                            }
+                            Ok(LoadOutcome::ModMismatch { diff }) => {
+                                modal.set(ModalAction::ModMismatch(id, name, diff, scope));
+                            }
+                            Ok(LoadOutcome::PassphraseRequired) => {
+                                modal.set(ModalAction::LoadPassphrase(id, name, force, scope));
+                            }
                             Err(e) => {
                                 console::log_1(&format!("加载存档失败：{:?}", e).into());
+                                // 口令错误之类的问题需要让用户知道
+                                if e.is_string() {
+                                    modal.set(ModalAction::ShowError(i18n::error(&e)));
+                                }
+                            }
+                        }
+                        finished.set(true);
+                    },
+                    ModalAction::ImportPassphrase(path) => {
+                        run_import.emit((Some(path), passphrase));
+                    },
+                    ModalAction::Export(id, _) => {
+                        finished.set(false);
+                        match invoke(&ExportBackup { backup_id: id, passphrase }).await {
+                            Ok(path) => {
+                                if let Some(path) = path {
+                                    console::log_1(&format!("已导出到：{}", path).into());
+                                }
+                            }
+                            Err(e) => {
+                                let err_msg = i18n::error(&e);
+                                modal.set(ModalAction::ShowError(err_msg));
                             }
                         }
                         finished.set(true);
                     },
-                    ModalAction::ConfirmDelete(id, _) => {
-                        let args = serde_wasm_bindgen::to_value(&json!({ "id": id })).unwrap();
-                        let _ = invoke("delete_backup", args).await;
+                    ModalAction::ConfirmDelete(id, _, pinned) => {
+                        // 置顶的备份在弹窗里已经额外确认过
+                        let _ = invoke(&DeleteBackup { id, force: Some(pinned) }).await;
                         console::log_1(&format!("删除存档：{}", id).into());
                         fetch(); // 删除后必须刷新列表
                     },
-                    ModalAction::ShowError(_) => {
-                        // 错误弹窗只需要关闭
+                    ModalAction::EditInfo(id, _, _) => {
+                        if let Err(e) = invoke(&SetBackupNotes { id, notes: Some(notes) }).await {
+                            console::log_1(&format!("保存备注失败：{:?}", e).into());
+                        }
+                        if let Err(e) = invoke(&SetBackupTags { id, tags }).await {
+                            console::log_1(&format!("保存标签失败：{:?}", e).into());
+                        }
+                        fetch();
+                    },
+                    ModalAction::BatchDelete(ids, pinned) => {
+                        // 有置顶的备份时弹窗里已经额外提示过
+                        finished.set(false);
+                        show_batch_result.emit(invoke(&BatchDeleteBackups { ids, force: pinned > 0 }).await);
+                        finished.set(true);
+                    },
+                    ModalAction::BatchTag(ids) => {
+                        show_batch_result.emit(invoke(&BatchTagBackups { ids, add: tags, remove: remove_tags }).await);
+                    },
+                    ModalAction::BatchExport(ids) => {
+                        finished.set(false);
+                        match invoke(&BatchExportBackups { ids, passphrase }).await {
+                            Ok(Some(result)) => show_batch_result.emit(Ok(result)),
+                            Ok(None) => {} // 取消了选择文件夹
+                            Err(e) => show_batch_result.emit(Err(e)),
+                        }
+                        finished.set(true);
+                    },
+                    ModalAction::BatchSummary(_) | ModalAction::ShowError(_) => {
+                        // 提示弹窗只需要关闭
                     },
                     ModalAction::None => {}
                 }
@@ -201,6 +904,158 @@ pub fn backups() -> Html {
         Callback::from(move |_| modal_state.set(ModalAction::None))
     };
 
+    // 单个备份卡片
+    let render_card = {
+        let trigger_open = trigger_open.clone();
+        let trigger_restore = trigger_restore.clone();
+        let trigger_delete = trigger_delete.clone();
+        let trigger_edit = trigger_edit.clone();
+        let trigger_pin = trigger_pin.clone();
+        let trigger_export = trigger_export.clone();
+        let toggle_select = toggle_select.clone();
+        let selected = selected.clone();
+        move |backup: &Backup| -> Html {
+            let id = backup.id;
+            let is_selected = selected.contains(&id);
+            let on_select = toggle_select.clone();
+            let name = backup.name.clone().unwrap_or_else(|| t("backups.unnamed"));
+            let name_for_restore = name.clone();
+            let name_for_delete = name.clone();
+            let name_for_export = name.clone();
+
+            let size_mb = (backup.size as f64) / (1024.0 * 1024.0);
+            let digest = &backup.digest[..8];
+            let fmt = match i18n::current() {
+                Locale::ZhCn => format_description!("[year]年[month]月[day]日 [hour]:[minute]"),
+                Locale::En => format_description!("[year]-[month]-[day] [hour]:[minute]"),
+            };
+
+            let time_str = backup.save_time
+                .format(&fmt)
+                .unwrap_or_else(|_| "Unknown".into());
+
+            let on_open = trigger_open.clone();
+            let on_restore = trigger_restore.clone();
+            let on_delete = trigger_delete.clone();
+            let on_edit = trigger_edit.clone();
+            let on_pin = trigger_pin.clone();
+            let on_export = trigger_export.clone();
+            let pinned = backup.pinned;
+            let notes = backup.notes.clone().unwrap_or_default();
+            let tags_str = backup.tags.join(", ");
+            let meta = backup.more_info.clone().unwrap_or_default();
+            // 旧备份没有记录群系，按位置推断
+            let biome = meta.biome.clone().or_else(|| meta.location.and_then(biome_at));
+
+            html! {
+                <div class={classes!("backup-card", pinned.then_some("pinned"), is_selected.then_some("selected"))}>
+                    <input
+                        class="card-select"
+                        type="checkbox"
+                        checked={is_selected}
+                        title={t("backups.select")}
+                        onchange={Callback::from(move |_| on_select.emit(id))}
+                    />
+                    <Thumbnail id={id} />
+                    // 左侧信息
+                    <div class="card-info">
+                        <h4>
+                            if pinned { <span class="pin-mark" title={t("backups.pinned")}>{"📌 "}</span> }
+                            { &name }
+                        </h4>
+                        <div class="card-meta">
+                            <span>{ "📅 " }{ &time_str }</span>
+                            <span>{ "💿 " }{ format!("{:.2} MB", size_mb) }</span>
+                            <span>{format!("digest: {}", digest)}</span>
+                        </div>
+                        <div class="card-meta">
+                            // 不再按局分组，用种子标出属于哪一局
+                            if let Some(seed) = meta.seed {
+                                <span>{ tf("backups.run_seed", &[("seed", seed.to_string())]) }</span>
+                            }
+                            if let (Some(hp), Some(max_hp)) = (meta.hp, meta.max_hp) {
+                                <span>{ format!("❤ {:.0}/{:.0}", hp, max_hp) }</span>
+                            }
+                            if let Some(gold) = meta.gold {
+                                <span>{ format!("💰 {}", gold) }</span>
+                            }
+                            if let Some(orbs) = meta.orbs {
+                                <span>{ format!("🔮 {}", orbs) }</span>
+                            }
+                            if let Some(biome) = biome {
+                                <span>{ format!("🗺 {}", biome) }</span>
+                            }
+                            if let Some(ng_plus) = meta.ng_plus.filter(|n| *n > 0) {
+                                <span class="ng-badge">{ format!("NG+{}", ng_plus) }</span>
+                            }
+                            if let Some(mods) = meta.mods.as_ref().filter(|mods| !mods.is_empty()) {
+                                <span class="mod-badge" title={mods.join("\n")}>{ format!("🧩 {} mods", mods.len()) }</span>
+                            }
+                            { for backup.tags.iter().map(|tag| html! { <span class="tag-chip">{ tag }</span> }) }
+                        </div>
+                        if !notes.is_empty() {
+                            <p class="card-notes">{ &notes }</p>
+                        }
+                    </div>
+
+                    // 右侧操作按钮
+                    <div class="card-actions">
+                        <button
+                            class="btn btn-open"
+                            onclick={Callback::from(move |_| on_open.emit((id)))}
+                        >
+                        {"Open"}
+                        </button>
+                        <button
+                            class="btn btn-secondary"
+                            onclick={Callback::from(move |_| on_pin.emit((id, pinned)))}
+                            title={if pinned { t("backups.unpin_title") } else { t("backups.pin_title") }}
+                        >
+                            { if pinned { "Unpin" } else { "Pin" } }
+                        </button>
+                        <button
+                            class="btn btn-secondary"
+                            onclick={Callback::from(move |_| on_edit.emit((id, notes.clone(), tags_str.clone())))}
+                            title={t("backups.edit_title")}
+                        >
+                            {"Edit"}
+                        </button>
+                        <button
+                            class="btn btn-secondary"
+                            onclick={Callback::from(move |_| on_export.emit((id, name_for_export.clone())))}
+                            title={t("backups.export_title")}
+                        >
+                            {"Export"}
+                        </button>
+                        <button
+                            class="btn btn-restore"
+                            onclick={Callback::from(move |_| on_restore.emit((id, name_for_restore.clone())))}
+                        >
+                            {"Load"}
+                        </button>
+                        <button
+                            class="btn btn-delete"
+                            onclick={Callback::from(move |_| on_delete.emit((id, name_for_delete.clone(), pinned)))}
+                            title={t("backups.delete_title")}
+                        >
+                            {"Delete"}
+                        </button>
+                    </div>
+                </div>
+            }
+        }
+    };
+
+    // 只渲染可见区域附近的行，上下用空白撑出滚动高度
+    let total = list.items.len();
+    let (scroll_top, view_height) = *viewport;
+    let first = ((scroll_top / ROW_HEIGHT) as usize).saturating_sub(OVERSCAN).min(total);
+    let last = (((scroll_top + view_height) / ROW_HEIGHT).ceil() as usize + OVERSCAN).min(total);
+    let (current_sort, ascending) = {
+        let paging = paging.borrow();
+        (paging.sort, paging.ascending)
+    };
+
     // --- 渲染 ---
     html! {
         <div class="backup-container">
@@ -209,81 +1064,72 @@ pub fn backups() -> Html {
                     ref={note_input_ref}
                     class="backup-note-input"
                     type="text"
-                    placeholder="存档名"
+                    placeholder={t("backups.name_placeholder")}
                 />
                 <button class="btn btn-create btn-primary" onclick={on_create_click}>
                     <span>{"Save"}</span>
                 </button>
+                <button class="btn btn-secondary" onclick={on_import_click} title={t("backups.import_title")}>
+                    {"Import"}
+                </button>
+            </div>
+
+            // 筛选栏
+            <div class="backup-filter">
+                <input ref={filter_text_ref} class="filter-input filter-text" type="text" placeholder={t("backups.filter_text")} />
+                <input ref={filter_tag_ref} class="filter-input" type="text" placeholder={t("backups.filter_tag")} list="backup-tags" />
+                <datalist id="backup-tags">
+                    { for all_tags.iter().map(|tag| html! { <option value={tag.clone()} /> }) }
+                </datalist>
+                <input ref={filter_from_ref} class="filter-input" type="date" title={t("backups.filter_from")} />
+                <input ref={filter_to_ref} class="filter-input" type="date" title={t("backups.filter_to")} />
+                <input ref={filter_min_ref} class="filter-input filter-size" type="number" min="0" placeholder="≥ MB" />
+                <input ref={filter_max_ref} class="filter-input filter-size" type="number" min="0" placeholder="≤ MB" />
+                <input ref={filter_meta_ref} class="filter-input filter-text" type="text" placeholder="orbs>=5, gold>1000" title={t("backups.filter_meta")} />
+                <button class="btn btn-secondary" onclick={on_search}>{ t("backups.filter") }</button>
+                <button class="btn btn-secondary" onclick={on_clear_search}>{ t("backups.clear") }</button>
+                <select class="filter-input" onchange={on_sort_change} title={t("backups.sort")}>
+                    { for SORTS.iter().map(|(sort, key)| html! {
+                        <option selected={*sort == current_sort}>{ t(key) }</option>
+                    }) }
+                </select>
+                <button class="btn btn-secondary" onclick={on_toggle_order} title={t("backups.sort_order")}>
+                    { if ascending { "↑" } else { "↓" } }
+                </button>
             </div>
 
 
+            // 多选后的批量操作
+            if !selected.is_empty() {
+                <div class="batch-bar">
+                    <span class="batch-count">{ tf("backups.selected_count", &[("count", selected.len().to_string())]) }</span>
+                    <button class="btn btn-secondary" onclick={on_select_all}>{ t("backups.select_all") }</button>
+                    <button class="btn btn-secondary" onclick={on_clear_selection}>{ t("backups.clear_selection") }</button>
+                    <button class="btn btn-secondary" onclick={let on_pin = on_batch_pin.clone(); Callback::from(move |_| on_pin.emit(true))}>{"Pin"}</button>
+                    <button class="btn btn-secondary" onclick={let on_pin = on_batch_pin.clone(); Callback::from(move |_| on_pin.emit(false))}>{"Unpin"}</button>
+                    <button class="btn btn-secondary" onclick={on_batch_tag}>{ t("backups.batch_tag") }</button>
+                    <button class="btn btn-secondary" onclick={on_batch_export}>{"Export"}</button>
+                    <button class="btn btn-delete" onclick={on_batch_delete}>{"Delete"}</button>
+                </div>
+            }
+
             // 备份列表区域
-            <div class="backup-list-container mt-4">
-                if backups_list.is_empty() {
+            <div class="backup-list-container mt-4" ref={list_ref} onscroll={on_scroll}>
+                if total == 0 {
                      <div class="backup-card">
                         // 左侧信息
                         <div class="card-info">
-                            <h4>{ "暂无备份记录" }</h4>
+                            <h4>{ t("backups.empty") }</h4>
                             <div class="card-meta">
                             </div>
                         </div>
                     </div>
                 } else {
-                    { for backups_list.iter().map(|backup| {
-                        let id = backup.id;
-                        let name = backup.name.clone().unwrap_or_else(|| "未命名备份".to_string());
-                        let name_for_restore = name.clone();
-                        let name_for_delete = name.clone();
-
-                        let size_mb = (backup.size as f64) / (1024.0 * 1024.0);
-                        let digest = &backup.digest[..8];
-                        let fmt = format_description!("[year]年[month]月[day]日 [hour]:[minute]");
-
-                        let time_str = backup.save_time
-                            .format(&fmt)
-                            .unwrap_or_else(|_| "Unknown".into());
-
-                        let on_open = trigger_open.clone();
-                        let on_restore = trigger_restore.clone();
-                        let on_delete = trigger_delete.clone();
-
-                        html! {
-                            <div class="backup-card">
-                                // 左侧信息
-                                <div class="card-info">
-                                    <h4>{ &name }</h4>
-                                    <div class="card-meta">
-                                        <span>{ "📅 " }{ &time_str }</span>
-                                        <span>{ "💿 " }{ format!("{:.2} MB", size_mb) }</span>
-                                        <span>{format!("digest: {}", digest)}</span>
-                                    </div>
-                                </div>
-
-                                // 右侧操作按钮
-                                <div class="card-actions">
-                                    <button
-                                        class="btn btn-open"
-                                        onclick={Callback::from(move |_| on_open.emit((id)))}
-                                    >
-                                    {"Open"}
-                                    </button>
-                                    <button
-                                        class="btn btn-restore"
-                                        onclick={Callback::from(move |_| on_restore.emit((id, name_for_restore.clone())))}
-                                    >
-                                        {"Load"}
-                                    </button>
-                                    <button
-                                        class="btn btn-delete"
-                                        onclick={Callback::from(move |_| on_delete.emit((id, name_for_delete.clone())))}
-                                        title="删除此备份"
-                                    >
-                                        {"Delete"}
-                                    </button>
-                                </div>
-                            </div>
-                        }
-                    })}
+                    <div style={format!("height: {}px", first as f64 * ROW_HEIGHT)} />
+                    { for list.items[first..last].iter().map(|backup| html! {
+                        <div class="backup-row" key={backup.id}>{ render_card(backup) }</div>
+                    }) }
+                    <div style={format!("height: {}px", (total - last) as f64 * ROW_HEIGHT)} />
                 }
             </div>
 
@@ -294,31 +1140,161 @@ pub fn backups() -> Html {
                         <div class="modal-header">
                             <h3 class="modal-title">
                                 {match &*modal_state {
-                                    ModalAction::ConfirmRestore(_, _) => "确认还原存档？",
-                                    ModalAction::ConfirmDelete(_, _) => "确认删除备份？",
-                                    ModalAction::ShowError(_) => "提示",
-                                    _ => ""
+                                    ModalAction::ConfirmRestore(_, _) => t("backups.modal_restore"),
+                                    ModalAction::ConfirmDelete(_, _, _) => t("backups.modal_delete"),
+                                    ModalAction::EditInfo(_, _, _) => t("backups.modal_edit"),
+                                    ModalAction::ModMismatch(..) => t("backups.modal_mods"),
+                                    ModalAction::LoadPassphrase(..) | ModalAction::ImportPassphrase(_) => t("backups.modal_passphrase"),
+                                    ModalAction::Export(..) | ModalAction::BatchExport(_) => t("backups.modal_export"),
+                                    ModalAction::BatchDelete(..) => t("backups.modal_delete"),
+                                    ModalAction::BatchTag(_) => t("backups.modal_batch_tag"),
+                                    ModalAction::BatchSummary(_) => t("backups.modal_batch_done"),
+                                    ModalAction::ShowError(_) => t("backups.modal_notice"),
+                                    _ => String::new()
                                 }}
                             </h3>
                         </div>
                         <div class="modal-body py-4 text-slate-300">
                             {match &*modal_state {
-                                ModalAction::ConfirmRestore(_, name) => format!("确定要回退到 [{}] 吗？\n当前的游戏进度将会被覆盖且无法找回！", name),
-                                ModalAction::ConfirmDelete(_, name) => format!("确定要永久删除 [{}] 吗？此操作无法撤销。", name),
-                                ModalAction::ShowError(msg) => msg.clone(),
-                                _ => "".to_string()
+                                ModalAction::ConfirmRestore(_, name) => {
+                                    let full = restore_scope.iter().any(|s| s == "full");
+                                    html! {
+                                        <>
+                                            <p>{ tf("backups.restore_confirm", &[("name", name.clone())]) }</p>
+                                            <div class="restore-scope">
+                                                { for RESTORE_SCOPES.iter().map(|value| {
+                                                    let on_change = on_scope_change.clone();
+                                                    let label = t(&format!("restore.scope_{}", value));
+                                                    let value = value.to_string();
+                                                    html! {
+                                                        <label class="scope-option">
+                                                            <input
+                                                                type="checkbox"
+                                                                checked={restore_scope.contains(&value)}
+                                                                disabled={full && value != "full"}
+                                                                onchange={Callback::from(move |_| on_change.emit(Some(value.clone())))}
+                                                            />
+                                                            { label }
+                                                        </label>
+                                                    }
+                                                }) }
+                                                <input
+                                                    ref={scope_extra_ref.clone()}
+                                                    class="backup-note-input"
+                                                    type="text"
+                                                    placeholder={t("backups.scope_extra")}
+                                                    disabled={full}
+                                                    onchange={let on_change = on_scope_change.clone(); Callback::from(move |_| on_change.emit(None))}
+                                                />
+                                            </div>
+                                            <div class="restore-preview">
+                                                {match &*restore_preview {
+                                                    None => html! { <p>{ t("backups.comparing") }</p> },
+                                                    Some(summary) if summary.is_empty() => html! { <p>{ t("backups.no_changes") }</p> },
+                                                    Some(summary) => html! {
+                                                        <ul>
+                                                            { for summary.iter().map(|c| html! {
+                                                                <li>{ tf("backups.conflict", &[
+                                                                    ("subtree", c.subtree.clone()),
+                                                                    ("overwritten", c.overwritten.to_string()),
+                                                                    ("deleted", c.deleted.to_string()),
+                                                                    ("added", c.added.to_string()),
+                                                                ]) }</li>
+                                                            }) }
+                                                        </ul>
+                                                    },
+                                                }}
+                                            </div>
+                                        </>
+                                    }
+                                },
+                                ModalAction::ConfirmDelete(_, name, false) => html! { tf("backups.delete_confirm", &[("name", name.clone())]) },
+                                ModalAction::ConfirmDelete(_, name, true) => html! { tf("backups.delete_pinned_confirm", &[("name", name.clone())]) },
+                                ModalAction::EditInfo(_, notes, tags) => html! {
+                                    <div class="edit-info">
+                                        <textarea ref={notes_input_ref.clone()} class="backup-note-input" rows="3" placeholder={t("backups.notes")} value={notes.clone()} />
+                                        <input ref={tags_input_ref.clone()} class="backup-note-input" type="text" placeholder={t("backups.tags")} list="backup-tags" value={tags.clone()} />
+                                    </div>
+                                },
+                                ModalAction::ModMismatch(_, name, diff, _) => html! {
+                                    <>
+                                        <p>{ tf("backups.mods_differ", &[("name", name.clone())]) }</p>
+                                        { render_mod_diff(diff) }
+                                        <p>{ t("backups.load_anyway") }</p>
+                                    </>
+                                },
+                                ModalAction::LoadPassphrase(_, name, _, _) => html! {
+                                    <div class="edit-info">
+                                        <p>{ tf("backups.passphrase_prompt", &[("name", name.clone())]) }</p>
+                                        <input ref={passphrase_input_ref.clone()} class="backup-note-input" type="password" placeholder={t("common.passphrase")} />
+                                    </div>
+                                },
+                                ModalAction::ImportPassphrase(path) => html! {
+                                    <div class="edit-info">
+                                        <p>{ tf("backups.import_passphrase_prompt", &[("path", path.clone())]) }</p>
+                                        <input ref={passphrase_input_ref.clone()} class="backup-note-input" type="password" placeholder={t("common.passphrase")} />
+                                    </div>
+                                },
+                                ModalAction::Export(_, name) => html! {
+                                    <div class="edit-info">
+                                        <p>{ tf("backups.export_prompt", &[("name", name.clone())]) }</p>
+                                        <input ref={passphrase_input_ref.clone()} class="backup-note-input" type="password" placeholder={t("backups.passphrase_optional")} />
+                                    </div>
+                                },
+                                ModalAction::BatchDelete(ids, pinned) => html! {
+                                    <>
+                                        <p>{ tf("backups.batch_delete_confirm", &[("count", ids.len().to_string())]) }</p>
+                                        if *pinned > 0 {
+                                            <p>{ tf("backups.batch_delete_pinned", &[("count", pinned.to_string())]) }</p>
+                                        }
+                                    </>
+                                },
+                                ModalAction::BatchTag(ids) => html! {
+                                    <div class="edit-info">
+                                        <p>{ tf("backups.batch_tag_prompt", &[("count", ids.len().to_string())]) }</p>
+                                        <input ref={tags_input_ref.clone()} class="backup-note-input" type="text" placeholder={t("backups.batch_tag_add")} list="backup-tags" />
+                                        <input ref={remove_tags_input_ref.clone()} class="backup-note-input" type="text" placeholder={t("backups.batch_tag_remove")} list="backup-tags" />
+                                    </div>
+                                },
+                                ModalAction::BatchExport(ids) => html! {
+                                    <div class="edit-info">
+                                        <p>{ tf("backups.batch_export_prompt", &[("count", ids.len().to_string())]) }</p>
+                                        <input ref={passphrase_input_ref.clone()} class="backup-note-input" type="password" placeholder={t("backups.passphrase_optional")} />
+                                    </div>
+                                },
+                                ModalAction::BatchSummary(result) => html! {
+                                    <>
+                                        <p>{ tf("backups.batch_summary", &[
+                                            ("succeeded", result.succeeded.len().to_string()),
+                                            ("failed", result.failed.len().to_string()),
+                                        ]) }</p>
+                                        if !result.failed.is_empty() {
+                                            <ul class="batch-failures">
+                                                { for result.failed.iter().map(|failure| {
+                                                    let name = list.items.iter()
+                                                        .find(|b| b.id == failure.id)
+                                                        .and_then(|b| b.name.clone())
+                                                        .unwrap_or_else(|| format!("#{}", failure.id));
+                                                    html! { <li>{ format!("{}: {}", name, i18n::message(&failure.error)) }</li> }
+                                                }) }
+                                            </ul>
+                                        }
+                                    </>
+                                },
+                                ModalAction::ShowError(msg) => html! { msg.clone() },
+                                _ => html! {}
                             }}
                         </div>
                         <div class="modal-footer">
                             {
                                 match &*modal_state {
-                                    ModalAction::ShowError(_) => html! {
-                                        <button class="btn btn-primary" onclick={on_modal_cancel}>{"确定"}</button>
+                                    ModalAction::BatchSummary(_) | ModalAction::ShowError(_) => html! {
+                                        <button class="btn btn-primary" onclick={on_modal_cancel}>{ t("common.confirm") }</button>
                                     },
                                     _ => html! {
                                         <>
-                                            <button class="btn btn-secondary" onclick={on_modal_cancel}>{"取消"}</button>
-                                            <button class="btn btn-primary" onclick={on_modal_confirm}>{"确定"}</button>
+                                            <button class="btn btn-secondary" onclick={on_modal_cancel}>{ t("common.cancel") }</button>
+                                            <button class="btn btn-primary" onclick={on_modal_confirm}>{ t("common.confirm") }</button>
                                         </>
                                     }
                                 }
@@ -333,14 +1309,14 @@ pub fn backups() -> Html {
                 <div class="modal-dialog">
                     <div class="modal-header">
                         <h3 class="modal-title">
-                            {"处理中"}
+                            { t("backups.busy_title") }
                         </h3>
                     </div>
 
                     // modal-body 复用样式
                     <div class="modal-body py-4 flex flex-col items-center justify-center">
                         <div class="modal-content">
-                            {"正在执行操作，若存档较大可能需要数分钟，请稍候..."}
+                            { t("backups.busy") }
                         </div>
                     </div>
                 </div>
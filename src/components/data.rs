@@ -1,29 +1,19 @@
-use serde::{Deserialize, Serialize};
-use wasm_bindgen::prelude::*;
+use svld_shared::commands::{GetDataPath, MoveDataPath, SelectDataPath, VerifyDataValidation};
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 use web_sys::console;
+use crate::i18n::t;
+use crate::invoke::invoke;
 
 #[derive(Properties, PartialEq)]
 pub struct PathProps {
     pub on_valid_change: Callback<bool>,
 }
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], catch)]
-    async fn invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
-}
-
-#[derive(Serialize, Deserialize)]
-struct SavePathArgs {
-    path: String,
-}
-
 #[function_component(Data)]
 pub fn path() -> Html {
     // 默认显示的提示文本
-    let current_path = use_state(|| "正在检测备份路径...".to_string());
+    let current_path = use_state(|| t("path.detecting_data"));
     let is_valid = use_state(|| false);
 
     // 初始化检测逻辑
@@ -33,30 +23,25 @@ pub fn path() -> Html {
 
         use_effect_with((), move |_| {
             spawn_local(async move {
-                let response = invoke("get_data_path", JsValue::NULL).await;
+                let response = invoke(&GetDataPath).await;
                 match response {
-                    Ok(value) => {
-                        if let Some(path) = value.as_string() {
-                            current_path.set(path);
+                    Ok(path) => {
+                        current_path.set(path);
 
-                            // Try to verify the path
-                            match invoke("verify_data_validation", JsValue::NULL).await {
-                                Ok(_) => {
-                                    console::log_1(&"验证成功".into());
-                                    is_valid.set(true);
-                                }
-                                Err(e) => {
-                                    console::log_1(&format!("验证失败：{:?}", e).into());
-                                    is_valid.set(false);
-                                }
+                        // Try to verify the path
+                        match invoke(&VerifyDataValidation).await {
+                            Ok(_) => {
+                                console::log_1(&"验证成功".into());
+                                is_valid.set(true);
+                            }
+                            Err(e) => {
+                                console::log_1(&format!("验证失败：{:?}", e).into());
+                                is_valid.set(false);
                             }
-                        } else {
-                            current_path.set("未设置路径".to_string());
-                            is_valid.set(false);
                         }
                     }
                     Err(_) => {
-                        current_path.set("未设置路径".to_string());
+                        current_path.set(t("path.unset"));
                         is_valid.set(false);
                     }
                 }
@@ -75,31 +60,32 @@ pub fn path() -> Html {
             let is_valid = is_valid.clone();
             spawn_local(async move {
                 // 调用 Tauri 的选择文件夹弹窗
-                let response = invoke("select_save_path", JsValue::NULL).await;
+                let response = invoke(&SelectDataPath).await;
                 match response {
-                    Ok(value) => {
-                        if let Some(path) = value.as_string() {
-                            // 更新 UI 显示
-                            current_path.set(path.clone());
+                    Ok(Some(path)) => {
+                        // 迁移已有备份并保存新路径
+                        if let Err(e) = invoke(&MoveDataPath { new_path: path.clone() }).await {
+                            console::log_1(&format!("迁移备份目录失败：{:?}", e).into());
+                            is_valid.set(false);
+                            return;
+                        }
 
-                            // 保存到后端环境
-                            let args = serde_wasm_bindgen::to_value(&SavePathArgs { path: path.clone() }).unwrap();
-                            let _ = invoke("save_data_path", args).await;
+                        // 更新 UI 显示
+                        current_path.set(path);
 
-                            // 验证有效性
-                            match invoke("verify_data_validation", JsValue::NULL).await {
-                                Ok(_) => {
-                                    console::log_1(&"验证成功".into());
-                                    is_valid.set(true);
-                                }
-                                Err(e) => {
-                                    console::log_1(&format!("验证失败：{:?}", e).into());
-                                    is_valid.set(false);
-                                }
+                        // 验证有效性
+                        match invoke(&VerifyDataValidation).await {
+                            Ok(_) => {
+                                console::log_1(&"验证成功".into());
+                                is_valid.set(true);
+                            }
+                            Err(e) => {
+                                console::log_1(&format!("验证失败：{:?}", e).into());
+                                is_valid.set(false);
                             }
                         }
                     }
-                    Err(_) => return, // 用户取消了选择
+                    _ => (), // 用户取消了选择
                 }
             })
         })
@@ -110,12 +96,12 @@ pub fn path() -> Html {
          <div class="path-card">
             // 标题行：左边是标签，右边是状态
             <div class="path-header">
-                <span class="path-label">{"备份将保存到"}</span>
+                <span class="path-label">{ t("path.data_label") }</span>
                 {
                     if *is_valid {
-                        html! { <span class="badge badge-success">{"● 此路径可写"}</span> }
+                        html! { <span class="badge badge-success">{ t("path.data_valid") }</span> }
                     } else {
-                        html! { <span class="badge badge-error">{"● 路径不可用"}</span> }
+                        html! { <span class="badge badge-error">{ t("path.data_invalid") }</span> }
                     }
                 }
             </div>
@@ -126,14 +112,14 @@ pub fn path() -> Html {
                     { &*current_path }
                 </div>
                 <button onclick={on_select_folder} class="btn btn-secondary btn-browse">
-                    {"📁 更改..."}
+                    { t("path.change") }
                 </button>
             </div>
 
             // 错误提示行：仅在无效时显示
             if !*is_valid {
                 <div class="path-help-text">
-                    {"路径验证失败，可能由于权限不足，请尝试更换"}
+                    { t("path.data_help") }
                 </div>
             }
          </div>
@@ -0,0 +1,88 @@
+use svld_shared::backup::{DiskSpace, DiskSpaceConfig};
+use svld_shared::commands::{GetDiskSpace, SaveDiskSpaceConfig};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use crate::i18n::{self, t, tf};
+use crate::invoke::invoke;
+use crate::pages::index::format_size;
+
+#[function_component(DiskSpaceSetting)]
+pub fn disk_space() -> Html {
+    let space = use_state(|| None::<DiskSpace>);
+    let message = use_state(|| None::<(bool, String)>);
+
+    let load = {
+        let space = space.clone();
+        move || {
+            let space = space.clone();
+            spawn_local(async move {
+                if let Ok(value) = invoke(&GetDiskSpace).await {
+                    space.set(Some(value));
+                }
+            });
+        }
+    };
+
+    {
+        let load = load.clone();
+        use_effect_with((), move |_| {
+            load();
+            || {}
+        });
+    }
+
+    // 修改后立即保存，重新读取以更新提醒状态
+    let on_change = {
+        let message = message.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Ok(warn_below_mb) = input.value().trim().parse::<u64>() else {
+                message.set(Some((false, t("space.invalid"))));
+                return;
+            };
+            let message = message.clone();
+            let load = load.clone();
+            spawn_local(async move {
+                let config = DiskSpaceConfig { warn_below_mb };
+                match invoke(&SaveDiskSpaceConfig { config }).await {
+                    Ok(_) => {
+                        message.set(Some((true, t("space.saved"))));
+                        load();
+                    }
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+            });
+        })
+    };
+
+    let Some(space) = &*space else {
+        return html! {};
+    };
+
+    html! {
+        <div class="path-card disk-space-card">
+            <div class="path-header">
+                <span class="path-label">{ t("space.title") }</span>
+            </div>
+            <p class={if space.low { "disk-space-free disk-space-low" } else { "disk-space-free" }}>
+                { match space.available {
+                    Some(available) => tf("space.free", &[("size", format_size(available))]),
+                    None => t("space.unknown"),
+                } }
+            </p>
+            <label class="disk-space-row">
+                <span>{ t("space.warn_below") }</span>
+                <input
+                    type="number"
+                    min="0"
+                    value={space.config.warn_below_mb.to_string()}
+                    onchange={on_change}
+                />
+            </label>
+            if let Some((ok, text)) = &*message {
+                <div class={if *ok { "disk-space-message" } else { "disk-space-message disk-space-error" }}>{ text }</div>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,191 @@
+use svld_shared::backup::EncryptionStatus;
+use svld_shared::commands::{
+    DisableEncryption, EnableEncryption, GetEncryptionStatus, LockEncryption, UnlockEncryption,
+};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use crate::i18n::{self, t, tf};
+use crate::invoke::invoke;
+
+/// 需要口令的操作
+#[derive(Clone, Copy, PartialEq)]
+enum Action {
+    Enable,
+    Unlock,
+    Disable,
+}
+
+impl Action {
+    /// 启用和关闭时返回处理的备份数量
+    async fn run(self, passphrase: String) -> Result<Option<usize>, JsValue> {
+        match self {
+            Action::Enable => invoke(&EnableEncryption { passphrase }).await.map(Some),
+            Action::Unlock => invoke(&UnlockEncryption { passphrase }).await.map(|_| None),
+            Action::Disable => invoke(&DisableEncryption { passphrase }).await.map(Some),
+        }
+    }
+}
+
+#[function_component(Encryption)]
+pub fn encryption() -> Html {
+    let status = use_state(EncryptionStatus::default);
+    let passphrase = use_state(String::new);
+    let confirm = use_state(String::new);
+    let message = use_state(|| None::<(bool, String)>);
+    let busy = use_state(|| false);
+
+    let refresh = {
+        let status = status.clone();
+        move || {
+            let status = status.clone();
+            spawn_local(async move {
+                if let Ok(value) = invoke(&GetEncryptionStatus).await {
+                    status.set(value);
+                }
+            });
+        }
+    };
+
+    {
+        let refresh = refresh.clone();
+        use_effect_with((), move |_| {
+            refresh();
+            || {}
+        });
+    }
+
+    // 调用一个需要口令的命令，成功后清空输入框并刷新状态
+    let run = {
+        let passphrase = passphrase.clone();
+        let confirm = confirm.clone();
+        let message = message.clone();
+        let busy = busy.clone();
+        let refresh = refresh.clone();
+        move |action: Action, success: &'static str| {
+            let passphrase = passphrase.clone();
+            let confirm = confirm.clone();
+            let message = message.clone();
+            let busy = busy.clone();
+            let refresh = refresh.clone();
+            Callback::from(move |_: MouseEvent| {
+                let passphrase = passphrase.clone();
+                let confirm = confirm.clone();
+                let message = message.clone();
+                let busy = busy.clone();
+                let refresh = refresh.clone();
+                if action == Action::Enable && *passphrase != *confirm {
+                    message.set(Some((false, t("encryption.confirm_mismatch"))));
+                    return;
+                }
+                busy.set(true);
+                spawn_local(async move {
+                    match action.run((*passphrase).clone()).await {
+                        Ok(count) => {
+                            let text = match count {
+                                Some(count) => tf(
+                                    "encryption.processed",
+                                    &[("message", t(success)), ("count", count.to_string())],
+                                ),
+                                None => t(success),
+                            };
+                            message.set(Some((true, text)));
+                            passphrase.set(String::new());
+                            confirm.set(String::new());
+                        }
+                        Err(e) => message.set(Some((false, i18n::error(&e)))),
+                    }
+                    busy.set(false);
+                    refresh();
+                });
+            })
+        }
+    };
+
+    let on_lock = {
+        let message = message.clone();
+        let refresh = refresh.clone();
+        Callback::from(move |_: MouseEvent| {
+            let message = message.clone();
+            let refresh = refresh.clone();
+            spawn_local(async move {
+                let _ = invoke(&LockEncryption).await;
+                message.set(Some((true, t("encryption.locked_now"))));
+                refresh();
+            });
+        })
+    };
+
+    let on_input = |state: UseStateHandle<String>| {
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            state.set(input.value());
+        })
+    };
+
+    let badge = match (status.enabled, status.unlocked) {
+        (false, _) => html! { <span class="badge badge-error">{ t("common.off") }</span> },
+        (true, false) => html! { <span class="badge badge-error">{ t("encryption.badge_locked") }</span> },
+        (true, true) => html! { <span class="badge badge-success">{ t("encryption.badge_unlocked") }</span> },
+    };
+
+    html! {
+        <div class="path-card encryption-card">
+            <div class="path-header">
+                <span class="path-label">{ t("encryption.title") }</span>
+                { badge }
+            </div>
+            <p class="encryption-help">
+                { t("encryption.help") }
+            </p>
+
+            <div class="encryption-inputs">
+                <input
+                    type="password"
+                    placeholder={t("common.passphrase")}
+                    value={(*passphrase).clone()}
+                    oninput={on_input(passphrase.clone())}
+                />
+                if !status.enabled {
+                    <input
+                        type="password"
+                        placeholder={t("encryption.confirm_placeholder")}
+                        value={(*confirm).clone()}
+                        oninput={on_input(confirm.clone())}
+                    />
+                }
+            </div>
+
+            <div class="encryption-actions">
+                if !status.enabled {
+                    <button class="btn btn-secondary" disabled={*busy} onclick={run(Action::Enable, "encryption.enabled_now")}>
+                        { t("encryption.enable") }
+                    </button>
+                } else {
+                    if status.unlocked {
+                        <button class="btn btn-secondary" onclick={on_lock}>{ t("encryption.lock") }</button>
+                    } else {
+                        <button class="btn btn-secondary" disabled={*busy} onclick={run(Action::Unlock, "encryption.unlocked_now")}>
+                            { t("encryption.unlock") }
+                        </button>
+                    }
+                    <button class="btn btn-secondary" disabled={*busy} onclick={run(Action::Disable, "encryption.disabled_now")}>
+                        { t("encryption.disable") }
+                    </button>
+                }
+                if !status.enabled && status.has_passphrase {
+                    <button class="btn btn-secondary" disabled={*busy} onclick={run(Action::Unlock, "encryption.unlocked_now")}>
+                        { t("encryption.unlock_old") }
+                    </button>
+                }
+            </div>
+
+            if *busy {
+                <div class="encryption-message">{ t("encryption.busy") }</div>
+            } else if let Some((ok, text)) = &*message {
+                <div class={if *ok { "encryption-message" } else { "encryption-message encryption-error" }}>{ text }</div>
+            }
+        </div>
+    }
+}
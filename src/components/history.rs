@@ -0,0 +1,193 @@
+use svld_shared::backup::{BackupGraph, BackupNode, LoadOutcome, ModDiff};
+use svld_shared::commands::{GetBackupGraph, LoadBackup};
+use svld_shared::events::{BackupCreated, BackupDeleted, BackupsChanged, RestoreFinished};
+use time::macros::format_description;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::console;
+use yew::prelude::*;
+use crate::i18n::{self, t};
+use crate::components::backups::render_mod_diff;
+use crate::invoke::invoke;
+use crate::event::use_event;
+
+fn children(graph: &BackupGraph, id: i32) -> Vec<&BackupNode> {
+    graph.nodes.iter().filter(|n| n.parent_id == Some(id)).collect()
+}
+
+/// 没有父节点（或父节点已被删除）的节点
+fn roots(graph: &BackupGraph) -> Vec<&BackupNode> {
+    graph
+        .nodes
+        .iter()
+        .filter(|n| match n.parent_id {
+            Some(parent) => !graph.nodes.iter().any(|p| p.id == parent),
+            None => true,
+        })
+        .collect()
+}
+
+fn render_node(graph: &BackupGraph, node: &BackupNode, pending: Option<i32>, on_load: &Callback<i32>) -> Html {
+    let fmt = format_description!("[year]-[month]-[day] [hour]:[minute]");
+    let time_str = node.save_time.format(&fmt).unwrap_or_else(|_| "Unknown".into());
+    let name = node.name.clone().unwrap_or_else(|| t("backups.unnamed"));
+    let is_head = graph.head == Some(node.id);
+    let children = children(graph, node.id);
+    let id = node.id;
+    let on_click = {
+        let on_load = on_load.clone();
+        Callback::from(move |_: MouseEvent| on_load.emit(id))
+    };
+
+    html! {
+        <li>
+            <div class={classes!("history-node", is_head.then_some("current"), (children.len() > 1).then_some("branch"))}>
+                if node.pinned { <span>{"📌 "}</span> }
+                <span class="history-name">{ name }</span>
+                <span class="history-time">{ time_str }</span>
+                if is_head {
+                    <span class="history-head">{ t("history.current") }</span>
+                }
+                <button class="history-load" onclick={on_click}>
+                    { if pending == Some(id) { t("history.confirm_go") } else { t("history.go") } }
+                </button>
+            </div>
+            if !children.is_empty() {
+                <ul>
+                    { for children.iter().map(|child| render_node(graph, child, pending, on_load)) }
+                </ul>
+            }
+        </li>
+    }
+}
+
+#[function_component(History)]
+pub fn history() -> Html {
+    let graph = use_state(BackupGraph::default);
+    // 点击一次进入待确认状态，再点一次才真正还原
+    let pending = use_state(|| None::<i32>);
+    // mod 不一致时等待再次确认
+    let mismatch = use_state(|| None::<(i32, ModDiff)>);
+    // 加载失败等需要提示的信息
+    let notice = use_state(|| None::<String>);
+
+    let fetch_graph = {
+        let graph = graph.clone();
+        move || {
+            let graph = graph.clone();
+            spawn_local(async move {
+                match invoke(&GetBackupGraph).await {
+                    Ok(data) => graph.set(data),
+                    Err(e) => console::log_1(&format!("获取备份历史失败: {:?}", e).into()),
+                }
+            });
+        }
+    };
+
+    {
+        let fetch = fetch_graph.clone();
+        use_effect_with((), move |_| {
+            fetch();
+            || {}
+        });
+    }
+
+    // 备份增删和还原都会改变历史图，收到事件后重新获取
+    {
+        let fetch = fetch_graph.clone();
+        use_event::<BackupCreated, _>(move |_| fetch());
+    }
+    {
+        let fetch = fetch_graph.clone();
+        use_event::<BackupDeleted, _>(move |_| fetch());
+    }
+    {
+        let fetch = fetch_graph.clone();
+        use_event::<BackupsChanged, _>(move |_| fetch());
+    }
+    {
+        let fetch = fetch_graph.clone();
+        use_event::<RestoreFinished, _>(move |_| fetch());
+    }
+
+    let load = {
+        let mismatch = mismatch.clone();
+        let notice = notice.clone();
+        let fetch = fetch_graph.clone();
+        move |id: i32, force: bool| {
+            let mismatch = mismatch.clone();
+            let notice = notice.clone();
+            let fetch = fetch.clone();
+            notice.set(None);
+            spawn_local(async move {
+                let args = LoadBackup { backup_id: id, force: Some(force), ..Default::default() };
+                match invoke(&args).await {
+                    Ok(LoadOutcome::Loaded { .. }) => {
+                        console::log_1(&format!("已回到备份 {}", id).into());
+                        mismatch.set(None);
+                    }
+                    Ok(LoadOutcome::ModMismatch { diff }) => mismatch.set(Some((id, diff))),
+                    Ok(LoadOutcome::PassphraseRequired) => {
+                        mismatch.set(None);
+                        notice.set(Some(t("history.encrypted")));
+                    }
+                    Err(e) => {
+                        console::log_1(&format!("加载存档失败：{:?}", e).into());
+                        notice.set(Some(i18n::error(&e)));
+                    }
+                }
+                fetch();
+            });
+        }
+    };
+
+    let on_load = {
+        let pending = pending.clone();
+        let load = load.clone();
+        Callback::from(move |id: i32| {
+            if *pending != Some(id) {
+                pending.set(Some(id));
+                return;
+            }
+            pending.set(None);
+            load(id, false);
+        })
+    };
+
+    let on_force_load = {
+        let mismatch = mismatch.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some((id, _)) = &*mismatch {
+                load(*id, true);
+            }
+        })
+    };
+
+    let on_cancel_load = {
+        let mismatch = mismatch.clone();
+        Callback::from(move |_: MouseEvent| mismatch.set(None))
+    };
+
+    html! {
+        <div class="history-card">
+            <h3 class="history-title">{ t("history.title") }</h3>
+            if let Some(text) = &*notice {
+                <div class="history-warning"><p>{ text }</p></div>
+            }
+            if let Some((_, diff)) = &*mismatch {
+                <div class="history-warning">
+                    <p>{ t("history.mod_mismatch") }</p>
+                    { render_mod_diff(diff) }
+                    <button class="history-load" onclick={on_cancel_load}>{ t("common.cancel") }</button>
+                    <button class="history-load" onclick={on_force_load}>{ t("history.load_anyway") }</button>
+                </div>
+            }
+            if graph.nodes.is_empty() {
+                <p class="history-empty">{ t("backups.empty") }</p>
+            } else {
+                <ul class="history-tree">
+                    { for roots(&graph).iter().map(|node| render_node(&graph, node, *pending, &on_load)) }
+                </ul>
+            }
+        </div>
+    }
+}
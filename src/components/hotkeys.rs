@@ -0,0 +1,126 @@
+use svld_shared::commands::{GetHotkeys, SaveHotkeys};
+use svld_shared::settings::HotkeyConfig;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use crate::i18n::{self, t};
+use crate::invoke::invoke;
+
+/// 把按键事件转成快捷键字符串，例如 CommandOrControl+Shift+F5，只按了修饰键时返回 None
+fn accelerator(e: &KeyboardEvent) -> Option<String> {
+    let code = e.code();
+    if code.starts_with("Control") || code.starts_with("Shift") || code.starts_with("Alt") || code.starts_with("Meta") {
+        return None;
+    }
+    let mut parts = Vec::new();
+    if e.ctrl_key() || e.meta_key() {
+        parts.push("CommandOrControl".to_string());
+    }
+    if e.alt_key() {
+        parts.push("Alt".to_string());
+    }
+    if e.shift_key() {
+        parts.push("Shift".to_string());
+    }
+    parts.push(code);
+    Some(parts.join("+"))
+}
+
+#[function_component(Hotkeys)]
+pub fn hotkeys() -> Html {
+    let config = use_state(HotkeyConfig::default);
+    let message = use_state(|| None::<(bool, String)>);
+
+    {
+        let config = config.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                if let Ok(value) = invoke(&GetHotkeys).await {
+                    config.set(value);
+                }
+            });
+            || {}
+        });
+    }
+
+    // 在输入框里按下组合键即可录入，Backspace 清空
+    let on_keydown = |field: fn(&mut HotkeyConfig) -> &mut Option<String>| {
+        let config = config.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() == "Tab" {
+                return;
+            }
+            e.prevent_default();
+            let mut next = (*config).clone();
+            if e.key() == "Backspace" || e.key() == "Delete" {
+                *field(&mut next) = None;
+            } else if let Some(accelerator) = accelerator(&e) {
+                *field(&mut next) = Some(accelerator);
+            } else {
+                return;
+            }
+            config.set(next);
+        })
+    };
+
+    let on_allow_change = {
+        let config = config.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut next = (*config).clone();
+            next.allow_load_while_running = input.checked();
+            config.set(next);
+        })
+    };
+
+    let on_save = {
+        let config = config.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let config = (*config).clone();
+            let message = message.clone();
+            spawn_local(async move {
+                match invoke(&SaveHotkeys { config }).await {
+                    Ok(_) => message.set(Some((true, t("hotkeys.applied")))),
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+            });
+        })
+    };
+
+    let row = |label: String, value: &Option<String>, onkeydown: Callback<KeyboardEvent>| {
+        html! {
+            <label class="hotkey-row">
+                <span>{ label }</span>
+                <input
+                    type="text"
+                    readonly=true
+                    placeholder={t("hotkeys.placeholder")}
+                    value={value.clone().unwrap_or_default()}
+                    {onkeydown}
+                />
+            </label>
+        }
+    };
+
+    html! {
+        <div class="path-card hotkey-card">
+            <div class="path-header">
+                <span class="path-label">{ t("hotkeys.title") }</span>
+            </div>
+            { row(t("hotkeys.quick_save"), &config.quick_save, on_keydown(|c| &mut c.quick_save)) }
+            { row(t("hotkeys.restore_latest"), &config.restore_latest, on_keydown(|c| &mut c.restore_latest)) }
+            { row(t("hotkeys.restore_previous"), &config.restore_previous, on_keydown(|c| &mut c.restore_previous)) }
+            <label class="hotkey-check">
+                <input type="checkbox" checked={config.allow_load_while_running} onchange={on_allow_change}/>
+                <span>{ t("hotkeys.allow_while_running") }</span>
+            </label>
+            <div class="hotkey-actions">
+                <button class="btn btn-secondary" onclick={on_save}>{ t("common.save") }</button>
+                if let Some((ok, text)) = &*message {
+                    <span class={if *ok { "hotkey-message" } else { "hotkey-message hotkey-error" }}>{ text }</span>
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,53 @@
+use svld_shared::commands::SetLanguage;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use crate::i18n::{self, t, Locale};
+use crate::invoke::invoke;
+
+#[function_component(Language)]
+pub fn language() -> Html {
+    let message = use_state(|| None::<String>);
+    let current = i18n::current();
+
+    let on_change = {
+        let message = message.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let locale = Locale::from_code(&select.value());
+            if locale == current {
+                return;
+            }
+            let message = message.clone();
+            spawn_local(async move {
+                let language = locale.code().to_string();
+                match invoke(&SetLanguage { language }).await {
+                    // 文字在渲染时取的，重新加载页面最简单
+                    Ok(_) => {
+                        if let Some(window) = web_sys::window() {
+                            let _ = window.location().reload();
+                        }
+                    }
+                    Err(e) => message.set(Some(i18n::error(&e))),
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="path-card language-card">
+            <div class="path-header">
+                <span class="path-label">{ t("language.title") }</span>
+            </div>
+            <p class="language-help">{ t("language.help") }</p>
+            <select class="language-select" onchange={on_change}>
+                { for Locale::ALL.iter().map(|locale| html! {
+                    <option value={locale.code()} selected={*locale == current}>{ locale.name() }</option>
+                }) }
+            </select>
+            if let Some(text) = &*message {
+                <div class="language-message language-error">{ text }</div>
+            }
+        </div>
+    }
+}
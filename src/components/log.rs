@@ -1,35 +1,267 @@
-use wasm_bindgen::JsValue;
-use wasm_bindgen::prelude::wasm_bindgen;
-use yew::{function_component, html, Callback, Html};
+use std::cell::Cell;
+use std::rc::Rc;
+use svld_shared::commands::{ExportSanitizedLog, ReadLogs, StartLogTail, StopLogTail};
+use svld_shared::events::LogTail;
+use svld_shared::logs::{LogEntry, LogFilter, LogPage};
+use time::OffsetDateTime;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
 use wasm_bindgen_futures::spawn_local;
+use crate::components::backups::parse_local_date;
+use crate::event;
+use crate::i18n::{self, t, tf};
+use crate::invoke::invoke;
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], catch)]
-    async fn invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+const PAGE_SIZE: usize = 200;
+/// 实时跟踪时最多保留的条数，超出后丢掉最旧的
+const MAX_ENTRIES: usize = 2000;
+const LEVELS: [&str; 4] = ["error", "warn", "info", "debug"];
+
+/// 已加载的日志，总是最新的连续一段
+#[derive(Debug, Default, PartialEq)]
+struct LogList {
+    entries: Vec<LogEntry>,
+    total: usize,
+    /// 新日志到达时是否滚动到底部，加载更早的日志时不滚动
+    follow: bool,
+}
+
+enum LogAction {
+    Replace(LogPage),
+    Older(LogPage),
+    Tail(Vec<LogEntry>),
+}
+
+impl Reducible for LogList {
+    type Action = LogAction;
+
+    fn reduce(self: Rc<Self>, action: LogAction) -> Rc<Self> {
+        match action {
+            LogAction::Replace(page) => Rc::new(LogList { entries: page.entries, total: page.total, follow: true }),
+            LogAction::Older(page) => {
+                let mut entries = page.entries;
+                entries.extend(self.entries.iter().cloned());
+                Rc::new(LogList { entries, total: page.total, follow: false })
+            }
+            LogAction::Tail(new) => {
+                let mut entries = self.entries.clone();
+                let total = self.total + new.len();
+                entries.extend(new);
+                if entries.len() > MAX_ENTRIES {
+                    entries.drain(..entries.len() - MAX_ENTRIES);
+                }
+                Rc::new(LogList { entries, total, follow: true })
+            }
+        }
+    }
+}
+
+fn stop_tail(generation: u64) {
+    spawn_local(async move {
+        let _ = invoke(&StopLogTail { generation }).await;
+    });
+}
+
+/// <input type="datetime-local"> 的值转换成带本地时区的时间
+fn parse_local_datetime(value: &str) -> Option<OffsetDateTime> {
+    let (date, time) = value.split_once('T')?;
+    parse_local_date(date, &format!("{}:00", time))
 }
 
 #[function_component(Log)]
 pub fn log() -> Html {
-    let on_open_log = Callback::from(|_| {
-        spawn_local(async {
-            invoke("open_log", JsValue::NULL).await;
+    let list = use_reducer(LogList::default);
+    let filter = use_state(LogFilter::default);
+    let live = use_state(|| true);
+    let message = use_state(|| None::<(bool, String)>);
+    let level_ref = use_node_ref();
+    let search_ref = use_node_ref();
+    let from_ref = use_node_ref();
+    let to_ref = use_node_ref();
+    let list_ref = use_node_ref();
+
+    // 筛选条件变化时重新读取第一页
+    {
+        let list = list.clone();
+        let message = message.clone();
+        use_effect_with((*filter).clone(), move |filter| {
+            let args = ReadLogs { filter: Some(filter.clone()), offset: Some(0), limit: Some(PAGE_SIZE) };
+            spawn_local(async move {
+                match invoke(&args).await {
+                    Ok(page) => list.dispatch(LogAction::Replace(page)),
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+            });
+            || {}
         });
-    });
+    }
+
+    // 实时跟踪，条件变化或关闭时停止之前的跟踪
+    {
+        let list = list.clone();
+        use_effect_with((*live, (*filter).clone()), move |(live, filter)| {
+            // 后端返回的跟踪编号，停止时只停掉自己开始的那一次
+            let generation = Rc::new(Cell::new(None::<u64>));
+            let cancelled = Rc::new(Cell::new(false));
+            let subscription = live.then(|| {
+                let args = StartLogTail { filter: Some(filter.clone()) };
+                let generation = generation.clone();
+                let cancelled = cancelled.clone();
+                spawn_local(async move {
+                    let Ok(value) = invoke(&args).await else {
+                        return;
+                    };
+                    // 开始之前组件已经卸载或条件已经变了
+                    if cancelled.get() {
+                        stop_tail(value);
+                    } else {
+                        generation.set(Some(value));
+                    }
+                });
+                event::subscribe::<LogTail, _>(move |LogTail(entries)| list.dispatch(LogAction::Tail(entries)))
+            });
+            move || {
+                drop(subscription);
+                cancelled.set(true);
+                if let Some(generation) = generation.get() {
+                    stop_tail(generation);
+                }
+            }
+        });
+    }
+
+    // 有新日志时滚动到底部
+    {
+        let list_ref = list_ref.clone();
+        let follow = list.follow;
+        use_effect_with(list.entries.len(), move |_| {
+            if follow {
+                if let Some(element) = list_ref.cast::<web_sys::Element>() {
+                    element.set_scroll_top(element.scroll_height());
+                }
+            }
+            || {}
+        });
+    }
+
+    let on_apply = {
+        let filter = filter.clone();
+        let level_ref = level_ref.clone();
+        let search_ref = search_ref.clone();
+        let from_ref = from_ref.clone();
+        let to_ref = to_ref.clone();
+        Callback::from(move |_: MouseEvent| {
+            let text = |node: &NodeRef| {
+                node.cast::<HtmlInputElement>()
+                    .map(|input| input.value().trim().to_string())
+                    .filter(|value| !value.is_empty())
+            };
+            filter.set(LogFilter {
+                level: level_ref.cast::<HtmlSelectElement>().map(|s| s.value()).filter(|v| !v.is_empty()),
+                from: text(&from_ref).and_then(|v| parse_local_datetime(&v)),
+                to: text(&to_ref).and_then(|v| parse_local_datetime(&v)),
+                search: text(&search_ref),
+            });
+        })
+    };
+
+    let on_load_older = {
+        let list = list.clone();
+        let filter = filter.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let list = list.clone();
+            let message = message.clone();
+            let args = ReadLogs {
+                filter: Some((*filter).clone()),
+                offset: Some(list.entries.len()),
+                limit: Some(PAGE_SIZE),
+            };
+            spawn_local(async move {
+                match invoke(&args).await {
+                    Ok(page) => list.dispatch(LogAction::Older(page)),
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+            });
+        })
+    };
+
+    let on_live = {
+        let live = live.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            live.set(input.checked());
+        })
+    };
+
+    // 导出的日志已替换用户名等信息，可以直接附在反馈里
+    let on_export_log = {
+        let message = message.clone();
+        Callback::from(move |_| {
+            let message = message.clone();
+            spawn_local(async move {
+                match invoke(&ExportSanitizedLog).await {
+                    Ok(path) => {
+                        if let Some(path) = path {
+                            message.set(Some((true, tf("log.exported", &[("path", path)]))));
+                        }
+                    }
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+            });
+        })
+    };
 
     html! {
-        <div class="settings-group">
-            <div class="setting-card">
-                <div class="setting-text">
-                    <span class="label">{"运行日志"}</span>
-                    <p class="description">{"反馈bug时请提供日志"}</p>
-                </div>
-
-                <button class="btn-log" onclick={on_open_log}>
-                    <span class="btn-emoji">{"📂"}</span>
-                    {"打开日志"}
+        <div class="path-card log-card">
+            <div class="path-header">
+                <span class="path-label">{ t("log.title") }</span>
+                <button class="btn-log" onclick={on_export_log}>
+                    <span class="btn-emoji">{"🛡"}</span>
+                    { t("log.export") }
                 </button>
             </div>
+            <p class="description">{ t("log.description") }</p>
+            if let Some((ok, text)) = &*message {
+                <p class={if *ok { "log-message" } else { "log-message log-error" }}>{ text }</p>
+            }
+
+            <div class="log-filter">
+                <select ref={level_ref} class="filter-input">
+                    <option value="">{ t("log.level_all") }</option>
+                    { for LEVELS.iter().map(|level| html! {
+                        <option value={*level}>{ level.to_uppercase() }</option>
+                    }) }
+                </select>
+                <input ref={search_ref} class="filter-input filter-text" type="text" placeholder={t("log.search")} />
+                <input ref={from_ref} class="filter-input" type="datetime-local" title={t("log.from")} />
+                <input ref={to_ref} class="filter-input" type="datetime-local" title={t("log.to")} />
+                <button class="btn btn-secondary" onclick={on_apply}>{ t("log.apply") }</button>
+                <label class="log-live">
+                    <input type="checkbox" checked={*live} onchange={on_live} />
+                    <span>{ t("log.live") }</span>
+                </label>
+            </div>
+
+            <div class="log-list" ref={list_ref}>
+                if list.entries.len() < list.total {
+                    <button class="log-older" onclick={on_load_older}>{ t("log.load_older") }</button>
+                }
+                if list.entries.is_empty() {
+                    <p class="log-empty">{ t("log.empty") }</p>
+                }
+                { for list.entries.iter().map(|entry| html! {
+                    <div class={classes!("log-entry", format!("level-{}", entry.level.to_lowercase()))}>
+                        <span class="log-time">{ &entry.time }</span>
+                        <span class="log-level">{ &entry.level }</span>
+                        <span class="log-target">{ &entry.target }</span>
+                        <span class="log-text">{ &entry.message }</span>
+                    </div>
+                }) }
+            </div>
+            <div class="log-count">
+                { tf("log.count", &[("shown", list.entries.len().to_string()), ("total", list.total.to_string())]) }
+            </div>
         </div>
     }
-}
\ No newline at end of file
+}
@@ -4,6 +4,15 @@ pub mod sideBar;
 pub mod data;
 pub mod log;
 pub mod version;
+pub mod history;
+pub mod remote;
+pub mod encryption;
+pub mod hotkeys;
+pub mod auto_backup;
+pub mod notifications;
+pub mod language;
+pub mod settings_file;
+pub mod disk_space;
 
 // 重导出组件
 pub use path::Path;
@@ -11,4 +20,13 @@ pub use backups::*;
 pub use sideBar::*;
 pub use data::*;
 pub use log::*;
-pub use version::*;
\ No newline at end of file
+pub use version::*;
+pub use history::*;
+pub use remote::*;
+pub use encryption::*;
+pub use hotkeys::*;
+pub use auto_backup::*;
+pub use notifications::*;
+pub use language::*;
+pub use settings_file::*;
+pub use disk_space::*;
\ No newline at end of file
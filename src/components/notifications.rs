@@ -0,0 +1,69 @@
+use svld_shared::commands::{GetNotifications, SaveNotifications};
+use svld_shared::settings::NotificationConfig;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use crate::i18n::{self, t};
+use crate::invoke::invoke;
+
+#[function_component(Notifications)]
+pub fn notifications() -> Html {
+    let config = use_state(NotificationConfig::default);
+    let message = use_state(|| None::<(bool, String)>);
+
+    {
+        let config = config.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                if let Ok(value) = invoke(&GetNotifications).await {
+                    config.set(value);
+                }
+            });
+            || {}
+        });
+    }
+
+    // 勾选后立即保存
+    let on_toggle = |field: fn(&mut NotificationConfig) -> &mut bool| {
+        let config = config.clone();
+        let message = message.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut next = (*config).clone();
+            *field(&mut next) = input.checked();
+            config.set(next.clone());
+            let message = message.clone();
+            spawn_local(async move {
+                match invoke(&SaveNotifications { config: next }).await {
+                    Ok(_) => message.set(None),
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+            });
+        })
+    };
+
+    let row = |label: String, checked: bool, onchange: Callback<Event>| {
+        html! {
+            <label class="notification-check">
+                <input type="checkbox" {checked} {onchange}/>
+                <span>{ label }</span>
+            </label>
+        }
+    };
+
+    html! {
+        <div class="path-card notification-card">
+            <div class="path-header">
+                <span class="path-label">{ t("notifications.title") }</span>
+            </div>
+            <p class="notification-help">{ t("notifications.help") }</p>
+            { row(t("notifications.backup_created"), config.backup_created, on_toggle(|c| &mut c.backup_created)) }
+            { row(t("notifications.duplicate_skipped"), config.duplicate_skipped, on_toggle(|c| &mut c.duplicate_skipped)) }
+            { row(t("notifications.restore_finished"), config.restore_finished, on_toggle(|c| &mut c.restore_finished)) }
+            { row(t("notifications.failure"), config.failure, on_toggle(|c| &mut c.failure)) }
+            if let Some((_, text)) = &*message {
+                <div class="notification-message notification-error">{ text }</div>
+            }
+        </div>
+    }
+}
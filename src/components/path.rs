@@ -1,29 +1,21 @@
-use serde::{Deserialize, Serialize};
-use wasm_bindgen::prelude::*;
+use svld_shared::commands::{GetSavePath, SavePathToEnv, SelectSavePath, VerifyValidation};
+use svld_shared::events::SaveDirChanged;
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 use web_sys::console;
+use crate::i18n::t;
+use crate::invoke::invoke;
+use crate::event::use_event;
 
 #[derive(Properties, PartialEq)]
 pub struct PathProps {
     pub on_valid_change: Callback<bool>,
 }
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], catch)]
-    async fn invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
-}
-
-#[derive(Serialize, Deserialize)]
-struct SavePathArgs {
-    path: String,
-}
-
 #[function_component(Path)]
 pub fn path() -> Html {
     // 默认显示的提示文本
-    let current_path = use_state(|| "正在检测存档路径...".to_string());
+    let current_path = use_state(|| t("path.detecting_save"));
     let is_valid = use_state(|| false);
 
     // 初始化检测逻辑
@@ -33,30 +25,25 @@ pub fn path() -> Html {
 
         use_effect_with((), move |_| {
             spawn_local(async move {
-                let response = invoke("get_save_path", JsValue::NULL).await;
+                let response = invoke(&GetSavePath).await;
                 match response {
-                    Ok(value) => {
-                        if let Some(path) = value.as_string() {
-                            current_path.set(path);
-                            
-                            // Try to verify the path
-                            match invoke("verify_validation", JsValue::NULL).await {
-                                Ok(_) => {
-                                    console::log_1(&"验证成功".into());
-                                    is_valid.set(true);
-                                }
-                                Err(e) => {
-                                    console::log_1(&format!("验证失败：{:?}", e).into());
-                                    is_valid.set(false);
-                                }
+                    Ok(path) => {
+                        current_path.set(path);
+
+                        // Try to verify the path
+                        match invoke(&VerifyValidation).await {
+                            Ok(_) => {
+                                console::log_1(&"验证成功".into());
+                                is_valid.set(true);
+                            }
+                            Err(e) => {
+                                console::log_1(&format!("验证失败：{:?}", e).into());
+                                is_valid.set(false);
                             }
-                        } else {
-                            current_path.set("未设置路径".to_string());
-                            is_valid.set(false);
                         }
                     }
                     Err(_) => {
-                        current_path.set("未设置路径".to_string());
+                        current_path.set(t("path.unset"));
                         is_valid.set(false);
                     }
                 }
@@ -65,6 +52,19 @@ pub fn path() -> Html {
         });
     }
 
+    // 存档目录可能在别的窗口或设置导入中被修改，收到事件后更新并重新验证
+    {
+        let current_path = current_path.clone();
+        let is_valid = is_valid.clone();
+        use_event::<SaveDirChanged, _>(move |SaveDirChanged { path }| {
+            current_path.set(path);
+            let is_valid = is_valid.clone();
+            spawn_local(async move {
+                is_valid.set(invoke(&VerifyValidation).await.is_ok());
+            });
+        });
+    }
+
     // 浏览文件夹
     let on_select_folder = {
         let current_path = current_path.clone();
@@ -75,31 +75,28 @@ pub fn path() -> Html {
             let is_valid = is_valid.clone();
             spawn_local(async move {
                 // 调用 Tauri 的选择文件夹弹窗
-                let response = invoke("select_save_path", JsValue::NULL).await;
+                let response = invoke(&SelectSavePath).await;
                 match response {
-                    Ok(value) => {
-                        if let Some(path) = value.as_string() {
-                            // 1. 更新 UI 显示
-                            current_path.set(path.clone());
+                    Ok(Some(path)) => {
+                        // 1. 更新 UI 显示
+                        current_path.set(path.clone());
 
-                            // 2. 保存到后端环境
-                            let args = serde_wasm_bindgen::to_value(&SavePathArgs { path: path.clone() }).unwrap();
-                            let _ = invoke("save_path_to_env", args).await;
+                        // 2. 保存到后端环境
+                        let _ = invoke(&SavePathToEnv { path }).await;
 
-                            // 3. 再次验证有效性
-                            match invoke("verify_validation", JsValue::NULL).await {
-                                Ok(_) => {
-                                    console::log_1(&"验证成功".into());
-                                    is_valid.set(true);
-                                }
-                                Err(e) => {
-                                    console::log_1(&format!("验证失败：{:?}", e).into());
-                                    is_valid.set(false);
-                                }
+                        // 3. 再次验证有效性
+                        match invoke(&VerifyValidation).await {
+                            Ok(_) => {
+                                console::log_1(&"验证成功".into());
+                                is_valid.set(true);
+                            }
+                            Err(e) => {
+                                console::log_1(&format!("验证失败：{:?}", e).into());
+                                is_valid.set(false);
                             }
                         }
                     }
-                    Err(_) => return, // 用户取消了选择
+                    _ => (), // 用户取消了选择
                 }
             })
         })
@@ -110,12 +107,12 @@ pub fn path() -> Html {
          <div class="path-card">
             // 标题行：左边是标签，右边是状态
             <div class="path-header">
-                <span class="path-label">{"Noita 存档位置 (save00)"}</span>
+                <span class="path-label">{ t("path.save_label") }</span>
                 {
                     if *is_valid {
-                        html! { <span class="badge badge-success">{"● 路径验证通过"}</span> }
+                        html! { <span class="badge badge-success">{ t("path.save_valid") }</span> }
                     } else {
-                        html! { <span class="badge badge-error">{"● 未找到存档所在"}</span> }
+                        html! { <span class="badge badge-error">{ t("path.save_invalid") }</span> }
                     }
                 }
             </div>
@@ -126,16 +123,16 @@ pub fn path() -> Html {
                     { &*current_path }
                 </div>
                 <button onclick={on_select_folder} class="btn btn-secondary btn-browse">
-                    {"📁 更改..."}
+                    { t("path.change") }
                 </button>
             </div>
 
             // 错误提示行：仅在无效时显示
             if !*is_valid {
                 <div class="path-help-text">
-                    {"无法在此路径下检测到存档文件。请手动选择 save00 文件夹"}
+                    { t("path.save_help") }
                     <br/>
-                    {"通常位于: C:/Users/%USERNAME%/AppData/LocalLow/Nolla_Games_Noita/save00"}
+                    { t("path.save_hint") }
                 </div>
             }
          </div>
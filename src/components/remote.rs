@@ -0,0 +1,334 @@
+use std::collections::BTreeMap;
+use svld_shared::commands::{GetRemoteConfig, SaveRemoteConfig, SyncBackups, TestRemote};
+use svld_shared::storage::{RemoteConfig, SyncReport};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use crate::i18n::{self, t, tf};
+use crate::invoke::invoke;
+
+/// (种类, 该种类的字段)，显示名的 key 是 remote.kind_<种类> 和 remote.field_<字段>
+const KINDS: &[(&str, &[&str])] = &[
+    ("local", &["path"]),
+    ("sftp", &["host", "port", "username", "root"]),
+    ("webdav", &["url", "username"]),
+    ("s3", &["endpoint", "region", "bucket", "access_key", "prefix"]),
+];
+
+fn kind_fields(kind: &str) -> &'static [&'static str] {
+    KINDS.iter().find(|(k, _)| *k == kind).map(|(_, f)| *f).unwrap_or(&[])
+}
+
+/// 表单里都是字符串，保存时再按种类转换成后端的 RemoteConfig
+#[derive(Clone, PartialEq)]
+struct RemoteForm {
+    kind: String,
+    fields: BTreeMap<String, String>,
+    path_style: bool,
+}
+
+impl Default for RemoteForm {
+    fn default() -> Self {
+        RemoteForm { kind: "local".to_string(), fields: BTreeMap::new(), path_style: true }
+    }
+}
+
+impl RemoteForm {
+    fn from_config(config: &RemoteConfig) -> Self {
+        let mut form = RemoteForm::default();
+        let fields: Vec<(&str, Option<String>)> = match config {
+            RemoteConfig::Local { path } => {
+                form.kind = "local".to_string();
+                vec![("path", Some(path.clone()))]
+            }
+            RemoteConfig::Sftp { host, port, username, root } => {
+                form.kind = "sftp".to_string();
+                vec![
+                    ("host", Some(host.clone())),
+                    ("port", port.map(|p| p.to_string())),
+                    ("username", Some(username.clone())),
+                    ("root", Some(root.clone())),
+                ]
+            }
+            RemoteConfig::Webdav { url, username } => {
+                form.kind = "webdav".to_string();
+                vec![("url", Some(url.clone())), ("username", username.clone())]
+            }
+            RemoteConfig::S3 { endpoint, region, bucket, access_key, prefix, path_style } => {
+                form.kind = "s3".to_string();
+                form.path_style = *path_style;
+                vec![
+                    ("endpoint", Some(endpoint.clone())),
+                    ("region", Some(region.clone())),
+                    ("bucket", Some(bucket.clone())),
+                    ("access_key", Some(access_key.clone())),
+                    ("prefix", prefix.clone()),
+                ]
+            }
+        };
+        for (key, value) in fields {
+            if let Some(value) = value {
+                form.fields.insert(key.to_string(), value);
+            }
+        }
+        form
+    }
+
+    fn get(&self, key: &str) -> String {
+        self.fields.get(key).map(|v| v.trim().to_string()).unwrap_or_default()
+    }
+
+    /// 空字符串对应后端的 None
+    fn optional(&self, key: &str) -> Option<String> {
+        Some(self.get(key)).filter(|value| !value.is_empty())
+    }
+
+    fn to_config(&self) -> Result<RemoteConfig, String> {
+        let config = match self.kind.as_str() {
+            "local" => RemoteConfig::Local { path: self.get("path") },
+            "sftp" => {
+                let port = match self.optional("port") {
+                    Some(port) => Some(port.parse::<u16>().map_err(|_| t("remote.port_invalid"))?),
+                    None => None,
+                };
+                RemoteConfig::Sftp {
+                    host: self.get("host"),
+                    port,
+                    username: self.get("username"),
+                    root: self.get("root"),
+                }
+            }
+            "webdav" => RemoteConfig::Webdav { url: self.get("url"), username: self.optional("username") },
+            "s3" => RemoteConfig::S3 {
+                endpoint: self.get("endpoint"),
+                region: self.get("region"),
+                bucket: self.get("bucket"),
+                access_key: self.get("access_key"),
+                prefix: self.optional("prefix"),
+                path_style: self.path_style,
+            },
+            _ => return Err(t("remote.unknown_kind")),
+        };
+        Ok(config)
+    }
+}
+
+#[function_component(Remote)]
+pub fn remote() -> Html {
+    let form = use_state(RemoteForm::default);
+    let secret = use_state(String::new);
+    let message = use_state(|| None::<(bool, String)>);
+    let report = use_state(|| None::<SyncReport>);
+    let busy = use_state(|| false);
+
+    // 读取已保存的配置
+    {
+        let form = form.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                if let Ok(Some(config)) = invoke(&GetRemoteConfig).await {
+                    form.set(RemoteForm::from_config(&config));
+                }
+            });
+            || {}
+        });
+    }
+
+    let on_kind_change = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let mut next = (*form).clone();
+            next.kind = select.value();
+            form.set(next);
+        })
+    };
+
+    let on_path_style = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut next = (*form).clone();
+            next.path_style = input.checked();
+            form.set(next);
+        })
+    };
+
+    let on_secret = {
+        let secret = secret.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            secret.set(input.value());
+        })
+    };
+
+    let on_save = {
+        let form = form.clone();
+        let secret = secret.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let config = match form.to_config() {
+                Ok(config) => config,
+                Err(e) => {
+                    message.set(Some((false, e)));
+                    return;
+                }
+            };
+            // 密码框留空时保留原来的密码
+            let secret_value = Some((*secret).clone()).filter(|s| !s.is_empty());
+            let secret = secret.clone();
+            let message = message.clone();
+            spawn_local(async move {
+                match invoke(&SaveRemoteConfig { config: Some(config), secret: secret_value }).await {
+                    Ok(_) => {
+                        secret.set(String::new());
+                        message.set(Some((true, t("remote.saved"))));
+                    }
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+            });
+        })
+    };
+
+    let on_clear = {
+        let form = form.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let form = form.clone();
+            let message = message.clone();
+            spawn_local(async move {
+                match invoke(&SaveRemoteConfig { config: None, secret: None }).await {
+                    Ok(_) => {
+                        form.set(RemoteForm::default());
+                        message.set(Some((true, t("remote.disabled"))));
+                    }
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+            });
+        })
+    };
+
+    let on_test = {
+        let message = message.clone();
+        let busy = busy.clone();
+        Callback::from(move |_: MouseEvent| {
+            let message = message.clone();
+            let busy = busy.clone();
+            busy.set(true);
+            spawn_local(async move {
+                match invoke(&TestRemote).await {
+                    Ok(count) => {
+                        message.set(Some((true, tf("remote.test_ok", &[("count", count.to_string())]))));
+                    }
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+                busy.set(false);
+            });
+        })
+    };
+
+    let on_sync = {
+        let message = message.clone();
+        let report = report.clone();
+        let busy = busy.clone();
+        Callback::from(move |_: MouseEvent| {
+            let message = message.clone();
+            let report = report.clone();
+            let busy = busy.clone();
+            busy.set(true);
+            message.set(Some((true, t("remote.syncing"))));
+            spawn_local(async move {
+                match invoke(&SyncBackups).await {
+                    Ok(result) => {
+                        message.set(Some((
+                            result.failed.is_empty(),
+                            tf(
+                                "remote.sync_done",
+                                &[
+                                    ("pushed", result.pushed.len().to_string()),
+                                    ("pulled", result.pulled.len().to_string()),
+                                    ("failed", result.failed.len().to_string()),
+                                ],
+                            ),
+                        )));
+                        report.set(Some(result));
+                    }
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+                busy.set(false);
+            });
+        })
+    };
+
+    let field_inputs = kind_fields(&form.kind).iter().map(|key| {
+        let oninput = {
+            let form = form.clone();
+            let key = key.to_string();
+            Callback::from(move |e: InputEvent| {
+                let input: HtmlInputElement = e.target_unchecked_into();
+                let mut next = (*form).clone();
+                next.fields.insert(key.clone(), input.value());
+                form.set(next);
+            })
+        };
+        html! {
+            <label class="remote-field">
+                <span>{ t(&format!("remote.field_{}", key)) }</span>
+                <input type="text" value={form.fields.get(*key).cloned().unwrap_or_default()} {oninput}/>
+            </label>
+        }
+    });
+
+    let secret_label = match form.kind.as_str() {
+        "s3" => t("remote.secret_key"),
+        "local" => String::new(),
+        _ => t("remote.password"),
+    };
+
+    html! {
+        <div class="path-card remote-card">
+            <div class="path-header">
+                <span class="path-label">{ t("remote.title") }</span>
+                <select class="remote-kind" onchange={on_kind_change}>
+                    { for KINDS.iter().map(|(kind, _)| html! {
+                        <option value={*kind} selected={form.kind == *kind}>{ t(&format!("remote.kind_{}", kind)) }</option>
+                    }) }
+                </select>
+            </div>
+
+            <div class="remote-fields">
+                { for field_inputs }
+                if form.kind == "s3" {
+                    <label class="remote-field remote-check">
+                        <input type="checkbox" checked={form.path_style} onchange={on_path_style}/>
+                        <span>{ t("remote.path_style") }</span>
+                    </label>
+                }
+                if !secret_label.is_empty() {
+                    <label class="remote-field">
+                        <span>{ secret_label }</span>
+                        <input type="password" placeholder={t("remote.secret_placeholder")} value={(*secret).clone()} oninput={on_secret}/>
+                    </label>
+                }
+            </div>
+
+            <div class="remote-actions">
+                <button class="btn btn-secondary" onclick={on_save}>{ t("remote.save") }</button>
+                <button class="btn btn-secondary" onclick={on_test} disabled={*busy}>{ t("remote.test") }</button>
+                <button class="btn btn-secondary" onclick={on_sync} disabled={*busy}>{ t("remote.sync") }</button>
+                <button class="btn btn-secondary" onclick={on_clear}>{ t("remote.close") }</button>
+            </div>
+
+            if let Some((ok, text)) = &*message {
+                <div class={if *ok { "remote-message" } else { "remote-message remote-error" }}>{ text }</div>
+            }
+            if let Some(report) = &*report {
+                if !report.failed.is_empty() {
+                    <ul class="remote-failed">
+                        { for report.failed.iter().map(|f| html! { <li>{ f }</li> }) }
+                    </ul>
+                }
+            }
+        </div>
+    }
+}
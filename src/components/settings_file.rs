@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+use svld_shared::commands::{
+    DismissSettingsProblems, ExportSettings, GetSettingsInfo, ImportSettings, PreviewSettingsImport,
+    ResetSettingsSection,
+};
+use svld_shared::settings::{ImportPreview, SettingsInfo};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+use crate::i18n::{self, t, tf};
+use crate::invoke::invoke;
+
+/// 可以恢复默认的设置项，数据路径和加密有各自的修改方式
+const RESETTABLE: [&str; 8] = [
+    "save_path", "auto_backup", "disk_space", "hotkeys", "notifications", "update", "language", "remote",
+];
+
+fn key_name(key: &str) -> String {
+    t(&format!("settings_io.key_{}", key))
+}
+
+fn value_text(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| t("settings_io.empty_value"))
+}
+
+/// 导入和恢复默认后设置页上的其他卡片还是旧值，重新加载页面最简单
+fn reload() {
+    if let Some(window) = web_sys::window() {
+        let _ = window.location().reload();
+    }
+}
+
+#[function_component(SettingsFile)]
+pub fn settings_file() -> Html {
+    let info = use_state(|| None::<SettingsInfo>);
+    let preview = use_state(|| None::<ImportPreview>);
+    let selected = use_state(HashSet::<String>::new);
+    let pending_reset = use_state(|| None::<String>);
+    let message = use_state(|| None::<(bool, String)>);
+
+    {
+        let info = info.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                if let Ok(value) = invoke(&GetSettingsInfo).await {
+                    info.set(Some(value));
+                }
+            });
+            || {}
+        });
+    }
+
+    let on_dismiss = {
+        let info = info.clone();
+        Callback::from(move |_: MouseEvent| {
+            let info = info.clone();
+            spawn_local(async move {
+                if invoke(&DismissSettingsProblems).await.is_ok() {
+                    if let Some(mut next) = (*info).clone() {
+                        next.problems.clear();
+                        info.set(Some(next));
+                    }
+                }
+            });
+        })
+    };
+
+    let on_export = {
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let message = message.clone();
+            spawn_local(async move {
+                match invoke(&ExportSettings).await {
+                    Ok(path) => {
+                        if let Some(path) = path {
+                            message.set(Some((true, tf("settings_io.exported", &[("path", path)]))));
+                        }
+                    }
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+            });
+        })
+    };
+
+    let on_import = {
+        let preview = preview.clone();
+        let selected = selected.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let preview = preview.clone();
+            let selected = selected.clone();
+            let message = message.clone();
+            spawn_local(async move {
+                match invoke(&PreviewSettingsImport).await {
+                    Ok(Some(value)) => {
+                        // 默认选中所有有变化并且能导入的项
+                        selected.set(value.items.iter().filter(|i| i.importable()).map(|i| i.key.clone()).collect());
+                        preview.set(Some(value));
+                        message.set(None);
+                    }
+                    Ok(None) => {}
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+            });
+        })
+    };
+
+    let on_toggle = |key: String| {
+        let selected = selected.clone();
+        Callback::from(move |_: Event| {
+            let mut next = (*selected).clone();
+            if !next.remove(&key) {
+                next.insert(key.clone());
+            }
+            selected.set(next);
+        })
+    };
+
+    let on_apply = {
+        let preview = preview.clone();
+        let selected = selected.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(file) = preview.as_ref().map(|p| p.file.clone()) else {
+                return;
+            };
+            let keys: Vec<String> = selected.iter().cloned().collect();
+            let message = message.clone();
+            spawn_local(async move {
+                match invoke(&ImportSettings { file, keys }).await {
+                    Ok(_) => reload(),
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+            });
+        })
+    };
+
+    let on_cancel = {
+        let preview = preview.clone();
+        Callback::from(move |_: MouseEvent| preview.set(None))
+    };
+
+    // 第一次点击只是确认，第二次才恢复
+    let on_reset = |key: &'static str| {
+        let pending_reset = pending_reset.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            if pending_reset.as_deref() != Some(key) {
+                pending_reset.set(Some(key.to_string()));
+                return;
+            }
+            pending_reset.set(None);
+            let message = message.clone();
+            spawn_local(async move {
+                match invoke(&ResetSettingsSection { key: key.to_string() }).await {
+                    Ok(_) => reload(),
+                    Err(e) => message.set(Some((false, i18n::error(&e)))),
+                }
+            });
+        })
+    };
+
+    let Some(info) = &*info else {
+        return html! {};
+    };
+
+    html! {
+        <div class="path-card settings-file-card">
+            <div class="path-header">
+                <span class="path-label">{ t("settings_file.title") }</span>
+                if info.portable {
+                    <span class="settings-file-badge">{ t("settings_file.portable") }</span>
+                }
+                <div class="settings-file-actions">
+                    <button class="btn btn-secondary" onclick={on_export}>{ t("settings_io.export") }</button>
+                    <button class="btn btn-secondary" onclick={on_import}>{ t("settings_io.import") }</button>
+                </div>
+            </div>
+            <div class="settings-file-location" title={format!("v{}", info.version)}>
+                <span>{ t("settings_file.location") }</span>
+                <code>{ &info.path }</code>
+            </div>
+            if !info.portable {
+                <p class="settings-file-help">{ t("settings_file.portable_help") }</p>
+            }
+            if !info.problems.is_empty() {
+                <div class="settings-file-problems">
+                    <p>{ t("settings_file.problems") }</p>
+                    <ul>
+                        { for info.problems.iter().map(|problem| html! { <li>{ i18n::message(problem) }</li> }) }
+                    </ul>
+                    <button class="btn btn-secondary" onclick={on_dismiss}>{ t("settings_file.dismiss") }</button>
+                </div>
+            }
+            if let Some((ok, text)) = &*message {
+                <p class={if *ok { "settings-file-message" } else { "settings-file-message settings-file-error" }}>{ text }</p>
+            }
+
+            if let Some(preview) = &*preview {
+                <div class="settings-import">
+                    <p class="settings-import-title">{ tf("settings_io.preview_title", &[("file", preview.file.clone())]) }</p>
+                    { for preview.problems.iter().map(|problem| html! {
+                        <p class="settings-import-warning">{ i18n::message(problem) }</p>
+                    }) }
+                    { for preview.items.iter().map(|item| html! {
+                        <div class="settings-import-item">
+                            <label>
+                                <input
+                                    type="checkbox"
+                                    disabled={!item.importable()}
+                                    checked={selected.contains(&item.key)}
+                                    onchange={on_toggle(item.key.clone())}
+                                />
+                                <span class="settings-import-key">{ key_name(&item.key) }</span>
+                                if item.changes.is_empty() {
+                                    <span class="settings-import-same">{ t("settings_io.unchanged") }</span>
+                                }
+                            </label>
+                            if let Some(error) = &item.error {
+                                <p class="settings-file-error">{ i18n::message(error) }</p>
+                            }
+                            if let Some(warning) = &item.warning {
+                                <p class="settings-import-warning">{ i18n::message(warning) }</p>
+                            }
+                            if let Some(manual) = &item.manual {
+                                <p class="settings-import-warning">{ i18n::message(manual) }</p>
+                            }
+                            { for item.changes.iter().map(|change| html! {
+                                <div class="settings-import-change">
+                                    if !change.field.is_empty() {
+                                        <span class="settings-import-field">{ &change.field }</span>
+                                    }
+                                    <span class="settings-import-old">{ value_text(&change.current) }</span>
+                                    <span>{"→"}</span>
+                                    <span class="settings-import-new">{ value_text(&change.imported) }</span>
+                                </div>
+                            }) }
+                        </div>
+                    }) }
+                    <p class="settings-file-help">{ t("settings_io.secret_note") }</p>
+                    <div class="settings-file-actions">
+                        <button class="btn btn-primary" disabled={selected.is_empty()} onclick={on_apply}>{ t("settings_io.apply") }</button>
+                        <button class="btn btn-secondary" onclick={on_cancel}>{ t("settings_io.cancel") }</button>
+                    </div>
+                </div>
+            }
+
+            <div class="settings-reset">
+                <span class="settings-reset-title">{ t("settings_io.reset_title") }</span>
+                { for RESETTABLE.iter().map(|key| {
+                    let pending = pending_reset.as_deref() == Some(*key);
+                    html! {
+                        <button class={classes!("settings-reset-button", pending.then_some("pending"))} onclick={on_reset(key)}>
+                            { if pending { t("settings_io.reset_confirm") } else { key_name(key) } }
+                        </button>
+                    }
+                }) }
+            </div>
+        </div>
+    }
+}
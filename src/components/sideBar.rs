@@ -1,6 +1,9 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
 use crate::router::Route;
+use crate::i18n::t;
+use crate::event;
+use svld_shared::events::ShowLog;
 use wasm_bindgen::prelude::*;
 
 #[derive(Properties, PartialEq)]
@@ -11,6 +14,14 @@ pub struct SidebarProps {
 #[function_component(SideBar)]
 pub fn side_bar(props: &SidebarProps) -> Html {
     let current_route = use_route::<Route>();
+    let navigator = use_navigator();
+
+    // 点击通知上的“查看日志”后跳到设置页的日志面板
+    event::use_event::<ShowLog, _>(move |_| {
+        if let Some(navigator) = &navigator {
+            navigator.push(&Route::Settings);
+        }
+    });
     let get_classes = |target: Route| {
         if current_route.as_ref() == Some(&target) {
             "sidebar-item active" // 激活时的样式
@@ -23,22 +34,22 @@ pub fn side_bar(props: &SidebarProps) -> Html {
         <nav class="sidebar">
             // 首页 (Index)
             <Link<Route> to={Route::Index} classes={get_classes(Route::Index)}>
-                { "首页" }
+                { t("nav.home") }
             </Link<Route>>
 
             // 备份 (Backup)
             <Link<Route> to={Route::Backup} classes={get_classes(Route::Backup)}>
-                { "存档" }
+                { t("nav.backups") }
             </Link<Route>>
 
             // 设置 (Settings)
             <Link<Route> to={Route::Settings} classes={get_classes(Route::Settings)}>
-                { "设置" }
+                { t("nav.settings") }
             </Link<Route>>
 
             // 信息 (Info)
             <Link<Route> to={Route::Info} classes={get_classes(Route::Info)}>
-                { "关于" }
+                { t("nav.about") }
             </Link<Route>>
         </nav>
     }
@@ -1,79 +1,187 @@
 use yew::prelude::*;
 use wasm_bindgen_futures::spawn_local;
-use serde::{Deserialize, Serialize};
-use wasm_bindgen::prelude::*;
+use svld_shared::commands::{CheckForUpdate, GetUpdateConfig, GetVersion, InstallUpdate, SaveUpdateConfig};
+use svld_shared::settings::{Channel, UpdateConfig, UpdateInfo, UpdateProgress, UpdateStage};
+use web_sys::HtmlSelectElement;
+use crate::event;
+use crate::i18n::{self, t, tf};
+use crate::invoke::invoke;
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
-    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
-}
+/// (渠道, 选项的值)，显示名的 key 是 version.channel_<值>
+const CHANNELS: [(Channel, &str); 2] = [(Channel::Stable, "stable"), (Channel::Beta, "beta")];
 
-#[derive(Serialize, Deserialize)]
-struct Empty {}
+fn progress_text(progress: &UpdateProgress) -> String {
+    match progress.stage {
+        UpdateStage::Waiting => t("version.waiting"),
+        UpdateStage::Installing => t("version.installing"),
+        UpdateStage::Failed => progress.error.as_deref().map(i18n::message).unwrap_or_default(),
+        UpdateStage::Downloading => {
+            let percent = match progress.total {
+                Some(total) if total > 0 => format!("{}%", progress.downloaded * 100 / total),
+                _ => format!("{:.1} MB", progress.downloaded as f64 / 1024.0 / 1024.0),
+            };
+            tf("version.downloading", &[("progress", percent)])
+        }
+    }
+}
 
 #[function_component(Version)]
 pub fn version() -> Html {
     let checking = use_state(|| false);
     let message = use_state(|| String::from(""));
-    let current_version = use_state(|| String::from("加载中..."));
+    let current_version = use_state(|| t("version.loading"));
+    let channel = use_state(Channel::default);
+    let available = use_state(|| None::<UpdateInfo>);
+    let progress = use_state(|| None::<UpdateProgress>);
 
     {
         let current_version = current_version.clone();
+        let channel = channel.clone();
         use_effect_with((), move |_| {
             spawn_local(async move {
-                match invoke("get_version", JsValue::NULL).await.as_string() {
-                    Some(v) => current_version.set(v),
-                    None => current_version.set("未知版本".to_string()),
+                match invoke(&GetVersion).await {
+                    Ok(v) => current_version.set(v),
+                    Err(_) => current_version.set(t("version.unknown")),
+                }
+                if let Ok(config) = invoke(&GetUpdateConfig).await {
+                    channel.set(config.channel);
                 }
             });
             || {}
         });
     }
 
+    // 安装在后台进行，进度通过事件发过来
+    {
+        let progress = progress.clone();
+        event::use_event::<UpdateProgress, _>(move |value| progress.set(Some(value)));
+    }
 
     let on_check_update = {
         let checking = checking.clone();
         let message = message.clone();
-        
+        let available = available.clone();
+
         Callback::from(move |_| {
             let checking = checking.clone();
             let message = message.clone();
-            
+            let available = available.clone();
+
             checking.set(true);
-            message.set(String::from("正在检查更新..."));
-            
+            message.set(String::new());
+
             spawn_local(async move {
-                let args = serde_wasm_bindgen::to_value(&Empty {}).unwrap();
-                let result = invoke("check_update", args).await;
-                
-                match serde_wasm_bindgen::from_value::<String>(result) {
-                    Ok(msg) => message.set(msg),
-                    Err(_) => message.set(String::from("检查更新失败")),
+                match invoke(&CheckForUpdate).await {
+                    Ok(Some(info)) => available.set(Some(info)),
+                    Ok(None) => {
+                        available.set(None);
+                        message.set(t("update.latest"));
+                    }
+                    Err(e) => message.set(i18n::error(&e)),
                 }
-                
+
                 checking.set(false);
             });
         })
     };
 
+    let on_install = {
+        let message = message.clone();
+        let progress = progress.clone();
+        Callback::from(move |_| {
+            let message = message.clone();
+            let progress = progress.clone();
+            spawn_local(async move {
+                if let Err(e) = invoke(&InstallUpdate).await {
+                    progress.set(None);
+                    message.set(i18n::error(&e));
+                }
+            });
+        })
+    };
+
+    let on_later = {
+        let available = available.clone();
+        Callback::from(move |_| available.set(None))
+    };
+
+    let on_channel = {
+        let channel = channel.clone();
+        let available = available.clone();
+        let message = message.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let Some((value, _)) = CHANNELS.into_iter().find(|(_, code)| *code == select.value()) else {
+                return;
+            };
+            channel.set(value);
+            // 换了渠道，之前检查到的版本不再有效
+            available.set(None);
+            let message = message.clone();
+            spawn_local(async move {
+                let config = UpdateConfig { channel: value };
+                if let Err(e) = invoke(&SaveUpdateConfig { config }).await {
+                    message.set(i18n::error(&e));
+                }
+            });
+        })
+    };
+
+    // 正在安装时不能再操作，失败后可以重试
+    let installing = progress.as_ref().is_some_and(|p| p.stage != UpdateStage::Failed);
+
     html! {
         <div class="version-card">
             <div class="version-row">
                 <div class="version-text">
-                    {"当前版本： "}
+                    { t("version.current") }
                     <span class="version-number">{ &*current_version }</span>
                 </div>
 
                 <button
                     onclick={on_check_update}
-                    disabled={*checking}
+                    disabled={*checking || installing}
                     class="update-button"
                 >
-                    { if *checking { "检查中..." } else { "检查更新" } }
+                    { if *checking { t("version.checking") } else { t("version.check") } }
                 </button>
             </div>
 
+            <label class="version-channel">
+                <span>{ t("version.channel") }</span>
+                <select onchange={on_channel} disabled={installing}>
+                    { for CHANNELS.iter().map(|(value, code)| html! {
+                        <option value={*code} selected={*channel == *value}>{ t(&format!("version.channel_{}", code)) }</option>
+                    }) }
+                </select>
+            </label>
+
+            if let Some(info) = &*available {
+                <div class="update-available">
+                    <div class="update-title">
+                        { tf("version.available", &[("version", info.version.clone())]) }
+                        <span class="update-from">{ format!("v{} → v{}", info.current_version, info.version) }</span>
+                    </div>
+                    if let Some(date) = &info.date {
+                        <div class="update-date">{ tf("version.released", &[("date", date.clone())]) }</div>
+                    }
+                    <pre class="update-notes">{ info.notes.clone().unwrap_or_else(|| t("version.no_notes")) }</pre>
+                    <div class="update-note">{ t("version.snapshot_note") }</div>
+                    if let Some(progress) = &*progress {
+                        <div class={if progress.stage == UpdateStage::Failed { "update-progress update-failed" } else { "update-progress" }}>
+                            { progress_text(progress) }
+                        </div>
+                        if let (Some(total), UpdateStage::Downloading) = (progress.total, progress.stage) {
+                            <progress max={total.to_string()} value={progress.downloaded.to_string()} />
+                        }
+                    }
+                    <div class="update-actions">
+                        <button class="update-button" onclick={on_install} disabled={installing}>{ t("version.install") }</button>
+                        <button class="update-button" onclick={on_later} disabled={installing}>{ t("version.later") }</button>
+                    </div>
+                </div>
+            }
+
             if !message.is_empty() {
                 <div class="update-message">
                     { &*message }
@@ -0,0 +1,102 @@
+//! 监听后端通过 app.emit 发来的事件，事件的类型和名字定义在 svld_shared::events 中
+use std::cell::RefCell;
+use std::rc::Rc;
+use serde::de::DeserializeOwned;
+use svld_shared::Event;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::console;
+use yew::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"], js_name = listen, catch)]
+    async fn tauri_listen(event: &str, handler: &js_sys::Function) -> Result<JsValue, JsValue>;
+}
+
+struct State {
+    /// listen 返回的取消函数，注册完成前为 None
+    unlisten: Option<js_sys::Function>,
+    closed: bool,
+    // 取消监听之前回调必须一直有效
+    _handler: Closure<dyn FnMut(JsValue)>,
+}
+
+/// 一个事件监听，drop 时取消
+pub struct Subscription {
+    state: Rc<RefCell<State>>,
+}
+
+/// 监听事件，payload 反序列化成 T 后交给 callback
+fn listen<T, F>(event: &'static str, mut callback: F) -> Subscription
+where
+    T: DeserializeOwned + 'static,
+    F: FnMut(T) + 'static,
+{
+    let handler = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+        let payload = js_sys::Reflect::get(&event, &JsValue::from_str("payload")).unwrap_or(JsValue::NULL);
+        match serde_wasm_bindgen::from_value::<T>(payload) {
+            Ok(payload) => callback(payload),
+            Err(e) => console::log_1(&format!("解析事件失败: {:?}", e).into()),
+        }
+    });
+    let function: js_sys::Function = handler.as_ref().unchecked_ref::<js_sys::Function>().clone();
+    let state = Rc::new(RefCell::new(State { unlisten: None, closed: false, _handler: handler }));
+
+    let pending = state.clone();
+    spawn_local(async move {
+        match tauri_listen(event, &function).await {
+            Ok(unlisten) => {
+                let unlisten: js_sys::Function = unlisten.unchecked_into();
+                let mut state = pending.borrow_mut();
+                // 注册完成前组件已经卸载
+                if state.closed {
+                    let _ = unlisten.call0(&JsValue::NULL);
+                } else {
+                    state.unlisten = Some(unlisten);
+                }
+            }
+            Err(e) => console::log_1(&format!("监听 {} 失败: {:?}", event, e).into()),
+        }
+    });
+    Subscription { state }
+}
+
+/// 监听事件 E，返回的 Subscription drop 时取消
+pub fn subscribe<E, F>(callback: F) -> Subscription
+where
+    E: Event + 'static,
+    F: FnMut(E) + 'static,
+{
+    listen(E::NAME, callback)
+}
+
+/// 组件挂载期间一直监听事件 E，收到时调用最近一次渲染传入的 callback
+#[hook]
+pub fn use_event<E, F>(callback: F)
+where
+    E: Event + 'static,
+    F: Fn(E) + 'static,
+{
+    let latest = use_mut_ref(|| None::<Box<dyn Fn(E)>>);
+    *latest.borrow_mut() = Some(Box::new(callback));
+    use_effect_with((), move |_| {
+        let subscription = subscribe::<E, _>(move |event| {
+            if let Some(callback) = &*latest.borrow() {
+                callback(event);
+            }
+        });
+        move || drop(subscription)
+    });
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut state = self.state.borrow_mut();
+        state.closed = true;
+        if let Some(unlisten) = state.unlisten.take() {
+            let _ = unlisten.call0(&JsValue::NULL);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! 界面文字的多语言支持，文案和翻译规则在 svld_shared::i18n 中和后端共用
+use std::cell::Cell;
+use svld_shared::commands::GetLanguage;
+use wasm_bindgen::JsValue;
+use crate::invoke::invoke;
+
+pub use svld_shared::i18n::Locale;
+
+thread_local! {
+    static CURRENT: Cell<Locale> = const { Cell::new(Locale::ZhCn) };
+}
+
+pub fn current() -> Locale {
+    CURRENT.with(|c| c.get())
+}
+
+/// 启动时从后端读取设置的语言，在渲染之前调用
+pub async fn init() {
+    if let Ok(code) = invoke(&GetLanguage).await {
+        CURRENT.with(|c| c.set(Locale::from_code(&code)));
+    }
+}
+
+/// 取一条文案，当前语言缺失时退回中文，都没有时原样返回 key
+pub fn t(key: &str) -> String {
+    current().t(key)
+}
+
+/// 带参数的文案，参数写成 {name}
+pub fn tf(key: &str, params: &[(&str, String)]) -> String {
+    current().tf(key, params)
+}
+
+/// 把后端返回的字符串翻译成当前语言，不是消息格式时原样返回
+pub fn message(text: &str) -> String {
+    current().translate(text)
+}
+
+/// invoke 失败时的错误文字
+pub fn error(e: &JsValue) -> String {
+    message(&e.as_string().unwrap_or_default())
+}
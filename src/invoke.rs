@@ -0,0 +1,26 @@
+//! 调用后端命令，命令名、参数和返回值的类型都来自 svld_shared
+use serde::Serialize;
+use svld_shared::Command;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke, catch)]
+    async fn tauri_invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+}
+
+/// 后端的命令函数都用一个 args 参数接收命令结构体
+#[derive(Serialize)]
+struct Args<'a, C> {
+    args: &'a C,
+}
+
+/// 调用命令，失败时返回后端的错误，一般是消息格式，用 i18n::error 显示
+pub async fn invoke<C: Command>(command: &C) -> Result<C::Output, JsValue> {
+    // 用普通的 JS 对象而不是 Map，后端才能解析
+    let args = Args { args: command }
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let value = tauri_invoke(C::NAME, args).await?;
+    serde_wasm_bindgen::from_value(value).map_err(|e| JsValue::from_str(&e.to_string()))
+}
@@ -1,4 +1,7 @@
 pub mod components;
 pub mod app;
 pub mod router;
-pub mod pages;
\ No newline at end of file
+pub mod pages;
+pub mod i18n;
+pub mod event;
+pub mod invoke;
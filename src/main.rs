@@ -2,6 +2,9 @@ mod app;
 mod components;
 mod router;
 mod pages;
+mod i18n;
+mod event;
+mod invoke;
 
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
@@ -12,6 +15,10 @@ use wasm_bindgen::JsValue;
 
 fn main() -> Result<()> {
     wasm_logger::init(wasm_logger::Config::default());
-    yew::Renderer::<App>::new().render();
+    // 先读取语言设置再渲染
+    spawn_local(async {
+        i18n::init().await;
+        yew::Renderer::<App>::new().render();
+    });
     Ok(())
 }
@@ -1,41 +1,28 @@
-use serde::{Deserialize, Serialize};
-use wasm_bindgen::JsValue;
-use wasm_bindgen::prelude::*;
+use svld_shared::backup::DashboardStats;
+use svld_shared::commands::{GetDashboardStats, IsGameRunning};
+use svld_shared::events::{BackupCreated, BackupDeleted, BackupsChanged, GameStatusChanged};
 use wasm_bindgen_futures::spawn_local;
 use yew::prelude::*;
 use yew_router::prelude::*;
-use crate::components::Backup;
+use crate::invoke::invoke;
+use crate::event::use_event;
 use crate::router::Route;
+use crate::i18n::t;
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "tauri"])]
-    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
-}
-
-#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
-pub struct DashboardStats {
-    backup_count: usize,
-    total_size: u64,    // 单位字节
-    is_ready: bool,     // 后端健康
-}
+pub fn format_size(bytes: u64) -> String {
+    let size = bytes as f64;
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    const GB: f64 = MB * 1024.0;
 
-impl DashboardStats {
-    pub fn formatted_size(&self) -> String {
-        let size = self.total_size as f64;
-        const KB: f64 = 1024.0;
-        const MB: f64 = KB * 1024.0;
-        const GB: f64 = MB * 1024.0;
-
-        if size < KB {
-            format!("{} B", size)
-        } else if size < MB {
-            format!("{:.1} KB", size / KB) // 保留1位小数
-        } else if size < GB {
-            format!("{:.1} MB", size / MB)
-        } else {
-            format!("{:.2} GB", size / GB) // 保留2位小数
-        }
+    if size < KB {
+        format!("{} B", size)
+    } else if size < MB {
+        format!("{:.1} KB", size / KB) // 保留1位小数
+    } else if size < GB {
+        format!("{:.1} MB", size / MB)
+    } else {
+        format!("{:.2} GB", size / GB) // 保留2位小数
     }
 }
 
@@ -45,34 +32,67 @@ pub fn home() -> Html {
         backup_count: 0,
         total_size: u64::MAX,
         is_ready: false,
+        free_space: None,
+        low_space: false,
     });
 
-    {
+    // 游戏是否在运行，None 表示还没拿到
+    let game_running = use_state(|| None::<bool>);
+
+    let fetch_stats = {
         let stats = stats.clone();
-        use_effect_with((), move |_| {
+        move || {
+            let stats = stats.clone();
             spawn_local(async move {
-                // 1. 直接获取 JsValue，不进行 Result 匹配
-                let response = invoke("get_dashboard_stats", JsValue::NULL).await;
-
-                // 2. 在反序列化 (from_value) 时进行 match
-                match serde_wasm_bindgen::from_value::<DashboardStats>(response) {
+                match invoke(&GetDashboardStats).await {
                     Ok(fetched_stats) => {
                         stats.set(fetched_stats);
                     },
                     Err(e) => {
-                        // 记录反序列化错误，比如后端没返回数据
-                        web_sys::console::log_1(&format!("无法解析统计数据: {:?}", e).into());
+                        // 后端还没准备好时保持加载中的状态
+                        web_sys::console::log_1(&format!("无法获取统计数据: {:?}", e).into());
                     }
                 }
             });
+        }
+    };
+
+    {
+        let fetch = fetch_stats.clone();
+        let game_running = game_running.clone();
+        use_effect_with((), move |_| {
+            fetch();
+            spawn_local(async move {
+                if let Ok(running) = invoke(&IsGameRunning).await {
+                    game_running.set(Some(running));
+                }
+            });
             || ()
         });
     }
 
+    // 备份数量和占用空间随备份增删变化
+    {
+        let fetch = fetch_stats.clone();
+        use_event::<BackupCreated, _>(move |_| fetch());
+    }
+    {
+        let fetch = fetch_stats.clone();
+        use_event::<BackupDeleted, _>(move |_| fetch());
+    }
+    {
+        let fetch = fetch_stats.clone();
+        use_event::<BackupsChanged, _>(move |_| fetch());
+    }
+    {
+        let game_running = game_running.clone();
+        use_event::<GameStatusChanged, _>(move |GameStatusChanged { running }| game_running.set(Some(running)));
+    }
+
     html! {
         <div class="dashboard-container">
             <div class="hero-section">
-                <h1 class="hero-title">{"Noita 存档管理器"}</h1>
+                <h1 class="hero-title">{ t("home.title") }</h1>
             </div>
 
             // 状态卡片 (Dashboard Stats)
@@ -81,14 +101,23 @@ pub fn home() -> Html {
                     <div class="stat-icon">{"📦"}</div>
                     <div class="stat-info">
                         <span class="stat-value">{stats.backup_count}</span>
-                        <span class="stat-label">{"现有存档"}</span>
+                        <span class="stat-label">{ t("home.backup_count") }</span>
                     </div>
                 </div>
                 <div class="stat-card">
                     <div class="stat-icon">{"💾"}</div>
                     <div class="stat-info">
-                        <span class="stat-value">{stats.formatted_size()}</span>
-                        <span class="stat-label">{"占用空间"}</span>
+                        <span class="stat-value">{format_size(stats.total_size)}</span>
+                        <span class="stat-label">{ t("home.total_size") }</span>
+                    </div>
+                </div>
+                <div class={classes!("stat-card", stats.low_space.then_some("stat-warning"))}>
+                    <div class="stat-icon">
+                        if stats.low_space { {"⚠️"} } else { {"🗄️"} }
+                    </div>
+                    <div class="stat-info">
+                        <span class="stat-value">{ stats.free_space.map(format_size).unwrap_or_else(|| "-".to_string()) }</span>
+                        <span class="stat-label">{ t("home.free_space") }</span>
                     </div>
                 </div>
                 <div class="stat-card">
@@ -97,20 +126,37 @@ pub fn home() -> Html {
                     </div>
                     <div class="stat-info">
                         <span class="stat-value">
-                            if stats.is_ready { {"Ready"} } else { {"Connecting..."} }
+                            if stats.is_ready { { t("home.ready") } } else { { t("home.connecting") } }
                         </span>
-                        <span class="stat-label">{"后端状态"}</span>
+                        <span class="stat-label">{ t("home.backend_status") }</span>
+                    </div>
+                </div>
+                <div class="stat-card">
+                    <div class="stat-icon">{"🎮"}</div>
+                    <div class="stat-info">
+                        <span class="stat-value">
+                            { match *game_running {
+                                Some(true) => t("home.game_running"),
+                                Some(false) => t("home.game_stopped"),
+                                None => "-".to_string(),
+                            } }
+                        </span>
+                        <span class="stat-label">{ t("home.game_status") }</span>
                     </div>
                 </div>
             </div>
 
+            if stats.low_space {
+                <div class="low-space-warning">{ t("home.low_space") }</div>
+            }
+
             // 3. 快速导航入口
             <div class="actions-grid">
                 <Link<Route> to={Route::Backup} classes="action-card action-primary">
                     <div class="action-content">
                         <span class="action-icon">{"⚡"}</span>
-                        <h3>{"管理存档"}</h3>
-                        <p>{"保存、加载存档"}</p>
+                        <h3>{ t("home.manage") }</h3>
+                        <p>{ t("home.manage_desc") }</p>
                     </div>
                     <div class="action-arrow">{"→"}</div>
                 </Link<Route>>
@@ -118,8 +164,8 @@ pub fn home() -> Html {
                 <Link<Route> to={Route::Settings} classes="action-card action-secondary">
                     <div class="action-content">
                         <span class="action-icon">{"⚙️"}</span>
-                        <h3>{"设置"}</h3>
-                        <p>{"请确保游戏存档正确，磁盘空间充足"}</p>
+                        <h3>{ t("nav.settings") }</h3>
+                        <p>{ t("home.settings_desc") }</p>
                     </div>
                     <div class="action-arrow">{"→"}</div>
                 </Link<Route>>
@@ -127,7 +173,7 @@ pub fn home() -> Html {
 
             // 4. 底部装饰或提示
             <div class="footer-tip">
-                {"💡 tips ：请向哈米斯投喂石板，它会报答你的"}
+                { t("home.tip") }
             </div>
         </div>
     }
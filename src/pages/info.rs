@@ -1,5 +1,6 @@
 use yew::prelude::*;
-use crate::components::Version;
+use crate::components::{History, Version};
+use crate::i18n::t;
 
 #[function_component(Info)]
 pub fn home() -> Html {
@@ -7,8 +8,9 @@ pub fn home() -> Html {
         <div class="dashboard-container">
             <Version />
             <br />
-            <h2 class="version-text" style="text-align: center;">{"反馈可发送至开发者邮箱：me@aucept.in"}</h2>
+            <h2 class="version-text" style="text-align: center;">{ t("info.feedback") }</h2>
             <br />
+            <History />
         </div>
     }
 }
\ No newline at end of file
@@ -1,14 +1,32 @@
 use yew::prelude::*;
+use crate::i18n::t;
 use crate::components::Path;
 use crate::components::Data;
 use crate::components::Log;
+use crate::components::Remote;
+use crate::components::Encryption;
+use crate::components::Hotkeys;
+use crate::components::AutoBackup;
+use crate::components::Notifications;
+use crate::components::Language;
+use crate::components::SettingsFile;
+use crate::components::DiskSpaceSetting;
 #[function_component(Setting)]
 pub fn home() -> Html {
     html! {
         <div class="dashboard-container">
-            <h1>{ "设置" }</h1>
+            <h1>{ t("settings.title") }</h1>
+            // 加载设置时的问题要放在最前面，让用户看到
+            <SettingsFile/>
             <Path/>
             <Data/>
+            <DiskSpaceSetting/>
+            <AutoBackup/>
+            <Notifications/>
+            <Language/>
+            <Remote/>
+            <Encryption/>
+            <Hotkeys/>
             <Log/>
         </div>
 